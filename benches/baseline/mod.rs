@@ -0,0 +1,114 @@
+use std::cmp::Ordering;
+
+use fnv::FnvHashMap;
+
+use poker_eval::hand::Card;
+use poker_eval::hand::Hand;
+use poker_eval::hand::HandRank;
+
+// Hand::cmp as it was before the rank counts moved to fixed arrays: a hash
+// map of rank frequencies, rebuilt every time a hand is classified, and each
+// hand classified again for every step of the comparison. Kept only so the
+// bench can time the old and new code in the same run.
+
+fn rank_bit(card: &Card) -> u16 {
+	return 1u16 << (card.rank.to_int() - 2);
+}
+
+fn same_kind(hand: &Hand) -> FnvHashMap<u16, u8> {
+	let mut rank_freq = FnvHashMap::with_capacity_and_hasher(5, Default::default());
+	for card in &hand.cards {
+		*rank_freq.entry(rank_bit(card)).or_insert(0) += 1;
+	}
+
+	return rank_freq;
+}
+
+fn flush(hand: &Hand) -> bool {
+	return hand.cards.iter().all(|c| c.suit == hand.cards[0].suit);
+}
+
+fn straight(hand: &Hand) -> bool {
+	let ranks = hand.cards.iter().fold(0u16, |bits, c| bits | rank_bit(c));
+	return (0..9).any(|j| ranks == 0b11111u16 << j) || ranks == 0b1000000001111u16;
+}
+
+fn freq_tuple(freq_map: &FnvHashMap<u16, u8>) -> (bool, bool, u8) {
+	let mut freq = (false, false, 0);
+	for count in freq_map.values() {
+		match count {
+			4 => freq.0 = true,
+			3 => freq.1 = true,
+			2 => freq.2 += 1,
+			_ => ()
+		}
+	}
+
+	return freq;
+}
+
+fn hand_rank(hand: &Hand) -> (HandRank, Option<FnvHashMap<u16, u8>>) {
+	if flush(hand) {
+		return (if straight(hand) { HandRank::STRAIGHT_FLUSH } else { HandRank::FLUSH }, None);
+	}
+
+	if straight(hand) {
+		return (HandRank::STRAIGHT, None);
+	}
+
+	let same_kind = same_kind(hand);
+	let freq = freq_tuple(&same_kind);
+	let rank = if freq.0 {
+		HandRank::FOUR_OF_A_KIND
+	} else if freq.1 && freq.2 == 1 {
+		HandRank::FULL_HOUSE
+	} else if freq.1 {
+		HandRank::THREE_OF_A_KIND
+	} else if freq.2 == 2 {
+		HandRank::TWO_PAIRS
+	} else if freq.2 == 1 {
+		HandRank::PAIR
+	} else {
+		HandRank::HIGH_CARD
+	};
+
+	return (rank, Some(same_kind));
+}
+
+fn compare(hand: &Hand, other: &Hand) -> Ordering {
+	let rank = hand_rank(hand).0;
+	let other_rank = hand_rank(other).0;
+	if rank != other_rank {
+		return other_rank.cmp(&rank);
+	}
+
+	if rank == HandRank::STRAIGHT_FLUSH || rank == HandRank::FLUSH || rank == HandRank::STRAIGHT || rank == HandRank::HIGH_CARD {
+		let ranks = |h: &Hand| h.cards.iter().fold(0u16, |bits, c| bits | rank_bit(c));
+		return ranks(hand).cmp(&ranks(other));
+	}
+
+	let mut combos = [(0u16, 0u16); 4];
+	for (bits, count) in hand_rank(hand).1.unwrap() {
+		combos[(4 - count) as usize].0 |= bits;
+	}
+
+	for (bits, count) in hand_rank(other).1.unwrap() {
+		combos[(4 - count) as usize].1 |= bits;
+	}
+
+	for (ours, theirs) in combos.iter() {
+		if *ours != 0 && ours != theirs {
+			return ours.cmp(theirs);
+		}
+	}
+
+	return Ordering::Equal;
+}
+
+pub fn cmp(hand: &Hand, other: &Hand) -> Ordering {
+	if hand.cards == other.cards {
+		return Ordering::Equal;
+	}
+
+	return compare(hand, other);
+}
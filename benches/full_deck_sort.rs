@@ -0,0 +1,54 @@
+// Sorts all 2,598,960 five card hands with the comparison from before the
+// fixed array rank counts, with Hand::cmp and with the table evaluator:
+//   cargo bench --bench full_deck_sort
+
+use std::time::Instant;
+
+mod baseline;
+
+use poker_eval::eval;
+use poker_eval::hand::Card;
+use poker_eval::hand::Hand;
+use poker_eval::hand::Rank;
+use poker_eval::hand::Suit;
+
+fn full_deck_hands() -> Vec<Hand> {
+	let mut deck = Vec::new();
+	for s in Suit::iterator() {
+		for r in Rank::iterator() {
+			deck.push(Card {rank: *r, suit: *s});
+		}
+	}
+
+	let mut hands: Vec<Hand> = Vec::with_capacity(2598960);
+	for a in 0..deck.len() {
+		for b in a+1..deck.len() {
+			for c in b+1..deck.len() {
+				for d in c+1..deck.len() {
+					for e in d+1..deck.len() {
+						hands.push(Hand {cards: [deck[a],deck[b],deck[c],deck[d],deck[e]]});
+					}
+				}
+			}
+		}
+	}
+
+	return hands;
+}
+
+fn main() {
+	let mut hands = full_deck_hands();
+	let start = Instant::now();
+	hands.sort_by(baseline::cmp);
+	println!("baseline Hand::cmp sort of {} hands: {:?}", hands.len(), start.elapsed());
+
+	let mut hands = full_deck_hands();
+	let start = Instant::now();
+	hands.sort();
+	println!("Hand::cmp sort of {} hands: {:?}", hands.len(), start.elapsed());
+
+	let mut hands = full_deck_hands();
+	let start = Instant::now();
+	hands.sort_by_cached_key(eval::evaluate_hand);
+	println!("eval::evaluate_hand keyed sort of {} hands: {:?}", hands.len(), start.elapsed());
+}
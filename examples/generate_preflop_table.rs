@@ -0,0 +1,11 @@
+use std::env;
+use std::fs;
+
+use poker_eval::preflop::PreflopTable;
+
+fn main() {
+	let path = env::args().nth(1).unwrap_or_else(|| "src/preflop/preflop_equity.txt".to_string());
+	let table = PreflopTable::generate();
+	fs::write(&path, table.to_string()).expect("Could not write preflop table!");
+	println!("Wrote 169x169 preflop equity table to {}", path);
+}
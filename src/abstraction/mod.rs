@@ -0,0 +1,383 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::sync::OnceLock;
+
+use rand::Rng;
+
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::indexer::HandIndexer;
+use crate::strength;
+
+#[cfg(test)]
+mod tests;
+
+// Equity distributions for card abstraction: every hand gets a histogram of its
+// equity against a random holding over the runouts to the river, and hands
+// with alike histograms are put in the same bucket by k-means under the
+// earth mover's distance.
+
+pub const DEFAULT_BINS: usize = 50;
+pub const MAX_ITERATIONS: usize = 100;
+
+static INDEXER: OnceLock<HandIndexer> = OnceLock::new();
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum AbstractionError {
+	INVALID_HOLE_CARDS,
+	// 0, 3, 4 or 5 board cards, the same for every hand in a bucketing; the
+	// exact histogram needs at least the flop
+	INVALID_BOARD,
+	REPEATING_CARDS,
+	INVALID_BINS,
+	// at least one bucket and no more than the hands to put in them
+	INVALID_BUCKETS,
+	// every hand needs a histogram with the same bins
+	INVALID_HISTOGRAMS,
+	IO,
+	INVALID_FILE
+}
+
+// Share of the runouts ending in each equity bin, the lowest equities first
+#[derive(Clone, PartialEq, Debug)]
+pub struct Histogram(pub Vec<f64>);
+
+impl Histogram {
+	pub fn bins(&self) -> usize {
+		return self.0.len();
+	}
+
+	// Mean equity, taking the middle of each bin
+	pub fn mean(&self) -> f64 {
+		let width = 1.0 / self.bins() as f64;
+		return self.0.iter().enumerate().map(|(i, share)| share * (i as f64 + 0.5) * width).sum();
+	}
+
+	// Earth mover's distance on the equity line: the area between the two
+	// cumulative distributions, from 0 for equal histograms to 1 at most
+	pub fn emd(&self, other: &Histogram) -> f64 {
+		let (mut distance, mut carried) = (0.0, 0.0);
+		for (a, b) in self.0.iter().zip(other.0.iter()) {
+			carried += a - b;
+			distance += f64::abs(carried);
+		}
+
+		return distance / self.bins() as f64;
+	}
+}
+
+fn check_cards(hole: &[Card], board: &[Card], bins: usize) -> Result<CardSet, AbstractionError> {
+	if hole.len() != 2 {
+		return Err(AbstractionError::INVALID_HOLE_CARDS);
+	}
+
+	if board.len() == 1 || board.len() == 2 || board.len() > 5 {
+		return Err(AbstractionError::INVALID_BOARD);
+	}
+
+	if bins == 0 {
+		return Err(AbstractionError::INVALID_BINS);
+	}
+
+	let known = CardSet::from_cards(hole).union(CardSet::from_cards(board));
+	if known.len() != hole.len() + board.len() {
+		return Err(AbstractionError::REPEATING_CARDS);
+	}
+
+	return Ok(known);
+}
+
+fn add_equity(histogram: &mut [f64], equity: f64) {
+	let bin = ((equity * histogram.len() as f64) as usize).min(histogram.len() - 1);
+	histogram[bin] += 1.0;
+}
+
+fn normalize(mut histogram: Vec<f64>) -> Histogram {
+	let total: f64 = histogram.iter().sum();
+	for share in histogram.iter_mut() {
+		*share /= total;
+	}
+
+	return Histogram(histogram);
+}
+
+// Every runout of the board to the river, from the flop on. The preflop has
+// too many runouts to enumerate; use sampled_histogram for it.
+pub fn histogram(hole: &[Card], board: &[Card], bins: usize) -> Result<Histogram, AbstractionError> {
+	let known = check_cards(hole, board, bins)?;
+	if board.is_empty() {
+		return Err(AbstractionError::INVALID_BOARD);
+	}
+
+	let hero = CardSet::from_cards(hole);
+	let deck: Vec<Card> = CardSet::FULL_DECK.difference(known).iter().collect();
+
+	let mut histogram = vec![0.0; bins];
+	let board_set = CardSet::from_cards(board);
+	for runout in strength::runouts(&deck, 5 - board.len()) {
+		add_equity(&mut histogram, strength::strength_against_random(hero, board_set.union(runout)));
+	}
+
+	return Ok(normalize(histogram));
+}
+
+// The given number of random runouts, each valued exactly on the river
+pub fn sampled_histogram(hole: &[Card], board: &[Card], bins: usize, samples: usize, rng: &mut impl Rng) -> Result<Histogram, AbstractionError> {
+	let known = check_cards(hole, board, bins)?;
+	let hero = CardSet::from_cards(hole);
+	let unseen: Vec<Card> = CardSet::FULL_DECK.difference(known).iter().collect();
+
+	let mut histogram = vec![0.0; bins];
+	for _ in 0..samples.max(1) {
+		let mut deck = unseen.clone();
+		let mut runout = CardSet::from_cards(board);
+		for _ in board.len()..5 {
+			runout.insert(deck.swap_remove(rng.gen_range(0, deck.len())));
+		}
+
+		add_equity(&mut histogram, strength::strength_against_random(hero, runout));
+	}
+
+	return Ok(normalize(histogram));
+}
+
+// Plain k-means with the earth mover's distance. The first centre is the
+// weakest histogram and each next one the histogram farthest from the centres
+// so far, so the same input always gives the same buckets. Centres are sorted
+// by mean equity, bucket 0 holding the weakest hands.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Clustering {
+	pub centroids: Vec<Histogram>,
+	pub assignments: Vec<usize>
+}
+
+fn nearest(centroids: &[Histogram], histogram: &Histogram) -> usize {
+	let mut best = 0;
+	for (i, centroid) in centroids.iter().enumerate() {
+		if histogram.emd(centroid) < histogram.emd(&centroids[best]) {
+			best = i;
+		}
+	}
+
+	return best;
+}
+
+pub fn kmeans(histograms: &[Histogram], buckets: usize, iterations: usize) -> Result<Clustering, AbstractionError> {
+	if buckets == 0 || buckets > histograms.len() {
+		return Err(AbstractionError::INVALID_BUCKETS);
+	}
+
+	let bins = histograms[0].bins();
+	if bins == 0 || histograms.iter().any(|h| h.bins() != bins) {
+		return Err(AbstractionError::INVALID_HISTOGRAMS);
+	}
+
+	let mut first = 0;
+	for (i, histogram) in histograms.iter().enumerate() {
+		if histogram.mean() < histograms[first].mean() {
+			first = i;
+		}
+	}
+
+	let mut centroids = vec![histograms[first].clone()];
+	let mut distances: Vec<f64> = histograms.iter().map(|h| h.emd(&centroids[0])).collect();
+	while centroids.len() < buckets {
+		let mut farthest = 0;
+		for (i, distance) in distances.iter().enumerate() {
+			if *distance > distances[farthest] {
+				farthest = i;
+			}
+		}
+
+		centroids.push(histograms[farthest].clone());
+		for (distance, histogram) in distances.iter_mut().zip(histograms.iter()) {
+			*distance = distance.min(histogram.emd(&histograms[farthest]));
+		}
+	}
+
+	let mut assignments = vec![usize::MAX; histograms.len()];
+	for _ in 0..iterations.max(1) {
+		let next: Vec<usize> = histograms.iter().map(|h| nearest(&centroids, h)).collect();
+		if next == assignments {
+			break;
+		}
+
+		assignments = next;
+
+		// a bucket left empty keeps its centre
+		let mut sums = vec![vec![0.0; bins]; buckets];
+		let mut counts = vec![0usize; buckets];
+		for (histogram, bucket) in histograms.iter().zip(assignments.iter()) {
+			for (sum, share) in sums[*bucket].iter_mut().zip(histogram.0.iter()) {
+				*sum += share;
+			}
+
+			counts[*bucket] += 1;
+		}
+
+		for (bucket, sum) in sums.into_iter().enumerate() {
+			if counts[bucket] > 0 {
+				centroids[bucket] = Histogram(sum.iter().map(|s| s / counts[bucket] as f64).collect());
+			}
+		}
+	}
+
+	let mut order: Vec<usize> = (0..buckets).collect();
+	order.sort_by(|a, b| centroids[*a].mean().partial_cmp(&centroids[*b].mean()).unwrap().then(a.cmp(b)));
+	let mut renamed = vec![0; buckets];
+	for (new, old) in order.iter().enumerate() {
+		renamed[*old] = new;
+	}
+
+	return Ok(Clustering {
+		centroids: order.iter().map(|i| centroids[*i].clone()).collect(),
+		assignments: assignments.iter().map(|a| renamed[*a]).collect()
+	});
+}
+
+// The bucket of every hand of a street, keyed by its suit-isomorphic index
+#[derive(Clone, PartialEq, Debug)]
+pub struct Bucketing {
+	board_cards: usize,
+	centroids: Vec<Histogram>,
+	buckets: BTreeMap<u64, usize>
+}
+
+fn indexer() -> &'static HandIndexer {
+	return INDEXER.get_or_init(|| HandIndexer::holdem(true));
+}
+
+impl Bucketing {
+	// Clusters the hands by the histogram given for each one. Suit-isomorphic
+	// hands share a bucket, the last one given deciding it.
+	pub fn build(hands: &[([Card; 2], Vec<Card>)], histograms: &[Histogram], buckets: usize) -> Result<Bucketing, AbstractionError> {
+		if hands.is_empty() || hands.len() != histograms.len() {
+			return Err(AbstractionError::INVALID_HISTOGRAMS);
+		}
+
+		let board_cards = hands[0].1.len();
+		for (hole, board) in hands {
+			if board.len() != board_cards {
+				return Err(AbstractionError::INVALID_BOARD);
+			}
+
+			check_cards(hole, board, 1)?;
+		}
+
+		let clustering = kmeans(histograms, buckets, MAX_ITERATIONS)?;
+		let mut bucketing = Bucketing {board_cards, centroids: clustering.centroids, buckets: BTreeMap::new()};
+		for ((hole, board), bucket) in hands.iter().zip(clustering.assignments) {
+			bucketing.buckets.insert(indexer().index(hole, board), bucket);
+		}
+
+		return Ok(bucketing);
+	}
+
+	pub fn board_cards(&self) -> usize {
+		return self.board_cards;
+	}
+
+	pub fn len(&self) -> usize {
+		return self.centroids.len();
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.centroids.is_empty();
+	}
+
+	pub fn centroids(&self) -> &[Histogram] {
+		return &self.centroids;
+	}
+
+	pub fn hands(&self) -> usize {
+		return self.buckets.len();
+	}
+
+	pub fn bucket(&self, hole: &[Card], board: &[Card]) -> Option<usize> {
+		if board.len() != self.board_cards || check_cards(hole, board, 1).is_err() {
+			return None;
+		}
+
+		return self.buckets.get(&indexer().index(hole, board)).copied();
+	}
+
+	// The bucket whose centre is closest, for a hand left out of the bucketing
+	pub fn nearest(&self, histogram: &Histogram) -> Option<usize> {
+		if histogram.bins() != self.centroids[0].bins() {
+			return None;
+		}
+
+		return Some(nearest(&self.centroids, histogram));
+	}
+
+	// A header line "buckets <board cards> <bins> <buckets>", one line per
+	// centre and then one "<hand index> <bucket>" line per hand
+	pub fn to_string(&self) -> String {
+		let mut result = format!("buckets {} {} {}\n", self.board_cards, self.centroids[0].bins(), self.centroids.len());
+		for centroid in &self.centroids {
+			let shares: Vec<String> = centroid.0.iter().map(|s| s.to_string()).collect();
+			result += &shares.join(" ");
+			result += "\n";
+		}
+
+		for (index, bucket) in &self.buckets {
+			writeln!(result, "{} {}", index, bucket).unwrap();
+		}
+
+		return result;
+	}
+
+	pub fn parse(s: &str) -> Result<Bucketing, AbstractionError> {
+		let mut lines = s.lines().filter(|l| !l.trim().is_empty());
+		let header: Vec<&str> = lines.next().ok_or(AbstractionError::INVALID_FILE)?.split_whitespace().collect();
+		if header.len() != 4 || header[0] != "buckets" {
+			return Err(AbstractionError::INVALID_FILE);
+		}
+
+		let number = |s: &str| s.parse::<usize>().map_err(|_| AbstractionError::INVALID_FILE);
+		let (board_cards, bins, buckets) = (number(header[1])?, number(header[2])?, number(header[3])?);
+		if ![0, 3, 4, 5].contains(&board_cards) || bins == 0 || buckets == 0 {
+			return Err(AbstractionError::INVALID_FILE);
+		}
+
+		let mut centroids = Vec::with_capacity(buckets);
+		for _ in 0..buckets {
+			let line = lines.next().ok_or(AbstractionError::INVALID_FILE)?;
+			let shares = line.split_whitespace().map(|s| s.parse::<f64>()).collect::<Result<Vec<f64>, _>>().map_err(|_| AbstractionError::INVALID_FILE)?;
+			if shares.len() != bins {
+				return Err(AbstractionError::INVALID_FILE);
+			}
+
+			centroids.push(Histogram(shares));
+		}
+
+		let size = indexer().size([0, 0, 0, 1, 2, 3][board_cards]);
+		let mut hands = BTreeMap::new();
+		for line in lines {
+			let fields: Vec<&str> = line.split_whitespace().collect();
+			if fields.len() != 2 {
+				return Err(AbstractionError::INVALID_FILE);
+			}
+
+			let index = fields[0].parse::<u64>().map_err(|_| AbstractionError::INVALID_FILE)?;
+			let bucket = number(fields[1])?;
+			if index >= size || bucket >= buckets {
+				return Err(AbstractionError::INVALID_FILE);
+			}
+
+			hands.insert(index, bucket);
+		}
+
+		return Ok(Bucketing {board_cards, centroids, buckets: hands});
+	}
+
+	pub fn save(&self, path: &str) -> Result<(), AbstractionError> {
+		return fs::write(path, self.to_string()).map_err(|_| AbstractionError::IO);
+	}
+
+	pub fn load(path: &str) -> Result<Bucketing, AbstractionError> {
+		return Bucketing::parse(&fs::read_to_string(path).map_err(|_| AbstractionError::IO)?);
+	}
+}
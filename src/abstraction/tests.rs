@@ -0,0 +1,114 @@
+use super::*;
+
+use std::convert::TryInto;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[test]
+fn check_emd() {
+	let low = Histogram(vec![1.0, 0.0, 0.0, 0.0]);
+	let high = Histogram(vec![0.0, 0.0, 0.0, 1.0]);
+	let split = Histogram(vec![0.5, 0.0, 0.0, 0.5]);
+
+	assert_eq!(low.emd(&low), 0.0);
+	assert_eq!(low.emd(&high), 0.75);
+	assert_eq!(high.emd(&low), 0.75);
+	assert_eq!(low.emd(&split), 0.375);
+	assert_eq!(low.mean(), 0.125);
+	assert_eq!(split.mean(), 0.5);
+}
+
+#[test]
+fn check_histograms() {
+	// the nut flush on the river is one spike at the top
+	let nuts = histogram(&Card::parse_list("Ah Kh").unwrap(), &Card::parse_list("2h 7h 9h Jc 3d").unwrap(), 10).unwrap();
+	assert_eq!(nuts.0[9], 1.0);
+
+	// a flush draw on the turn either gets there or is left with ace high
+	let draw = histogram(&Card::parse_list("Ah Kh").unwrap(), &Card::parse_list("2h 7h 9c Jd").unwrap(), 10).unwrap();
+	assert_eq!(draw.bins(), 10);
+	assert_eq!((draw.0.iter().sum::<f64>() - 1.0).abs() < 1e-9, true);
+	assert_eq!((draw.0[9] - 9.0 / 46.0).abs() < 1e-9, true);
+	assert_eq!(draw.0.iter().filter(|s| **s > 0.0).count() > 2, true);
+
+	let made = histogram(&Card::parse_list("Jc Js").unwrap(), &Card::parse_list("2h 7h 9c Jd").unwrap(), 10).unwrap();
+	assert_eq!(made.mean() > draw.mean(), true);
+	assert_eq!(made.emd(&nuts) < draw.emd(&nuts), true);
+
+	let mut rng = StdRng::seed_from_u64(7);
+	let sampled = sampled_histogram(&Card::parse_list("Ah Kh").unwrap(), &Card::parse_list("2h 7h 9c Jd").unwrap(), 10, 200, &mut rng).unwrap();
+	assert_eq!((sampled.mean() - draw.mean()).abs() < 0.05, true);
+
+	assert_eq!(histogram(&Card::parse_list("Ah").unwrap(), &Card::parse_list("2h 7h 9c").unwrap(), 10).err(), Some(AbstractionError::INVALID_HOLE_CARDS));
+	assert_eq!(histogram(&Card::parse_list("Ah Kh").unwrap(), &Card::parse_list("2h 7h").unwrap(), 10).err(), Some(AbstractionError::INVALID_BOARD));
+	assert_eq!(histogram(&Card::parse_list("Ah Kh").unwrap(), &[], 10).err(), Some(AbstractionError::INVALID_BOARD));
+	assert_eq!(sampled_histogram(&Card::parse_list("Ah Kh").unwrap(), &[], 10, 100, &mut rng).unwrap().bins(), 10);
+	assert_eq!(histogram(&Card::parse_list("Ah Kh").unwrap(), &Card::parse_list("Ah 7h 9c").unwrap(), 10).err(), Some(AbstractionError::REPEATING_CARDS));
+	assert_eq!(histogram(&Card::parse_list("Ah Kh").unwrap(), &Card::parse_list("2h 7h 9c").unwrap(), 0).err(), Some(AbstractionError::INVALID_BINS));
+}
+
+#[test]
+fn check_kmeans() {
+	let histograms = vec![
+		Histogram(vec![0.0, 0.1, 0.0, 0.9]),
+		Histogram(vec![0.9, 0.1, 0.0, 0.0]),
+		Histogram(vec![0.0, 0.0, 0.1, 0.9]),
+		Histogram(vec![1.0, 0.0, 0.0, 0.0]),
+		Histogram(vec![0.6, 0.3, 0.1, 0.0])
+	];
+
+	let clustering = kmeans(&histograms, 2, MAX_ITERATIONS).unwrap();
+	assert_eq!(clustering.assignments, vec![1, 0, 1, 0, 0]);
+	assert_eq!(clustering.centroids[0].mean() < clustering.centroids[1].mean(), true);
+	assert_eq!(kmeans(&histograms, 2, MAX_ITERATIONS).unwrap(), clustering);
+
+	let clustering = kmeans(&histograms, 5, MAX_ITERATIONS).unwrap();
+	let mut assignments = clustering.assignments.clone();
+	assignments.sort();
+	assert_eq!(assignments, vec![0, 1, 2, 3, 4]);
+
+	assert_eq!(kmeans(&histograms, 0, MAX_ITERATIONS).err(), Some(AbstractionError::INVALID_BUCKETS));
+	assert_eq!(kmeans(&histograms, 6, MAX_ITERATIONS).err(), Some(AbstractionError::INVALID_BUCKETS));
+	assert_eq!(kmeans(&[Histogram(vec![1.0]), Histogram(vec![0.5, 0.5])], 1, MAX_ITERATIONS).err(), Some(AbstractionError::INVALID_HISTOGRAMS));
+}
+
+#[test]
+fn check_bucketing() {
+	let board = Card::parse_list("2h 7h 9c Jd Ks").unwrap();
+	let hands: Vec<([Card; 2], Vec<Card>)> = ["Ah Kh", "Kc Kd", "3c 4d", "5c 6d", "Jc 9d", "8c Td", "Ac 3h"].iter()
+		.map(|h| (Card::parse_list(h).unwrap()[..].try_into().unwrap(), board.clone()))
+		.collect();
+	let histograms: Vec<Histogram> = hands.iter().map(|(h, b)| histogram(h, b, 20).unwrap()).collect();
+
+	let bucketing = Bucketing::build(&hands, &histograms, 3).unwrap();
+	assert_eq!(bucketing.len(), 3);
+	assert_eq!(bucketing.hands(), 7);
+	assert_eq!(bucketing.board_cards(), 5);
+	assert_eq!(bucketing.bucket(&Card::parse_list("3c 4d").unwrap(), &board), Some(0));
+	assert_eq!(bucketing.bucket(&Card::parse_list("Kc Kd").unwrap(), &board), Some(2));
+	assert_eq!(bucketing.bucket(&Card::parse_list("8c Td").unwrap(), &board), Some(2));
+	assert_eq!(bucketing.bucket(&Card::parse_list("Qc Qd").unwrap(), &board), None);
+	assert_eq!(bucketing.bucket(&Card::parse_list("3c 4d").unwrap(), &board[..4]), None);
+
+	// the same hand with the suits swapped shares the bucket
+	assert_eq!(bucketing.bucket(&Card::parse_list("3s 4d").unwrap(), &Card::parse_list("2h 7h 9s Jd Kc").unwrap()), Some(0));
+
+	let queens = histogram(&Card::parse_list("Qc Qd").unwrap(), &board, 20).unwrap();
+	assert_eq!(bucketing.nearest(&queens), Some(2));
+	assert_eq!(bucketing.nearest(&Histogram(vec![1.0])), None);
+
+	let parsed = Bucketing::parse(&bucketing.to_string()).unwrap();
+	assert_eq!(parsed, bucketing);
+
+	let path = std::env::temp_dir().join(format!("poker_eval_buckets_test_{}.txt", std::process::id()));
+	let path = path.to_str().unwrap();
+	bucketing.save(path).unwrap();
+	assert_eq!(Bucketing::load(path).unwrap(), bucketing);
+	fs::remove_file(path).unwrap();
+
+	assert_eq!(Bucketing::load("/nonexistent/buckets.txt").err(), Some(AbstractionError::IO));
+	assert_eq!(Bucketing::parse("buckets 5 20").err(), Some(AbstractionError::INVALID_FILE));
+	assert_eq!(Bucketing::parse("buckets 5 2 1\n0.5 0.5\n12 1").err(), Some(AbstractionError::INVALID_FILE));
+	assert_eq!(Bucketing::build(&hands, &histograms[..6], 3).err(), Some(AbstractionError::INVALID_HISTOGRAMS));
+}
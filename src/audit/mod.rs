@@ -0,0 +1,195 @@
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::history::ActionKind;
+use crate::history::HandRecord;
+use crate::pot;
+use crate::pot::PotError;
+use crate::showdown::split_pot;
+use crate::showdown::Showdown;
+
+#[cfg(test)]
+mod tests;
+
+// Amounts are (expected, recorded) unless noted otherwise.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Eq, PartialEq)]
+pub enum Discrepancy {
+	// a card seen twice between the board and the known hole cards
+	DUPLICATE_CARD(Card),
+	// more distinct hole cards dealt, shown or mucked than the game deals
+	CONFLICTING_HOLE_CARDS(String),
+	// put in more chips than the starting stack
+	OVERCOMMITTED(String),
+	UNCALLED_BET(Option<(String, u64)>, Option<(String, u64)>),
+	TOTAL_POT(u64, u64),
+	POT_COUNT(usize, usize),
+	// pot, expected, recorded
+	POT_AMOUNT(usize, u64, u64),
+	// rake implied by the total pot and the amounts collected, recorded rake
+	RAKE(u64, u64),
+	// pot, expected and recorded winners
+	WRONG_WINNERS(usize, Vec<String>, Vec<String>),
+	// pot, player, expected and recorded share
+	WRONG_SPLIT(usize, String, u64, u64),
+	// the winners could not be recomputed, e.g. cards were not shown
+	UNVERIFIABLE(PotError)
+}
+
+#[derive(Clone)]
+pub struct AuditReport {
+	pub hand_id: u64,
+	pub discrepancies: Vec<Discrepancy>
+}
+
+impl AuditReport {
+	pub fn is_clean(&self) -> bool {
+		return self.discrepancies.is_empty();
+	}
+}
+
+fn check_cards(record: &HandRecord, discrepancies: &mut Vec<Discrepancy>) {
+	let mut seen = CardSet::new();
+	let mut insert = |card: Card, discrepancies: &mut Vec<Discrepancy>| {
+		if !seen.insert(card) {
+			discrepancies.push(Discrepancy::DUPLICATE_CARD(card));
+		}
+	};
+
+	for card in &record.board {
+		insert(*card, discrepancies);
+	}
+
+	for seat in &record.seats {
+		let mut known = CardSet::new();
+		for (_, cards) in record.dealt.iter().filter(|d| d.0 == seat.name) {
+			known = known.union(CardSet::from_cards(cards));
+		}
+
+		for action in record.player_actions(&seat.name) {
+			if let ActionKind::SHOW(cards) | ActionKind::MUCK(cards) = &action.kind {
+				known = known.union(CardSet::from_cards(cards));
+			}
+		}
+
+		if known.len() > record.variant.hole_cards() {
+			discrepancies.push(Discrepancy::CONFLICTING_HOLE_CARDS(seat.name.clone()));
+		}
+
+		for card in known.iter() {
+			insert(card, discrepancies);
+		}
+	}
+}
+
+fn name_of(record: &HandRecord, seat: usize) -> String {
+	return record.seats.iter().find(|s| s.seat == seat).map(|s| s.name.clone()).unwrap_or_default();
+}
+
+// Collected amounts per player for one pot, in seat order
+fn recorded_awards(record: &HandRecord, pot: usize) -> Vec<(String, u64)> {
+	return record.seats.iter().filter_map(|seat| {
+		let amounts: Vec<u64> = record.player_actions(&seat.name).filter_map(|a| match a.kind {
+			ActionKind::COLLECT(amount, p) if p == pot => Some(amount),
+			_ => None
+		}).collect();
+
+		if amounts.is_empty() { None } else { Some((seat.name.clone(), amounts.iter().sum())) }
+	}).collect();
+}
+
+// Recomputes everything the hand history claims from the actions and the
+// cards, and lists every claim that does not hold.
+pub fn audit(record: &HandRecord) -> AuditReport {
+	let mut discrepancies = Vec::new();
+	check_cards(record, &mut discrepancies);
+
+	let contributions = record.contributions();
+	for c in &contributions {
+		let seat = record.seats.iter().find(|s| s.seat == c.seat).unwrap();
+		if c.amount > seat.stack {
+			discrepancies.push(Discrepancy::OVERCOMMITTED(seat.name.clone()));
+		}
+	}
+
+	let expected_uncalled = pot::uncalled_bet(&contributions).map(|(seat, amount)| (name_of(record, seat), amount));
+	let recorded_uncalled = record.actions.iter().find_map(|a| match a.kind {
+		ActionKind::UNCALLED(amount) => Some((a.player.clone(), amount)),
+		_ => None
+	});
+	if expected_uncalled != recorded_uncalled {
+		discrepancies.push(Discrepancy::UNCALLED_BET(expected_uncalled, recorded_uncalled));
+	}
+
+	let pots = match pot::build_pots(&contributions) {
+		Ok((pots, _)) => pots,
+		Err(e) => {
+			discrepancies.push(Discrepancy::UNVERIFIABLE(e));
+			return AuditReport {hand_id: record.hand_id, discrepancies};
+		}
+	};
+
+	let total = pots.iter().map(|p| p.amount).sum::<u64>();
+	if total != record.total_pot {
+		discrepancies.push(Discrepancy::TOTAL_POT(total, record.total_pot));
+	}
+
+	let collected = record.actions.iter().map(|a| match a.kind {
+		ActionKind::COLLECT(amount, _) => amount,
+		_ => 0
+	}).sum::<u64>();
+	if collected + record.rake != record.total_pot {
+		discrepancies.push(Discrepancy::RAKE(record.total_pot.saturating_sub(collected), record.rake));
+	}
+
+	if pots.len() != record.pots.len() {
+		discrepancies.push(Discrepancy::POT_COUNT(pots.len(), record.pots.len()));
+		return AuditReport {hand_id: record.hand_id, discrepancies};
+	}
+
+	if pots.len() > 1 {
+		for (i, (pot, recorded)) in pots.iter().zip(record.pots.iter()).enumerate() {
+			if pot.amount != *recorded {
+				discrepancies.push(Discrepancy::POT_AMOUNT(i, pot.amount, *recorded));
+			}
+		}
+	}
+
+	let players = record.seats.iter()
+		.filter(|s| record.folded(&s.name).is_none())
+		.filter_map(|s| record.hole_cards(&s.name).map(|cards| (s.seat, cards)))
+		.collect();
+	let showdown = Showdown {variant: record.variant, board: record.board.clone(), button: record.button, players};
+	let resolution = match pot::settle_conceding(&contributions, &showdown) {
+		Ok(resolution) => resolution,
+		Err(e) => {
+			discrepancies.push(Discrepancy::UNVERIFIABLE(e));
+			return AuditReport {hand_id: record.hand_id, discrepancies};
+		}
+	};
+
+	for i in 0..pots.len() {
+		let expected: Vec<String> = record.seats.iter()
+			.filter(|s| resolution.awards.iter().any(|a| a.pot == i && a.seat == s.seat))
+			.map(|s| s.name.clone())
+			.collect();
+		let awards = recorded_awards(record, i);
+		let recorded: Vec<String> = awards.iter().map(|a| a.0.clone()).collect();
+
+		if expected != recorded {
+			discrepancies.push(Discrepancy::WRONG_WINNERS(i, expected, recorded));
+			continue;
+		}
+
+		// the rake comes off before the split, so only the shares are checked
+		let seats: Vec<usize> = awards.iter().map(|a| record.seat(&a.0).unwrap().seat).collect();
+		let amount = awards.iter().map(|a| a.1).sum::<u64>();
+		for (seat, share) in split_pot(amount, &seats, record.button) {
+			let (name, recorded) = &awards[seats.iter().position(|s| *s == seat).unwrap()];
+			if share != *recorded {
+				discrepancies.push(Discrepancy::WRONG_SPLIT(i, name.clone(), share, *recorded));
+			}
+		}
+	}
+
+	return AuditReport {hand_id: record.hand_id, discrepancies};
+}
@@ -0,0 +1,152 @@
+use super::*;
+
+use crate::history::parse_hand;
+use crate::showdown::ShowdownError;
+
+const SPLIT_HAND: &str = "\
+PokerStars Hand #230000000001:  Hold'em No Limit ($0.05/$0.10 USD) - 2021/06/02 20:00:00 ET
+Table 'Gamma' 6-max Seat #1 is the button
+Seat 1: Hal ($10 in chips)
+Seat 2: Ivy ($10 in chips)
+Seat 3: Jon ($10 in chips)
+Ivy: posts small blind $0.05
+Jon: posts big blind $0.10
+*** HOLE CARDS ***
+Hal: calls $0.10
+Ivy: calls $0.05
+Jon: checks
+*** FLOP *** [Ah Kh Qh]
+Ivy: checks
+Jon: checks
+Hal: checks
+*** TURN *** [Ah Kh Qh] [Jh]
+Ivy: checks
+Jon: checks
+Hal: checks
+*** RIVER *** [Ah Kh Qh Jh] [Th]
+Ivy: checks
+Jon: checks
+Hal: checks
+*** SHOW DOWN ***
+Ivy: shows [2c 3d] (a Royal Flush)
+Jon: shows [4c 5d] (a Royal Flush)
+Hal: shows [6c 7d] (a Royal Flush)
+Ivy collected $0.10 from pot
+Jon collected $0.10 from pot
+Hal collected $0.09 from pot
+*** SUMMARY ***
+Total pot $0.30 | Rake $0.01
+Board [Ah Kh Qh Jh Th]
+";
+
+const ALL_IN_HAND: &str = "\
+PokerStars Hand #220000000001:  Hold'em No Limit ($0.25/$0.50 USD) - 2021/06/01 20:00:00 ET
+Table 'Beta' 6-max Seat #3 is the button
+Seat 1: Eve ($50 in chips)
+Seat 3: Finn ($20 in chips)
+Seat 4: Gus ($60 in chips)
+Gus: posts small blind $0.25
+Eve: posts big blind $0.50
+*** HOLE CARDS ***
+Dealt to Finn [Th Tc]
+Finn: raises $1 to $1.50
+Gus: folds
+Eve: calls $1
+*** FLOP *** [Ts 6d 2s]
+Eve: bets $2
+Finn: raises $4 to $6
+Eve: raises $42.50 to $48.50 and is all-in
+Finn: calls $12.50 and is all-in
+Uncalled bet ($30) returned to Eve
+*** TURN *** [Ts 6d 2s] [Kh]
+*** RIVER *** [Ts 6d 2s Kh] [4s]
+*** SHOW DOWN ***
+Eve: shows [As Qs] (a flush, Ace high)
+Finn: shows [Th Tc] (three of a kind, Tens)
+Eve collected $38.60 from pot
+*** SUMMARY ***
+Total pot $40.25 | Rake $1.65
+Board [Ts 6d 2s Kh 4s]
+";
+
+fn audit_text(text: &str) -> Vec<Discrepancy> {
+	return audit(&parse_hand(text).unwrap()).discrepancies;
+}
+
+fn card(s: &str) -> Card {
+	return Card::new_from_string(s.to_string());
+}
+
+#[test]
+fn check_clean_hands() {
+	let report = audit(&parse_hand(SPLIT_HAND).unwrap());
+	assert_eq!(report.hand_id, 230000000001);
+	assert_eq!(report.is_clean(), true);
+	assert_eq!(audit(&parse_hand(ALL_IN_HAND).unwrap()).is_clean(), true);
+}
+
+#[test]
+fn check_wrong_winner() {
+	let text = ALL_IN_HAND.replace("Eve collected", "Finn collected");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::WRONG_WINNERS(0, vec!["Eve".to_string()], vec!["Finn".to_string()])], true);
+
+	let text = SPLIT_HAND.replace("Hal collected $0.09 from pot\n", "").replace("Rake $0.01", "Rake $0.10");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::WRONG_WINNERS(0, vec!["Hal".to_string(), "Ivy".to_string(), "Jon".to_string()], vec!["Ivy".to_string(), "Jon".to_string()])], true);
+}
+
+#[test]
+fn check_wrong_split() {
+	let text = SPLIT_HAND.replace("Jon collected $0.10", "Jon collected $0.09").replace("Hal collected $0.09", "Hal collected $0.10");
+	assert_eq!(audit_text(&text) == vec![
+		Discrepancy::WRONG_SPLIT(0, "Jon".to_string(), 10, 9),
+		Discrepancy::WRONG_SPLIT(0, "Hal".to_string(), 9, 10)
+	], true);
+}
+
+#[test]
+fn check_rake_and_pots() {
+	let text = SPLIT_HAND.replace("Rake $0.01", "Rake $0.02");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::RAKE(1, 2)], true);
+
+	let text = SPLIT_HAND.replace("Total pot $0.30", "Total pot $0.31");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::TOTAL_POT(30, 31), Discrepancy::RAKE(2, 1)], true);
+
+	let text = ALL_IN_HAND.replace("Uncalled bet ($30)", "Uncalled bet ($20)");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::UNCALLED_BET(Some(("Eve".to_string(), 3000)), Some(("Eve".to_string(), 2000)))], true);
+
+	let text = ALL_IN_HAND.replace("Total pot $40.25 |", "Total pot $40.25 Main pot $30. Side pot $10.25. |");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::POT_COUNT(1, 2)], true);
+}
+
+#[test]
+fn check_impossible_cards() {
+	let repeating = Discrepancy::UNVERIFIABLE(PotError::SHOWDOWN(ShowdownError::REPEATING_CARDS));
+
+	let text = SPLIT_HAND.replace("Hal: shows [6c 7d]", "Hal: shows [Ah 7d]");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::DUPLICATE_CARD(card("Ah")), repeating.clone()], true);
+
+	let text = SPLIT_HAND.replace("Jon: shows [4c 5d]", "Jon: shows [2c 5d]");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::DUPLICATE_CARD(card("2c")), repeating], true);
+
+	let text = ALL_IN_HAND.replace("Finn: shows [Th Tc]", "Finn: shows [Th 9c]");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::CONFLICTING_HOLE_CARDS("Finn".to_string())], true);
+
+	let text = SPLIT_HAND.replace("Hal ($10 in chips)", "Hal ($0.05 in chips)");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::OVERCOMMITTED("Hal".to_string())], true);
+}
+
+#[test]
+fn check_mucked_loser() {
+	let text = ALL_IN_HAND.replace("Dealt to Finn [Th Tc]\n", "").replace("Finn: shows [Th Tc] (three of a kind, Tens)", "Finn: mucks hand");
+	assert_eq!(audit_text(&text).is_empty(), true);
+
+	// a player who does not show concedes the pot to the shown hands
+	let text = SPLIT_HAND.replace("Jon: shows [4c 5d] (a Royal Flush)\n", "");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::WRONG_WINNERS(0, vec!["Hal".to_string(), "Ivy".to_string()], vec!["Hal".to_string(), "Ivy".to_string(), "Jon".to_string()])], true);
+}
+
+#[test]
+fn check_unverifiable() {
+	let text = ALL_IN_HAND.replace("Dealt to Finn [Th Tc]\n", "").replace("Finn: shows [Th Tc] (three of a kind, Tens)\n", "").replace("Eve: shows [As Qs] (a flush, Ace high)\n", "");
+	assert_eq!(audit_text(&text) == vec![Discrepancy::UNVERIFIABLE(PotError::SHOWDOWN(ShowdownError::NO_PLAYERS))], true);
+}
@@ -0,0 +1,626 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use rand::Rng;
+use serde_json::json;
+
+use crate::hand::Card;
+use crate::hand::Rank;
+use crate::hand::Suit;
+
+#[cfg(test)]
+mod tests;
+
+// Counterfactual regret minimization for two player zero-sum games, with
+// Kuhn and Leduc hold'em as the games that ship with it.
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum NodeKind {
+	TERMINAL,
+	CHANCE,
+	PLAYER(usize)
+}
+
+// A game tree walked one state at a time. Actions are indexes into the list
+// given for the state; players are 0 and 1.
+pub trait Game {
+	type State: Clone;
+
+	fn name(&self) -> &str;
+	fn root(&self) -> Self::State;
+	fn node(&self, state: &Self::State) -> NodeKind;
+	// the states after a chance node with their probabilities
+	fn chance_outcomes(&self, state: &Self::State) -> Vec<(Self::State, f64)>;
+	fn actions(&self, state: &Self::State) -> Vec<String>;
+	fn play(&self, state: &Self::State, action: usize) -> Self::State;
+	// what the acting player knows, the same for every state they cannot tell apart
+	fn info_set(&self, state: &Self::State) -> String;
+	// payoff of player 0 at a terminal state, player 1 getting the opposite
+	fn utility(&self, state: &Self::State) -> f64;
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Variant {
+	// simultaneous updates with uniform averaging
+	VANILLA,
+	// alternating updates, regrets floored at zero and linear averaging
+	CFR_PLUS,
+	// external sampling: one chance outcome and one opponent action per node
+	MONTE_CARLO
+}
+
+impl Variant {
+	pub fn parse(s: &str) -> Option<Variant> {
+		match s.to_lowercase().as_str() {
+			"vanilla" | "cfr" => return Some(Variant::VANILLA),
+			"cfr+" | "plus" => return Some(Variant::CFR_PLUS),
+			"mccfr" | "monte-carlo" => return Some(Variant::MONTE_CARLO),
+			_ => return None
+		}
+	}
+
+	pub fn to_string(&self) -> &str {
+		match self {
+			Variant::VANILLA => return "vanilla",
+			Variant::CFR_PLUS => return "cfr+",
+			Variant::MONTE_CARLO => return "mccfr"
+		}
+	}
+}
+
+struct Node {
+	actions: Vec<String>,
+	regrets: Vec<f64>,
+	// regrets gathered during the current pass
+	pending: Vec<f64>,
+	// regret matching on the regrets before the current pass
+	strategy: Vec<f64>,
+	strategy_sum: Vec<f64>
+}
+
+impl Node {
+	fn new(actions: Vec<String>) -> Node {
+		let count = actions.len();
+		return Node {
+			actions,
+			regrets: vec![0.0; count],
+			pending: vec![0.0; count],
+			strategy: vec![1.0 / count as f64; count],
+			strategy_sum: vec![0.0; count]
+		};
+	}
+
+	fn finish_pass(&mut self, floor: bool) {
+		for (regret, pending) in self.regrets.iter_mut().zip(self.pending.iter_mut()) {
+			*regret += *pending;
+			if floor {
+				*regret = regret.max(0.0);
+			}
+
+			*pending = 0.0;
+		}
+
+		let positive: f64 = self.regrets.iter().map(|r| r.max(0.0)).sum();
+		for (share, regret) in self.strategy.iter_mut().zip(self.regrets.iter()) {
+			*share = if positive > 0.0 { regret.max(0.0) / positive } else { 1.0 / self.regrets.len() as f64 };
+		}
+	}
+
+	fn average(&self) -> Vec<f64> {
+		let total: f64 = self.strategy_sum.iter().sum();
+		if total <= 0.0 {
+			return vec![1.0 / self.actions.len() as f64; self.actions.len()];
+		}
+
+		return self.strategy_sum.iter().map(|s| s / total).collect();
+	}
+}
+
+pub struct Solver<G: Game> {
+	pub game: G,
+	pub variant: Variant,
+	pub iterations: u64,
+	nodes: HashMap<String, Node>
+}
+
+impl<G: Game> Solver<G> {
+	pub fn new(game: G, variant: Variant) -> Solver<G> {
+		return Solver {game, variant, iterations: 0, nodes: HashMap::new()};
+	}
+
+	fn node(&mut self, state: &G::State) -> &mut Node {
+		let key = self.game.info_set(state);
+		if !self.nodes.contains_key(&key) {
+			let actions = self.game.actions(state);
+			self.nodes.insert(key.clone(), Node::new(actions));
+		}
+
+		return self.nodes.get_mut(&key).unwrap();
+	}
+
+	fn finish_pass(&mut self) {
+		let floor = self.variant == Variant::CFR_PLUS;
+		for node in self.nodes.values_mut() {
+			node.finish_pass(floor);
+		}
+	}
+
+	// Value for player 0. Regrets and strategy sums are updated for the
+	// players marked in update.
+	fn traverse(&mut self, state: &G::State, reach: [f64; 2], chance: f64, update: [bool; 2], weight: f64) -> f64 {
+		let player = match self.game.node(state) {
+			NodeKind::TERMINAL => return self.game.utility(state),
+			NodeKind::CHANCE => {
+				let mut value = 0.0;
+				for (next, probability) in self.game.chance_outcomes(state) {
+					value += probability * self.traverse(&next, reach, chance * probability, update, weight);
+				}
+
+				return value;
+			},
+			NodeKind::PLAYER(player) => player
+		};
+
+		let strategy = self.node(state).strategy.clone();
+		let mut values = vec![0.0; strategy.len()];
+		let mut value = 0.0;
+		for (action, share) in strategy.iter().enumerate() {
+			let mut next_reach = reach;
+			next_reach[player] *= share;
+			values[action] = self.traverse(&self.game.play(state, action), next_reach, chance, update, weight);
+			value += share * values[action];
+		}
+
+		if update[player] {
+			let sign = if player == 0 { 1.0 } else { -1.0 };
+			let counterfactual = reach[1 - player] * chance;
+			let node = self.node(state);
+			for action in 0..strategy.len() {
+				node.pending[action] += counterfactual * sign * (values[action] - value);
+				node.strategy_sum[action] += weight * reach[player] * strategy[action];
+			}
+		}
+
+		return value;
+	}
+
+	// External sampling, value for the traverser
+	fn sample(&mut self, state: &G::State, traverser: usize, rng: &mut impl Rng) -> f64 {
+		let player = match self.game.node(state) {
+			NodeKind::TERMINAL => return if traverser == 0 { self.game.utility(state) } else { -self.game.utility(state) },
+			NodeKind::CHANCE => {
+				let outcomes = self.game.chance_outcomes(state);
+				let next = pick(&outcomes.iter().map(|o| o.1).collect::<Vec<f64>>(), rng);
+				return self.sample(&outcomes[next].0, traverser, rng);
+			},
+			NodeKind::PLAYER(player) => player
+		};
+
+		let strategy = self.node(state).strategy.clone();
+		if player != traverser {
+			let node = self.node(state);
+			for (sum, share) in node.strategy_sum.iter_mut().zip(strategy.iter()) {
+				*sum += share;
+			}
+
+			let action = pick(&strategy, rng);
+			return self.sample(&self.game.play(state, action), traverser, rng);
+		}
+
+		let mut values = vec![0.0; strategy.len()];
+		let mut value = 0.0;
+		for (action, share) in strategy.iter().enumerate() {
+			values[action] = self.sample(&self.game.play(state, action), traverser, rng);
+			value += share * values[action];
+		}
+
+		let node = self.node(state);
+		for (pending, action_value) in node.pending.iter_mut().zip(values.iter()) {
+			*pending += action_value - value;
+		}
+
+		return value;
+	}
+
+	// One iteration; only the Monte Carlo variant draws from the generator
+	pub fn iterate(&mut self, rng: &mut impl Rng) {
+		self.iterations += 1;
+		let root = self.game.root();
+		match self.variant {
+			Variant::VANILLA => {
+				self.traverse(&root, [1.0, 1.0], 1.0, [true, true], 1.0);
+				self.finish_pass();
+			},
+			Variant::CFR_PLUS => {
+				let weight = self.iterations as f64;
+				for player in 0..2 {
+					let mut update = [false, false];
+					update[player] = true;
+					self.traverse(&root, [1.0, 1.0], 1.0, update, weight);
+					self.finish_pass();
+				}
+			},
+			Variant::MONTE_CARLO => {
+				for player in 0..2 {
+					self.sample(&root, player, rng);
+					self.finish_pass();
+				}
+			}
+		}
+	}
+
+	// Runs the iterations and gives the exploitability after every given
+	// number of them, and after the last one
+	pub fn solve(&mut self, iterations: u64, report_every: u64, rng: &mut impl Rng) -> Vec<(u64, f64)> {
+		let mut report = Vec::new();
+		for i in 1..=iterations {
+			self.iterate(rng);
+			if i == iterations || (report_every > 0 && i % report_every == 0) {
+				report.push((self.iterations, self.exploitability()));
+			}
+		}
+
+		return report;
+	}
+
+	// The average strategy at an information set, uniform where it was never reached
+	pub fn average_strategy(&self, state: &G::State) -> Vec<f64> {
+		return match self.nodes.get(&self.game.info_set(state)) {
+			Some(node) => node.average(),
+			None => vec![1.0 / self.game.actions(state).len() as f64; self.game.actions(state).len()]
+		};
+	}
+
+	// Expected payoff of player 0 when both play the average strategy
+	pub fn game_value(&self) -> f64 {
+		return self.expected_value(&self.game.root());
+	}
+
+	fn expected_value(&self, state: &G::State) -> f64 {
+		match self.game.node(state) {
+			NodeKind::TERMINAL => return self.game.utility(state),
+			NodeKind::CHANCE => return self.game.chance_outcomes(state).iter().map(|(next, p)| p * self.expected_value(next)).sum(),
+			NodeKind::PLAYER(_) => {
+				let strategy = self.average_strategy(state);
+				return strategy.iter().enumerate().map(|(a, share)| share * self.expected_value(&self.game.play(state, a))).sum();
+			}
+		}
+	}
+
+	// The states of each of the player's information sets, weighted by the
+	// chance and opponent probability of reaching them
+	fn collect(&self, state: &G::State, player: usize, weight: f64, histories: &mut HashMap<String, Vec<(G::State, f64)>>) {
+		match self.game.node(state) {
+			NodeKind::TERMINAL => {},
+			NodeKind::CHANCE => {
+				for (next, probability) in self.game.chance_outcomes(state) {
+					self.collect(&next, player, weight * probability, histories);
+				}
+			},
+			NodeKind::PLAYER(acting) => {
+				if acting == player {
+					histories.entry(self.game.info_set(state)).or_default().push((state.clone(), weight));
+				}
+
+				let strategy = self.average_strategy(state);
+				for (action, share) in strategy.iter().enumerate() {
+					let share = if acting == player { 1.0 } else { *share };
+					self.collect(&self.game.play(state, action), player, weight * share, histories);
+				}
+			}
+		}
+	}
+
+	fn best_action(&self, info_set: &str, player: usize, histories: &HashMap<String, Vec<(G::State, f64)>>, decisions: &mut HashMap<String, usize>) -> usize {
+		if let Some(action) = decisions.get(info_set) {
+			return *action;
+		}
+
+		let states = &histories[info_set];
+		let mut best = (0, f64::NEG_INFINITY);
+		for action in 0..self.game.actions(&states[0].0).len() {
+			let mut value = 0.0;
+			for (state, weight) in states {
+				value += weight * self.response_value(&self.game.play(state, action), player, histories, decisions);
+			}
+
+			if value > best.1 {
+				best = (action, value);
+			}
+		}
+
+		decisions.insert(info_set.to_string(), best.0);
+		return best.0;
+	}
+
+	fn response_value(&self, state: &G::State, player: usize, histories: &HashMap<String, Vec<(G::State, f64)>>, decisions: &mut HashMap<String, usize>) -> f64 {
+		match self.game.node(state) {
+			NodeKind::TERMINAL => return if player == 0 { self.game.utility(state) } else { -self.game.utility(state) },
+			NodeKind::CHANCE => {
+				let mut value = 0.0;
+				for (next, probability) in self.game.chance_outcomes(state) {
+					value += probability * self.response_value(&next, player, histories, decisions);
+				}
+
+				return value;
+			},
+			NodeKind::PLAYER(acting) if acting == player => {
+				let action = self.best_action(&self.game.info_set(state), player, histories, decisions);
+				return self.response_value(&self.game.play(state, action), player, histories, decisions);
+			},
+			NodeKind::PLAYER(_) => {
+				let mut value = 0.0;
+				for (action, share) in self.average_strategy(state).iter().enumerate() {
+					if *share > 0.0 {
+						value += share * self.response_value(&self.game.play(state, action), player, histories, decisions);
+					}
+				}
+
+				return value;
+			}
+		}
+	}
+
+	// What the player wins with a best response to the other's average strategy
+	pub fn best_response(&self, player: usize) -> f64 {
+		let root = self.game.root();
+		let mut histories = HashMap::new();
+		self.collect(&root, player, 1.0, &mut histories);
+		return self.response_value(&root, player, &histories, &mut HashMap::new());
+	}
+
+	// Mean of what each player gains over the average strategy by deviating;
+	// zero at a Nash equilibrium
+	pub fn exploitability(&self) -> f64 {
+		return (self.best_response(0) + self.best_response(1)) / 2.0;
+	}
+
+	// {"game", "variant", "iterations", "strategy": {info set: {action: probability}}}
+	pub fn to_json(&self) -> String {
+		let mut strategy = BTreeMap::new();
+		for (info_set, node) in &self.nodes {
+			let actions: BTreeMap<&str, f64> = node.actions.iter().map(|a| a.as_str()).zip(node.average()).collect();
+			strategy.insert(info_set.as_str(), actions);
+		}
+
+		let json = json!({
+			"game": self.game.name(),
+			"variant": self.variant.to_string(),
+			"iterations": self.iterations,
+			"strategy": strategy
+		});
+
+		return serde_json::to_string_pretty(&json).unwrap();
+	}
+}
+
+fn pick(weights: &[f64], rng: &mut impl Rng) -> usize {
+	let mut target = rng.gen_range(0.0, weights.iter().sum::<f64>());
+	for (i, weight) in weights.iter().enumerate() {
+		if target < *weight {
+			return i;
+		}
+
+		target -= weight;
+	}
+
+	return weights.len() - 1;
+}
+
+fn ordered_deals(deck: &[Card]) -> Vec<[Card; 2]> {
+	let mut deals = Vec::new();
+	for first in deck {
+		for second in deck.iter().filter(|c| *c != first) {
+			deals.push([*first, *second]);
+		}
+	}
+
+	return deals;
+}
+
+// Kuhn poker: a jack, a queen and a king, one card each, an ante of one and a
+// single bet of one. Actions are "p" to pass or fold and "b" to bet or call.
+pub struct Kuhn;
+
+#[derive(Clone)]
+pub struct KuhnState {
+	pub cards: Option<[Card; 2]>,
+	pub history: String
+}
+
+impl Game for Kuhn {
+	type State = KuhnState;
+
+	fn name(&self) -> &str {
+		return "kuhn";
+	}
+
+	fn root(&self) -> KuhnState {
+		return KuhnState {cards: None, history: String::new()};
+	}
+
+	fn node(&self, state: &KuhnState) -> NodeKind {
+		if state.cards.is_none() {
+			return NodeKind::CHANCE;
+		}
+
+		match state.history.as_str() {
+			"pp" | "bp" | "bb" | "pbp" | "pbb" => return NodeKind::TERMINAL,
+			history => return NodeKind::PLAYER(history.len() % 2)
+		}
+	}
+
+	fn chance_outcomes(&self, _state: &KuhnState) -> Vec<(KuhnState, f64)> {
+		let deck: Vec<Card> = [Rank::JACK, Rank::QUEEN, Rank::KING].iter().map(|r| Card {suit: Suit::SPADES, rank: *r}).collect();
+		let deals = ordered_deals(&deck);
+		let probability = 1.0 / deals.len() as f64;
+		return deals.into_iter().map(|d| (KuhnState {cards: Some(d), history: String::new()}, probability)).collect();
+	}
+
+	fn actions(&self, _state: &KuhnState) -> Vec<String> {
+		return vec!["p".to_string(), "b".to_string()];
+	}
+
+	fn play(&self, state: &KuhnState, action: usize) -> KuhnState {
+		let mut next = state.clone();
+		next.history.push(if action == 0 { 'p' } else { 'b' });
+		return next;
+	}
+
+	fn info_set(&self, state: &KuhnState) -> String {
+		let player = state.history.len() % 2;
+		return format!("{}:{}", state.cards.unwrap()[player].rank.to_string(), state.history);
+	}
+
+	fn utility(&self, state: &KuhnState) -> f64 {
+		let cards = state.cards.unwrap();
+		let showdown = if cards[0].rank > cards[1].rank { 1.0 } else { -1.0 };
+		match state.history.as_str() {
+			"bp" => return 1.0,
+			"pbp" => return -1.0,
+			"pp" => return showdown,
+			_ => return 2.0 * showdown
+		}
+	}
+}
+
+// Leduc hold'em: two jacks, queens and kings, one private card each and one
+// board card after the first round. Antes of one, bets of two and then four,
+// at most two raises a round. A pair with the board wins, then the higher card.
+// Actions are "f" to fold, "c" to check or call and "r" to bet or raise.
+pub struct Leduc;
+
+#[derive(Clone)]
+pub struct LeducState {
+	pub cards: Option<[Card; 2]>,
+	pub board: Option<Card>,
+	// the actions of each round
+	pub rounds: [String; 2],
+	pub committed: [u32; 2]
+}
+
+impl LeducState {
+	fn round(&self) -> usize {
+		return if self.board.is_some() { 1 } else { 0 };
+	}
+
+	fn folded(&self) -> bool {
+		return self.rounds[self.round()].ends_with('f');
+	}
+
+	fn round_over(&self, round: usize) -> bool {
+		let actions = &self.rounds[round];
+		return actions.len() >= 2 && actions.ends_with('c');
+	}
+}
+
+fn leduc_deck() -> Vec<Card> {
+	let mut deck = Vec::new();
+	for rank in [Rank::JACK, Rank::QUEEN, Rank::KING] {
+		for suit in [Suit::HEARTS, Suit::SPADES] {
+			deck.push(Card {suit, rank});
+		}
+	}
+
+	return deck;
+}
+
+impl Game for Leduc {
+	type State = LeducState;
+
+	fn name(&self) -> &str {
+		return "leduc";
+	}
+
+	fn root(&self) -> LeducState {
+		return LeducState {cards: None, board: None, rounds: [String::new(), String::new()], committed: [1, 1]};
+	}
+
+	fn node(&self, state: &LeducState) -> NodeKind {
+		if state.cards.is_none() {
+			return NodeKind::CHANCE;
+		}
+
+		if state.folded() || state.round_over(1) {
+			return NodeKind::TERMINAL;
+		}
+
+		if state.board.is_none() && state.round_over(0) {
+			return NodeKind::CHANCE;
+		}
+
+		return NodeKind::PLAYER(state.rounds[state.round()].len() % 2);
+	}
+
+	fn chance_outcomes(&self, state: &LeducState) -> Vec<(LeducState, f64)> {
+		let deck = leduc_deck();
+		match state.cards {
+			None => {
+				let deals = ordered_deals(&deck);
+				let probability = 1.0 / deals.len() as f64;
+				return deals.into_iter().map(|d| (LeducState {cards: Some(d), ..state.clone()}, probability)).collect();
+			},
+			Some(cards) => {
+				let left: Vec<Card> = deck.into_iter().filter(|c| !cards.contains(c)).collect();
+				let probability = 1.0 / left.len() as f64;
+				return left.into_iter().map(|c| (LeducState {board: Some(c), ..state.clone()}, probability)).collect();
+			}
+		}
+	}
+
+	fn actions(&self, state: &LeducState) -> Vec<String> {
+		let actions = &state.rounds[state.round()];
+		let mut result = Vec::new();
+		if state.committed[0] != state.committed[1] {
+			result.push("f".to_string());
+		}
+
+		result.push("c".to_string());
+		if actions.matches('r').count() < 2 {
+			result.push("r".to_string());
+		}
+
+		return result;
+	}
+
+	fn play(&self, state: &LeducState, action: usize) -> LeducState {
+		let mut next = state.clone();
+		let round = state.round();
+		let player = state.rounds[round].len() % 2;
+		let action = self.actions(state)[action].clone();
+		match action.as_str() {
+			"c" => next.committed[player] = state.committed[1 - player],
+			"r" => next.committed[player] = state.committed[1 - player] + if round == 0 { 2 } else { 4 },
+			_ => {}
+		}
+
+		next.rounds[round] += &action;
+		return next;
+	}
+
+	fn info_set(&self, state: &LeducState) -> String {
+		let player = state.rounds[state.round()].len() % 2;
+		let board = state.board.map_or(String::new(), |c| c.rank.to_string().to_string());
+		return format!("{}{}:{}/{}", state.cards.unwrap()[player].rank.to_string(), board, state.rounds[0], state.rounds[1]);
+	}
+
+	fn utility(&self, state: &LeducState) -> f64 {
+		if state.folded() {
+			let loser = (state.rounds[state.round()].len() - 1) % 2;
+			let lost = state.committed[loser] as f64;
+			return if loser == 0 { -lost } else { lost };
+		}
+
+		let cards = state.cards.unwrap();
+		let board = state.board.unwrap().rank;
+		let strength = |card: Card| if card.rank == board { 100 } else { card.rank.to_int() };
+		let pot = state.committed[0] as f64;
+		match strength(cards[0]).cmp(&strength(cards[1])) {
+			std::cmp::Ordering::Greater => return pot,
+			std::cmp::Ordering::Less => return -pot,
+			std::cmp::Ordering::Equal => return 0.0
+		}
+	}
+}
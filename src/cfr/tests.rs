@@ -0,0 +1,112 @@
+use super::*;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn count_info_sets<G: Game>(game: &G, state: &G::State, seen: &mut Vec<String>) {
+	match game.node(state) {
+		NodeKind::TERMINAL => {},
+		NodeKind::CHANCE => {
+			for (next, _) in game.chance_outcomes(state) {
+				count_info_sets(game, &next, seen);
+			}
+		},
+		NodeKind::PLAYER(_) => {
+			let info_set = game.info_set(state);
+			if !seen.contains(&info_set) {
+				seen.push(info_set);
+			}
+
+			for action in 0..game.actions(state).len() {
+				count_info_sets(game, &game.play(state, action), seen);
+			}
+		}
+	}
+}
+
+#[test]
+fn check_games() {
+	let mut seen = Vec::new();
+	count_info_sets(&Kuhn, &Kuhn.root(), &mut seen);
+	assert_eq!(seen.len(), 12);
+
+	let mut seen = Vec::new();
+	count_info_sets(&Leduc, &Leduc.root(), &mut seen);
+	assert_eq!(seen.len(), 288);
+
+	// king against queen: bet and call wins two
+	let mut state = Kuhn.chance_outcomes(&Kuhn.root()).remove(5).0;
+	assert_eq!(Kuhn.info_set(&state), "K:");
+	state = Kuhn.play(&Kuhn.play(&state, 1), 1);
+	assert_eq!(Kuhn.node(&state), NodeKind::TERMINAL);
+	assert_eq!(Kuhn.utility(&state), 2.0);
+
+	// a pair of queens with the board beats the king after raise, call, bet, call
+	let mut state = Leduc.chance_outcomes(&Leduc.root()).remove(13).0;
+	assert_eq!(Leduc.actions(&state), vec!["c", "r"]);
+	state = Leduc.play(&Leduc.play(&state, 1), 1);
+	assert_eq!(Leduc.node(&state), NodeKind::CHANCE);
+	let outcomes = Leduc.chance_outcomes(&state);
+	assert_eq!(outcomes.len(), 4);
+	state = outcomes.into_iter().find(|(s, _)| s.board.unwrap().rank == Rank::QUEEN).unwrap().0;
+	state = Leduc.play(&state, 1);
+	assert_eq!(Leduc.info_set(&state), "KQ:rc/r");
+	state = Leduc.play(&state, 1);
+	assert_eq!(Leduc.node(&state), NodeKind::TERMINAL);
+	assert_eq!(Leduc.utility(&state), 7.0);
+}
+
+#[test]
+fn check_kuhn() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let mut solver = Solver::new(Kuhn, Variant::VANILLA);
+	let report = solver.solve(2000, 500, &mut rng);
+	assert_eq!(report.len(), 4);
+	assert_eq!(report[0].1 > report[3].1, true);
+	assert_eq!(report[3].1 < 0.01, true);
+	assert_eq!((solver.game_value() + 1.0 / 18.0).abs() < 0.005, true);
+
+	// the first player never opens with the queen
+	let queen = KuhnState {cards: Some([Card {suit: Suit::SPADES, rank: Rank::QUEEN}, Card {suit: Suit::SPADES, rank: Rank::JACK}]), history: String::new()};
+	assert_eq!(solver.average_strategy(&queen)[0] > 0.98, true);
+
+	let mut solver = Solver::new(Kuhn, Variant::CFR_PLUS);
+	let report = solver.solve(300, 0, &mut rng);
+	assert_eq!(report.len(), 1);
+	assert_eq!(report[0].1 < 0.005, true);
+
+	let mut solver = Solver::new(Kuhn, Variant::MONTE_CARLO);
+	solver.solve(20000, 0, &mut rng);
+	assert_eq!(solver.exploitability() < 0.02, true);
+}
+
+#[test]
+fn check_leduc() {
+	let mut rng = StdRng::seed_from_u64(1);
+	let mut solver = Solver::new(Leduc, Variant::CFR_PLUS);
+	let start = solver.exploitability();
+	let report = solver.solve(100, 50, &mut rng);
+	assert_eq!(start > 1.0, true);
+	assert_eq!(report[1].1 < report[0].1, true);
+	assert_eq!(report[1].1 < 0.05, true);
+	assert_eq!((solver.game_value() + 0.0856).abs() < 0.02, true);
+}
+
+#[test]
+fn check_json() {
+	let mut solver = Solver::new(Kuhn, Variant::CFR_PLUS);
+	solver.solve(10, 0, &mut StdRng::seed_from_u64(1));
+	let json: serde_json::Value = serde_json::from_str(&solver.to_json()).unwrap();
+	assert_eq!(json["game"], "kuhn");
+	assert_eq!(json["variant"], "cfr+");
+	assert_eq!(json["iterations"], 10);
+	assert_eq!(json["strategy"].as_object().unwrap().len(), 12);
+
+	let king = &json["strategy"]["K:pb"];
+	assert_eq!(king["b"].as_f64().unwrap() > 0.95, true);
+	assert_eq!(((king["p"].as_f64().unwrap() + king["b"].as_f64().unwrap()) - 1.0).abs() < 1e-9, true);
+
+	assert_eq!(Variant::parse("CFR+"), Some(Variant::CFR_PLUS));
+	assert_eq!(Variant::parse("mccfr"), Some(Variant::MONTE_CARLO));
+	assert_eq!(Variant::parse("dcfr"), None);
+}
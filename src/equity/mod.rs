@@ -0,0 +1,249 @@
+use rand::Rng;
+
+use crate::eval;
+use crate::eval::CardSet;
+use crate::eval::HandValue;
+use crate::hand::Card;
+use crate::range::Range;
+
+#[cfg(test)]
+mod tests;
+
+// Exact enumeration is used up to this many deals, sampling above it
+pub const EXACT_LIMIT: u64 = 2_000_000;
+pub const DEFAULT_TRIALS: u64 = 100_000;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EquityError {
+	NOT_ENOUGH_PLAYERS,
+	// the deck cannot deal two cards to everyone and a full board
+	TOO_MANY_PLAYERS,
+	TOO_MANY_BOARD_CARDS,
+	REPEATING_CARDS,
+	// the player's range has no combo left next to the known cards
+	EMPTY_RANGE(usize),
+	// no deal gives every player a combo of their range
+	NO_VALID_DEALS
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct PlayerEquity {
+	// share of the deals won outright
+	pub win: f64,
+	// share of the deals split with someone else
+	pub tie: f64,
+	// pot share, with ties split evenly
+	pub equity: f64
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct EquityResult {
+	pub players: Vec<PlayerEquity>,
+	// deals evaluated, a deal being one combo per player and a full board
+	pub deals: u64,
+	pub exact: bool
+}
+
+struct Tally {
+	wins: Vec<f64>,
+	ties: Vec<f64>,
+	shares: Vec<f64>,
+	deals: u64
+}
+
+impl Tally {
+	fn new(players: usize) -> Tally {
+		return Tally {wins: vec![0.0; players], ties: vec![0.0; players], shares: vec![0.0; players], deals: 0};
+	}
+
+	fn add(&mut self, hands: &[CardSet], board: CardSet) {
+		let values: Vec<HandValue> = hands.iter().map(|h| eval::evaluate(h.union(board))).collect();
+		let best = *values.iter().max().unwrap();
+		let winners = values.iter().filter(|v| **v == best).count();
+
+		for (i, value) in values.iter().enumerate() {
+			if *value == best {
+				if winners == 1 {
+					self.wins[i] += 1.0;
+				} else {
+					self.ties[i] += 1.0;
+				}
+
+				self.shares[i] += 1.0 / winners as f64;
+			}
+		}
+
+		self.deals += 1;
+	}
+
+	fn result(&self, exact: bool) -> Result<EquityResult, EquityError> {
+		if self.deals == 0 {
+			return Err(EquityError::NO_VALID_DEALS);
+		}
+
+		let deals = self.deals as f64;
+		let players = (0..self.wins.len()).map(|i| PlayerEquity {
+			win: self.wins[i] / deals,
+			tie: self.ties[i] / deals,
+			equity: self.shares[i] / deals
+		}).collect();
+
+		return Ok(EquityResult {players, deals: self.deals, exact});
+	}
+}
+
+// Ranges with the combos blocked by the board and dead cards removed
+fn live_ranges(ranges: &[Range], board: &[Card], dead: &[Card]) -> Result<Vec<Vec<CardSet>>, EquityError> {
+	if ranges.len() < 2 {
+		return Err(EquityError::NOT_ENOUGH_PLAYERS);
+	}
+
+	if board.len() > 5 {
+		return Err(EquityError::TOO_MANY_BOARD_CARDS);
+	}
+
+	let known = CardSet::from_cards(board).union(CardSet::from_cards(dead));
+	if known.len() != board.len() + dead.len() {
+		return Err(EquityError::REPEATING_CARDS);
+	}
+
+	if 2 * ranges.len() + 5 + dead.len() > 52 {
+		return Err(EquityError::TOO_MANY_PLAYERS);
+	}
+
+	let mut live = Vec::with_capacity(ranges.len());
+	for (i, range) in ranges.iter().enumerate() {
+		let combos: Vec<CardSet> = range.without(known).combos().iter().map(|c| CardSet::from_cards(c)).collect();
+		if combos.is_empty() {
+			return Err(EquityError::EMPTY_RANGE(i));
+		}
+
+		live.push(combos);
+	}
+
+	return Ok(live);
+}
+
+fn binomial(n: u64, k: u64) -> u64 {
+	if k > n {
+		return 0;
+	}
+
+	let mut result = 1u64;
+	for i in 0..k {
+		result = result * (n - i) / (i + 1);
+	}
+
+	return result;
+}
+
+// Upper bound of the deals an exact enumeration would go through, to decide
+// between it and sampling.
+pub fn exact_deals(ranges: &[Range], board: &[Card]) -> u64 {
+	let combos = ranges.iter().fold(1u64, |total, r| total.saturating_mul(r.len() as u64));
+	let remaining = 52u64.saturating_sub(board.len() as u64 + 2 * ranges.len() as u64);
+
+	return combos.saturating_mul(binomial(remaining, 5 - board.len().min(5) as u64));
+}
+
+fn enumerate_boards(deck: &[Card], start: usize, missing: usize, board: CardSet, hands: &[CardSet], tally: &mut Tally) {
+	if missing == 0 {
+		tally.add(hands, board);
+		return;
+	}
+
+	for i in start..=deck.len() - missing {
+		let mut next = board;
+		next.insert(deck[i]);
+		enumerate_boards(deck, i + 1, missing - 1, next, hands, tally);
+	}
+}
+
+fn enumerate_combos(live: &[Vec<CardSet>], board: CardSet, missing: usize, hands: &mut Vec<CardSet>, used: CardSet, tally: &mut Tally) {
+	if hands.len() == live.len() {
+		let deck: Vec<Card> = CardSet::FULL_DECK.difference(used).iter().collect();
+		enumerate_boards(&deck, 0, missing, board, hands, tally);
+		return;
+	}
+
+	for combo in &live[hands.len()] {
+		if used.intersects(*combo) {
+			continue;
+		}
+
+		hands.push(*combo);
+		enumerate_combos(live, board, missing, hands, used.union(*combo), tally);
+		hands.pop();
+	}
+}
+
+// Every combination of combos and every runout of the board, each weighted the same
+pub fn exact(ranges: &[Range], board: &[Card], dead: &[Card]) -> Result<EquityResult, EquityError> {
+	let live = live_ranges(ranges, board, dead)?;
+	let board_set = CardSet::from_cards(board);
+	let used = board_set.union(CardSet::from_cards(dead));
+
+	let mut tally = Tally::new(ranges.len());
+	enumerate_combos(&live, board_set, 5 - board.len(), &mut Vec::with_capacity(ranges.len()), used, &mut tally);
+
+	return tally.result(true);
+}
+
+fn pick(deck: &mut Vec<Card>, rng: &mut impl Rng) -> Card {
+	let index = rng.gen_range(0, deck.len());
+	return deck.swap_remove(index);
+}
+
+pub fn monte_carlo(ranges: &[Range], board: &[Card], dead: &[Card], trials: u64, rng: &mut impl Rng) -> Result<EquityResult, EquityError> {
+	let live = live_ranges(ranges, board, dead)?;
+	let board_set = CardSet::from_cards(board);
+	let used = board_set.union(CardSet::from_cards(dead));
+
+	let mut tally = Tally::new(ranges.len());
+	let mut hands = vec![CardSet::EMPTY; ranges.len()];
+	let mut failures = 0u64;
+	while tally.deals < trials {
+		// give up on ranges that can hardly ever be dealt together
+		if failures > trials.max(1000) * 10 {
+			break;
+		}
+
+		let mut taken = used;
+		let mut valid = true;
+		for (i, combos) in live.iter().enumerate() {
+			let combo = combos[rng.gen_range(0, combos.len())];
+			if taken.intersects(combo) {
+				valid = false;
+				break;
+			}
+
+			hands[i] = combo;
+			taken = taken.union(combo);
+		}
+
+		if !valid {
+			failures += 1;
+			continue;
+		}
+
+		let mut deck: Vec<Card> = CardSet::FULL_DECK.difference(taken).iter().collect();
+		let mut runout = board_set;
+		for _ in board.len()..5 {
+			runout.insert(pick(&mut deck, rng));
+		}
+
+		tally.add(&hands, runout);
+	}
+
+	return tally.result(false);
+}
+
+// Exact when that is cheap enough, sampled otherwise
+pub fn calculate(ranges: &[Range], board: &[Card], dead: &[Card], rng: &mut impl Rng) -> Result<EquityResult, EquityError> {
+	if exact_deals(ranges, board) <= EXACT_LIMIT {
+		return exact(ranges, board, dead);
+	}
+
+	return monte_carlo(ranges, board, dead, DEFAULT_TRIALS, rng);
+}
@@ -0,0 +1,68 @@
+use super::*;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use crate::hand::cards;
+
+fn range(s: &str) -> Range {
+	return Range::parse(s).unwrap();
+}
+
+#[test]
+fn check_exact() {
+	let result = exact(&[range("QhJh"), range("AhAd")], &cards("As Ks 2d 3c"), &[]).unwrap();
+	assert_eq!(result.exact, true);
+	assert_eq!(result.deals, 44);
+	assert_eq!(result.players[0].win, 4.0 / 44.0);
+	assert_eq!(result.players[1].equity, 40.0 / 44.0);
+
+	let result = exact(&[range("AhKh"), range("AdKd")], &cards("2c 7s 9c Jd Qs"), &[]).unwrap();
+	assert_eq!(result.players[0] == PlayerEquity {win: 0.0, tie: 1.0, equity: 0.5}, true);
+
+	// the dead queen leaves one out for the pair
+	let result = exact(&[range("QhJh"), range("AhAd")], &cards("As Ks 2d 3c"), &cards("Qs Qd")).unwrap();
+	assert_eq!(result.deals, 42);
+	assert_eq!(result.players[0].win, 4.0 / 42.0);
+}
+
+#[test]
+fn check_ranges() {
+	// the kings on the board leave a single KK combo
+	let result = exact(&[range("QQ"), range("AA,KK")], &cards("Ks Kd 2c 3h 4s"), &[]).unwrap();
+	assert_eq!(result.deals, 6 * 7);
+	assert_eq!(result.players[1].equity, 1.0);
+
+	let deals = exact_deals(&[range("QQ"), range("AA,KK")], &cards("2c 3h 4s"));
+	assert_eq!(deals, 6 * 12 * 990);
+}
+
+#[test]
+fn check_monte_carlo() {
+	let ranges = [range("AhKh"), range("QQ,JJ")];
+	let board = cards("2c 7d 9h");
+	let expected = exact(&ranges, &board, &[]).unwrap();
+
+	let mut rng = StdRng::seed_from_u64(37);
+	let result = monte_carlo(&ranges, &board, &[], 20000, &mut rng).unwrap();
+	assert_eq!(result.exact, false);
+	assert_eq!(result.deals, 20000);
+	assert_eq!((result.players[0].equity - expected.players[0].equity).abs() < 0.02, true);
+	assert_eq!((result.players[0].equity + result.players[1].equity - 1.0).abs() < 1e-9, true);
+}
+
+#[test]
+fn check_errors() {
+	assert_eq!(exact(&[range("AA")], &[], &[]).err(), Some(EquityError::NOT_ENOUGH_PLAYERS));
+	assert_eq!(exact(&[range("AA"), range("KK")], &cards("2c 2c"), &[]).err(), Some(EquityError::REPEATING_CARDS));
+	assert_eq!(exact(&[range("AA"), range("KK")], &cards("2c 3c 4c 5c 6c 7c"), &[]).err(), Some(EquityError::TOO_MANY_BOARD_CARDS));
+	assert_eq!(exact(&[range("AA"), range("KhKd")], &cards("Kh 2c 3d"), &[]).err(), Some(EquityError::EMPTY_RANGE(1)));
+	assert_eq!(exact(&[range("AhAd"), range("AhAs")], &cards("2c 3d 4h"), &[]).err(), Some(EquityError::NO_VALID_DEALS));
+
+	// more players than the deck can deal to
+	let crowd = vec![range("22+"); 26];
+	let mut rng = StdRng::seed_from_u64(1);
+	assert_eq!(exact_deals(&crowd, &[]), 0);
+	assert_eq!(exact(&crowd, &[], &[]).err(), Some(EquityError::TOO_MANY_PLAYERS));
+	assert_eq!(monte_carlo(&crowd[..24], &[], &[], 10, &mut rng).err(), Some(EquityError::TOO_MANY_PLAYERS));
+	assert_eq!(exact_deals(&crowd[..23], &[]), u64::MAX);
+}
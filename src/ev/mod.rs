@@ -0,0 +1,141 @@
+use rand::Rng;
+
+use crate::equity;
+use crate::equity::EquityError;
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::range::Range;
+
+#[cfg(test)]
+mod tests;
+
+// Calling an all-in: what the call wins on average against each combo of the
+// opponent's range and against the whole of it. Amounts are in chips; folding
+// is worth nothing from here on.
+
+// A combo is enumerated when it has no more runouts than this, else sampled
+pub const COMBO_EXACT_LIMIT: u64 = 50_000;
+pub const COMBO_TRIALS: u64 = 5_000;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EvError {
+	// a positive pot and bet to call
+	INVALID_AMOUNTS,
+	EQUITY(EquityError)
+}
+
+pub struct CallSpot {
+	// everything in the middle, the opponent's bet included
+	pub pot: f64,
+	pub to_call: f64,
+	pub hand: [Card; 2],
+	pub range: Range,
+	pub board: Vec<Card>,
+	pub dead: Vec<Card>
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ComboEv {
+	pub combo: [Card; 2],
+	// share of the pot won with the call, ties split
+	pub equity: f64,
+	pub ev: f64
+}
+
+#[derive(Clone, PartialEq)]
+pub struct CallAnalysis {
+	pub equity: f64,
+	// pot odds as a share: the equity at which calling and folding are even
+	pub required_equity: f64,
+	// chips in the pot for each chip called
+	pub pot_odds: f64,
+	pub call_ev: f64,
+	pub fold_ev: f64,
+	// every live combo, those the call does best against first
+	pub combos: Vec<ComboEv>,
+	// the combos the call wins chips against
+	pub profitable: Range,
+	// the opponent's strongest combos that, taken alone, still leave the call
+	// even; the call loses against any tighter range, None when it loses
+	// against the whole range
+	pub breakeven: Option<Range>
+}
+
+impl CallAnalysis {
+	pub fn should_call(&self) -> bool {
+		return self.call_ev > self.fold_ev;
+	}
+
+	// Share of the live combos the break-even range takes
+	pub fn breakeven_share(&self) -> Option<f64> {
+		return self.breakeven.as_ref().map(|r| r.len() as f64 / self.combos.len() as f64);
+	}
+}
+
+fn combo_equity(spot: &CallSpot, combo: [Card; 2], rng: &mut impl Rng) -> Result<f64, EquityError> {
+	let ranges = [Range::from_combos(&[spot.hand]), Range::from_combos(&[combo])];
+	let result = if equity::exact_deals(&ranges, &spot.board) <= COMBO_EXACT_LIMIT {
+		equity::exact(&ranges, &spot.board, &spot.dead)?
+	} else {
+		equity::monte_carlo(&ranges, &spot.board, &spot.dead, COMBO_TRIALS, rng)?
+	};
+
+	return Ok(result.players[0].equity);
+}
+
+pub fn analyze_call(spot: &CallSpot, rng: &mut impl Rng) -> Result<CallAnalysis, EvError> {
+	if !(spot.pot > 0.0 && spot.to_call > 0.0 && spot.pot.is_finite() && spot.to_call.is_finite()) {
+		return Err(EvError::INVALID_AMOUNTS);
+	}
+
+	let known = CardSet::from_cards(&spot.hand).union(CardSet::from_cards(&spot.board)).union(CardSet::from_cards(&spot.dead));
+	if known.len() != 2 + spot.board.len() + spot.dead.len() {
+		return Err(EvError::EQUITY(EquityError::REPEATING_CARDS));
+	}
+
+	if spot.board.len() > 5 {
+		return Err(EvError::EQUITY(EquityError::TOO_MANY_BOARD_CARDS));
+	}
+
+	let live = spot.range.without(known);
+	if live.is_empty() {
+		return Err(EvError::EQUITY(EquityError::EMPTY_RANGE(1)));
+	}
+
+	let won = spot.pot + spot.to_call;
+	let mut combos = Vec::with_capacity(live.len());
+	for combo in live.combos() {
+		let equity = combo_equity(spot, *combo, rng).map_err(EvError::EQUITY)?;
+		combos.push(ComboEv {combo: *combo, equity, ev: equity * won - spot.to_call});
+	}
+
+	combos.sort_by(|a, b| b.equity.partial_cmp(&a.equity).unwrap());
+
+	// every combo has as many runouts, so the range equity is their mean
+	let equity = combos.iter().map(|c| c.equity).sum::<f64>() / combos.len() as f64;
+	let required_equity = spot.to_call / won;
+	let profitable = combos.iter().filter(|c| c.ev > 0.0).map(|c| c.combo).collect::<Vec<[Card; 2]>>();
+
+	let mut breakeven = None;
+	let mut total = 0.0;
+	for (count, combo) in combos.iter().rev().enumerate() {
+		total += combo.equity;
+		if total / (count + 1) as f64 >= required_equity {
+			let strongest: Vec<[Card; 2]> = combos.iter().rev().take(count + 1).map(|c| c.combo).collect();
+			breakeven = Some(Range::from_combos(&strongest));
+			break;
+		}
+	}
+
+	return Ok(CallAnalysis {
+		equity,
+		required_equity,
+		pot_odds: spot.pot / spot.to_call,
+		call_ev: equity * won - spot.to_call,
+		fold_ev: 0.0,
+		combos,
+		profitable: Range::from_combos(&profitable),
+		breakeven
+	});
+}
@@ -0,0 +1,67 @@
+use super::*;
+
+use std::convert::TryInto;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn spot(pot: f64, to_call: f64, hand: &str, range: &str, board: &str) -> CallSpot {
+	return CallSpot {
+		pot,
+		to_call,
+		hand: Card::parse_list(hand).unwrap()[..].try_into().unwrap(),
+		range: Range::parse(range).unwrap(),
+		board: Card::parse_list(board).unwrap(),
+		dead: Vec::new()
+	};
+}
+
+#[test]
+fn check_river_call() {
+	let mut rng = StdRng::seed_from_u64(3);
+
+	// top pair against three sets and a missed draw
+	let analysis = analyze_call(&spot(100.0, 50.0, "Ah Kh", "99,QsJs", "Kd 9s 5c 2h 3d"), &mut rng).unwrap();
+	assert_eq!(analysis.combos.len(), 4);
+	assert_eq!(analysis.equity, 0.25);
+	assert_eq!(analysis.required_equity, 50.0 / 150.0);
+	assert_eq!(analysis.pot_odds, 2.0);
+	assert_eq!(analysis.call_ev, -12.5);
+	assert_eq!(analysis.should_call(), false);
+	assert_eq!(analysis.combos[0].ev, 100.0);
+	assert_eq!(analysis.combos[3].ev, -50.0);
+	assert_eq!(analysis.profitable.to_string(), "QsJs");
+	assert_eq!(analysis.breakeven.is_none(), true);
+
+	let analysis = analyze_call(&spot(200.0, 50.0, "Ah Kh", "99,QsJs", "Kd 9s 5c 2h 3d"), &mut rng).unwrap();
+	assert_eq!(analysis.should_call(), true);
+	assert_eq!(analysis.breakeven_share(), Some(1.0));
+
+	// two of the four bluffs are enough next to the sets
+	let analysis = analyze_call(&spot(100.0, 50.0, "Ah Kh", "99,QJs", "Kd 9s 5c 2h 3d"), &mut rng).unwrap();
+	assert_eq!(analysis.should_call(), true);
+	let breakeven = analysis.breakeven.unwrap();
+	assert_eq!(breakeven.len(), 5);
+	assert_eq!(breakeven.combos().iter().filter(|c| c[0].rank == c[1].rank).count(), 3);
+}
+
+#[test]
+fn check_earlier_streets() {
+	let mut rng = StdRng::seed_from_u64(3);
+
+	// a flush draw against a set, over all 990 runouts
+	let analysis = analyze_call(&spot(100.0, 100.0, "Ah Kh", "QsQd", "Qh 7h 2c"), &mut rng).unwrap();
+	assert_eq!((analysis.equity * 990.0 - 253.0).abs() < 1e-9, true);
+	assert_eq!(analysis.should_call(), false);
+
+	// sampled before the flop
+	let analysis = analyze_call(&spot(150.0, 100.0, "As Ad", "KK", ""), &mut rng).unwrap();
+	assert_eq!(analysis.combos.len(), 6);
+	assert_eq!((analysis.equity - 0.82).abs() < 0.02, true);
+	assert_eq!(analysis.should_call(), true);
+	assert_eq!(analysis.profitable.len(), 6);
+
+	assert_eq!(analyze_call(&spot(0.0, 100.0, "As Ad", "KK", ""), &mut rng).err(), Some(EvError::INVALID_AMOUNTS));
+	assert_eq!(analyze_call(&spot(100.0, 100.0, "As Ad", "KK", "As 7h 2c"), &mut rng).err(), Some(EvError::EQUITY(EquityError::REPEATING_CARDS)));
+	assert_eq!(analyze_call(&spot(100.0, 100.0, "As Ad", "AhAc", "Ah 7h 2c"), &mut rng).err(), Some(EvError::EQUITY(EquityError::EMPTY_RANGE(1))));
+}
@@ -0,0 +1,376 @@
+use crate::hand::Card;
+use crate::hand::Hand;
+use crate::hand::HandRank;
+use crate::hand::Rank;
+use crate::hand::Suit;
+
+#[cfg(test)]
+mod tests;
+
+// Bit layout: 16 bits per suit (clubs lowest), rank TWO..ACE in bits 0..12.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash)]
+pub struct CardSet(pub u64);
+
+impl CardSet {
+	pub const EMPTY: CardSet = CardSet(0);
+	pub const FULL_DECK: CardSet = CardSet(0x1fff_1fff_1fff_1fff);
+
+	pub fn new() -> CardSet {
+		return CardSet::EMPTY;
+	}
+
+	pub fn from_cards(cards: &[Card]) -> CardSet {
+		let mut set = CardSet::EMPTY;
+		for card in cards {
+			set.insert(*card);
+		}
+
+		return set;
+	}
+
+	fn card_bit(card: Card) -> u64 {
+		return 1u64 << (card.suit.to_int() as u64 * 16 + (card.rank.to_int() - 2) as u64);
+	}
+
+	pub fn insert(&mut self, card: Card) -> bool {
+		let bit = CardSet::card_bit(card);
+		let inserted = self.0 & bit == 0;
+		self.0 |= bit;
+
+		return inserted;
+	}
+
+	pub fn remove(&mut self, card: Card) -> bool {
+		let bit = CardSet::card_bit(card);
+		let removed = self.0 & bit != 0;
+		self.0 &= !bit;
+
+		return removed;
+	}
+
+	pub fn contains(&self, card: Card) -> bool {
+		return self.0 & CardSet::card_bit(card) != 0;
+	}
+
+	pub fn len(&self) -> usize {
+		return self.0.count_ones() as usize;
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.0 == 0;
+	}
+
+	pub fn union(&self, other: CardSet) -> CardSet {
+		return CardSet(self.0 | other.0);
+	}
+
+	pub fn intersects(&self, other: CardSet) -> bool {
+		return self.0 & other.0 != 0;
+	}
+
+	pub fn difference(&self, other: CardSet) -> CardSet {
+		return CardSet(self.0 & !other.0);
+	}
+
+	pub fn suit_mask(&self, suit: Suit) -> u16 {
+		return ((self.0 >> (suit.to_int() as u64 * 16)) & 0x1fff) as u16;
+	}
+
+	pub fn rank_mask(&self) -> u16 {
+		return self.suit_mask(Suit::CLUBS) | self.suit_mask(Suit::DIAMONDS) |
+			self.suit_mask(Suit::HEARTS) | self.suit_mask(Suit::SPADES);
+	}
+
+	pub fn iter(&self) -> CardSetIter {
+		return CardSetIter { bits: self.0 };
+	}
+}
+
+pub struct CardSetIter {
+	bits: u64
+}
+
+impl Iterator for CardSetIter {
+	type Item = Card;
+
+	fn next(&mut self) -> Option<Card> {
+		if self.bits == 0 {
+			return None;
+		}
+
+		let bit = self.bits.trailing_zeros();
+		self.bits &= self.bits - 1;
+
+		return Some(Card {
+			suit: Suit::iterator().as_slice()[(bit / 16) as usize],
+			rank: Rank::iterator().as_slice()[(bit % 16) as usize]
+		});
+	}
+}
+
+// Highest rank index + 1 of the best straight contained in a rank mask, 0 if none.
+static STRAIGHT_HIGH: [u8; 8192] = build_straight_table();
+
+const fn build_straight_table() -> [u8; 8192] {
+	let mut table = [0u8; 8192];
+	let mut mask = 0usize;
+	while mask < 8192 {
+		let mut high = 12usize;
+		while high >= 4 {
+			let pattern = 0b11111usize << (high - 4);
+			if mask & pattern == pattern {
+				table[mask] = high as u8 + 1;
+				break;
+			}
+
+			high -= 1;
+		}
+
+		// ACE as ONE
+		if table[mask] == 0 && mask & 0b1000000001111 == 0b1000000001111 {
+			table[mask] = 4;
+		}
+
+		mask += 1;
+	}
+
+	return table;
+}
+
+// High rank index of the best straight in a 13 bit rank mask (TWO in bit 0), 3 for the wheel
+pub fn straight_high(mask: u16) -> Option<u8> {
+	return STRAIGHT_HIGH[mask as usize].checked_sub(1);
+}
+
+const CATEGORY_SHIFT: u32 = 26;
+const MAJOR_SHIFT: u32 = 13;
+
+// Category in the top bits, then the deciding rank masks, so that plain
+// integer comparison orders hands by strength.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Ord, PartialOrd, Hash)]
+pub struct HandValue(pub u32);
+
+impl HandValue {
+	fn new(category: HandRank, major: u16, minor: u16) -> HandValue {
+		let category_int = 9 - category.to_int() as u32;
+		return HandValue((category_int << CATEGORY_SHIFT) | ((major as u32) << MAJOR_SHIFT) | minor as u32);
+	}
+
+	pub fn category(&self) -> HandRank {
+		match self.0 >> CATEGORY_SHIFT {
+			8 => HandRank::STRAIGHT_FLUSH,
+			7 => HandRank::FOUR_OF_A_KIND,
+			6 => HandRank::FULL_HOUSE,
+			5 => HandRank::FLUSH,
+			4 => HandRank::STRAIGHT,
+			3 => HandRank::THREE_OF_A_KIND,
+			2 => HandRank::TWO_PAIRS,
+			1 => HandRank::PAIR,
+			_ => HandRank::HIGH_CARD
+		}
+	}
+
+	pub fn major_ranks(&self) -> u16 {
+		return ((self.0 >> MAJOR_SHIFT) & 0x1fff) as u16;
+	}
+
+	pub fn minor_ranks(&self) -> u16 {
+		return (self.0 & 0x1fff) as u16;
+	}
+}
+
+fn highest_bit(mask: u16) -> u16 {
+	if mask == 0 {
+		return 0;
+	}
+
+	return 1u16 << (15 - mask.leading_zeros());
+}
+
+fn top_bits(mask: u16, n: u32) -> u16 {
+	let mut result = mask;
+	while result.count_ones() > n {
+		result &= result - 1;
+	}
+
+	return result;
+}
+
+pub fn evaluate(cards: CardSet) -> HandValue {
+	let suits = [
+		cards.suit_mask(Suit::CLUBS),
+		cards.suit_mask(Suit::DIAMONDS),
+		cards.suit_mask(Suit::HEARTS),
+		cards.suit_mask(Suit::SPADES)
+	];
+
+	let mut flush_mask = 0u16;
+	for suit_mask in &suits {
+		if suit_mask.count_ones() >= 5 {
+			let straight_high = STRAIGHT_HIGH[*suit_mask as usize];
+			if straight_high != 0 {
+				return HandValue::new(HandRank::STRAIGHT_FLUSH, 1u16 << (straight_high - 1), 0);
+			}
+
+			if *suit_mask > flush_mask {
+				flush_mask = *suit_mask;
+			}
+		}
+	}
+
+	let all = suits[0] | suits[1] | suits[2] | suits[3];
+	let quads = suits[0] & suits[1] & suits[2] & suits[3];
+	let mut threes = 0u16;
+	let mut twos = 0u16;
+	let mut r = 0;
+	while r < 13 {
+		let count = suits.iter().filter(|m| *m & (1u16 << r) != 0).count();
+		if count == 3 {
+			threes |= 1u16 << r;
+		} else if count == 2 {
+			twos |= 1u16 << r;
+		}
+
+		r += 1;
+	}
+
+	if quads != 0 {
+		let quad = highest_bit(quads);
+		return HandValue::new(HandRank::FOUR_OF_A_KIND, quad, highest_bit(all & !quad));
+	}
+
+	if threes != 0 {
+		let trips = highest_bit(threes);
+		let pair = highest_bit((threes & !trips) | twos);
+		if pair != 0 {
+			return HandValue::new(HandRank::FULL_HOUSE, trips, pair);
+		}
+	}
+
+	if flush_mask != 0 {
+		return HandValue::new(HandRank::FLUSH, top_bits(flush_mask, 5), 0);
+	}
+
+	let straight_high = STRAIGHT_HIGH[all as usize];
+	if straight_high != 0 {
+		return HandValue::new(HandRank::STRAIGHT, 1u16 << (straight_high - 1), 0);
+	}
+
+	if threes != 0 {
+		let trips = highest_bit(threes);
+		return HandValue::new(HandRank::THREE_OF_A_KIND, trips, top_bits(all & !trips, 2));
+	}
+
+	if twos.count_ones() >= 2 {
+		let pairs = top_bits(twos, 2);
+		return HandValue::new(HandRank::TWO_PAIRS, pairs, highest_bit(all & !pairs));
+	}
+
+	if twos != 0 {
+		return HandValue::new(HandRank::PAIR, twos, top_bits(all & !twos, 3));
+	}
+
+	return HandValue::new(HandRank::HIGH_CARD, top_bits(all, 5), 0);
+}
+
+pub fn evaluate_cards(cards: &[Card]) -> HandValue {
+	return evaluate(CardSet::from_cards(cards));
+}
+
+pub fn evaluate_hand(hand: &Hand) -> HandValue {
+	return evaluate_cards(&hand.cards);
+}
+
+pub fn best_hand(cards: &[Card]) -> Hand {
+	assert!(cards.len() >= 5, "At least five cards are required!");
+
+	let mut best = Hand {cards: [cards[0], cards[1], cards[2], cards[3], cards[4]]};
+	let mut best_value = evaluate_hand(&best);
+	for a in 0..cards.len() {
+		for b in a+1..cards.len() {
+			for c in b+1..cards.len() {
+				for d in c+1..cards.len() {
+					for e in d+1..cards.len() {
+						let hand = Hand {cards: [cards[a], cards[b], cards[c], cards[d], cards[e]]};
+						let value = evaluate_hand(&hand);
+						if value > best_value {
+							best = hand;
+							best_value = value;
+						}
+					}
+				}
+			}
+		}
+	}
+
+	return best;
+}
+
+// Omaha hands use exactly two hole cards and three board cards.
+pub fn best_omaha_hand(hole: &[Card], board: &[Card]) -> Hand {
+	assert!(hole.len() >= 2 && board.len() >= 3, "Not enough cards for an Omaha hand!");
+
+	let mut best: Option<(HandValue, Hand)> = None;
+	for a in 0..hole.len() {
+		for b in a+1..hole.len() {
+			for c in 0..board.len() {
+				for d in c+1..board.len() {
+					for e in d+1..board.len() {
+						let hand = Hand {cards: [hole[a], hole[b], board[c], board[d], board[e]]};
+						let value = evaluate_hand(&hand);
+						if best.is_none() || value > best.unwrap().0 {
+							best = Some((value, hand));
+						}
+					}
+				}
+			}
+		}
+	}
+
+	return best.unwrap().1;
+}
+
+// Position of the value among the 7462 distinct five-card hands, from 1 for a
+// royal flush to 7462 for 7-5-4-3-2 offsuit.
+pub fn equivalence_class(value: HandValue) -> u16 {
+	let ranks = Rank::iterator().as_slice();
+	let suits = Suit::iterator().as_slice();
+	let mut better = 0u16;
+
+	for a in 0..13 {
+		for b in a..13 {
+			for c in b..13 {
+				for d in c..13 {
+					for e in d..13 {
+						if a == e {
+							continue;
+						}
+
+						// cycling the suits never makes a flush
+						let indices = [a, b, c, d, e];
+						let mut cards = [Card {rank: Rank::TWO, suit: Suit::CLUBS}; 5];
+						for i in 0..5 {
+							cards[i] = Card {rank: ranks[indices[i]], suit: suits[i % 4]};
+						}
+
+						if evaluate_cards(&cards) > value {
+							better += 1;
+						}
+
+						if a < b && b < c && c < d && d < e {
+							for card in cards.iter_mut() {
+								card.suit = Suit::CLUBS;
+							}
+
+							if evaluate_cards(&cards) > value {
+								better += 1;
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	return better + 1;
+}
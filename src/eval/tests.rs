@@ -0,0 +1,156 @@
+use super::*;
+
+fn cards(s: &str) -> CardSet {
+	let mut set = CardSet::new();
+	for card_string in s.split(" ") {
+		set.insert(Card::new_from_string(card_string.to_string()));
+	}
+
+	return set;
+}
+
+#[test]
+fn check_card_set() {
+	let mut set = CardSet::new();
+	assert_eq!(set.is_empty(), true);
+
+	let ace = Card::new_from_string("As".to_string());
+	assert_eq!(set.insert(ace), true);
+	assert_eq!(set.insert(ace), false);
+	assert_eq!(set.contains(ace), true);
+	assert_eq!(set.len(), 1);
+	assert_eq!(set.suit_mask(Suit::SPADES), 0b1000000000000);
+	assert_eq!(set.suit_mask(Suit::HEARTS), 0);
+
+	assert_eq!(set.remove(ace), true);
+	assert_eq!(set.remove(ace), false);
+	assert_eq!(set.is_empty(), true);
+
+	assert_eq!(CardSet::FULL_DECK.len(), 52);
+	assert_eq!(CardSet::FULL_DECK.iter().count(), 52);
+	for card in CardSet::FULL_DECK.iter() {
+		assert_eq!(Card::from_index(card.to_index()) == card, true);
+	}
+}
+
+#[test]
+fn check_five_card_categories() {
+	let deck: Vec<Card> = CardSet::FULL_DECK.iter().collect();
+	let mut counts = [0u32; 9];
+	let mut values = Vec::new();
+
+	for a in 0..deck.len() {
+		for b in a+1..deck.len() {
+			for c in b+1..deck.len() {
+				for d in c+1..deck.len() {
+					for e in d+1..deck.len() {
+						let value = evaluate_cards(&[deck[a], deck[b], deck[c], deck[d], deck[e]]);
+						counts[(value.category().to_int() - 1) as usize] += 1;
+						values.push(value);
+					}
+				}
+			}
+		}
+	}
+
+	assert_eq!(counts, [40, 624, 3744, 5108, 10200, 54912, 123552, 1098240, 1302540]);
+
+	values.sort();
+	values.dedup();
+	assert_eq!(values.len(), 7462);
+}
+
+#[test]
+fn check_seven_card_evaluation() {
+	let v = evaluate(cards("As Ks Qs Js Ts 2c 2d"));
+	assert_eq!(v.category(), HandRank::STRAIGHT_FLUSH);
+
+	let v = evaluate(cards("Ah 2h 3h 4h 5h 6c 7d"));
+	assert_eq!(v.category(), HandRank::STRAIGHT_FLUSH);
+	assert_eq!(v < evaluate(cards("2h 3h 4h 5h 6h Kc Kd")), true);
+
+	let v = evaluate(cards("9c 9d 9h 9s Kd Kc Ks"));
+	assert_eq!(v.category(), HandRank::FOUR_OF_A_KIND);
+	assert_eq!(v == evaluate(cards("9c 9d 9h 9s Kd 2c 3s")), true);
+
+	let v = evaluate(cards("Kc Kd Ks 9c 9d 9h 2s"));
+	assert_eq!(v.category(), HandRank::FULL_HOUSE);
+	assert_eq!(v > evaluate(cards("Kc Kd Ks 8c 8d 8h As")), true);
+
+	let v = evaluate(cards("2c 5c 7c 9c Jc Qc Ad"));
+	assert_eq!(v.category(), HandRank::FLUSH);
+	assert_eq!(v == evaluate(cards("3c 5c 7c 9c Jc Qc Ad")), true);
+
+	let v = evaluate(cards("Ac 2d 3h 4s 5c Kd Kh"));
+	assert_eq!(v.category(), HandRank::STRAIGHT);
+	assert_eq!(v < evaluate(cards("2d 3h 4s 5c 6c Kd Kh")), true);
+
+	let v = evaluate(cards("Tc Td 8s 8h 4c 4d Ah"));
+	assert_eq!(v.category(), HandRank::TWO_PAIRS);
+	assert_eq!(v == evaluate(cards("Tc Td 8s 8h 2c 2d Ah")), true);
+
+	let v = evaluate(cards("Tc Td 8s 7h 4c 3d 2h"));
+	assert_eq!(v.category(), HandRank::PAIR);
+	assert_eq!(v == evaluate(cards("Tc Td 8s 7h 4c 2d 3h")), true);
+
+	let v = evaluate(cards("Ac Jd 8s 7h 4c 3d 2h"));
+	assert_eq!(v.category(), HandRank::HIGH_CARD);
+}
+
+#[test]
+fn check_agrees_with_hand_compare() {
+	let pairs = [
+		("3h 4c 6c Td Tc", "3h 4c 6c 7d 9c"),
+		("3h Th 6c Td Tc", "3h 3c 6c Td Tc"),
+		("6s 7s 2s 9s Ts", "6s 7s 8c 9s Ts"),
+		("5s 9h 9d Qc 2d", "5s 8h 8d Kc 2d"),
+		("6s Th Td 6c 3d", "6s Th Td 6c 2d"),
+		("3s Th Td Tc 3d", "2s Th Td Tc 2d"),
+		("As Ah Ad Ac Qd", "Ts Th Td Tc Kd")
+	];
+
+	for (h, o) in pairs.iter() {
+		let hand = Hand::new_from_string(h.to_string());
+		let other = Hand::new_from_string(o.to_string());
+		assert_eq!(hand > other, true);
+		assert_eq!(evaluate_hand(&hand) > evaluate_hand(&other), true);
+	}
+}
+
+#[test]
+fn check_best_hand() {
+	let seven: Vec<Card> = cards("Ah 2h 9c 3h 4h Kd 5h").iter().collect();
+	let best = best_hand(&seven);
+	assert_eq!(evaluate_hand(&best), evaluate(cards("Ah 2h 3h 4h 5h")));
+
+	let hole: Vec<Card> = cards("Ah Kh 2c 3d").iter().collect();
+	let board: Vec<Card> = cards("Qh Jh Th 9h 8h").iter().collect();
+	let best = best_omaha_hand(&hole, &board);
+	assert_eq!(evaluate_hand(&best).category(), HandRank::STRAIGHT_FLUSH);
+	assert_eq!(evaluate_hand(&best), evaluate(cards("Ah Kh Qh Jh Th")));
+
+	let hole: Vec<Card> = cards("As 2c 3d 4h").iter().collect();
+	let board: Vec<Card> = cards("Ks Qs Js Ts 9d").iter().collect();
+	assert_eq!(evaluate_hand(&best_omaha_hand(&hole, &board)).category(), HandRank::HIGH_CARD);
+}
+
+#[test]
+fn check_equivalence_class() {
+	assert_eq!(equivalence_class(evaluate(cards("Ah Kh Qh Jh Th"))), 1);
+	assert_eq!(equivalence_class(evaluate(cards("5d 4d 3d 2d Ad"))), 10);
+	assert_eq!(equivalence_class(evaluate(cards("As Ah Ad Ac Ks"))), 11);
+	assert_eq!(equivalence_class(evaluate(cards("7h 5d 4c 3s 2h"))), 7462);
+	assert_eq!(equivalence_class(evaluate(cards("Ah Kh Qh Jh Th 2c 2d"))), 1);
+
+	let low = equivalence_class(evaluate(cards("2c 2d 3h 4s 5c")));
+	let high = equivalence_class(evaluate(cards("2c 2d 3h 4s 6c")));
+	assert_eq!(high + 1, low);
+}
+
+#[test]
+fn check_straight_high() {
+	assert_eq!(straight_high(cards("Ah Kh Qh Jh Th 2c").rank_mask()), Some(12));
+	assert_eq!(straight_high(cards("9c 8d 7h 6s 5c 4c").rank_mask()), Some(7));
+	assert_eq!(straight_high(cards("Ac 2d 3h 4s 5c").rank_mask()), Some(3));
+	assert_eq!(straight_high(cards("Ac 2d 3h 4s 6c").rank_mask()), None);
+}
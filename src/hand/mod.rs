@@ -1,8 +1,10 @@
-use std::cmp::Ordering;
-use std::slice::Iter;
+use core::cmp::Ordering;
+use core::fmt;
+use core::fmt::Write;
+use core::hash::Hash;
+use core::hash::Hasher;
+use core::slice::Iter;
 
-use fnv::FnvHashMap;
-use fnv::FnvHashSet;
 
 #[cfg(test)]
 mod tests;
@@ -27,6 +29,43 @@ impl Suit {
 		}
 	}
 
+	pub fn to_int(&self) -> u8 {
+		match self {
+			Suit::CLUBS => 		0,
+			Suit::DIAMONDS => 	1,
+			Suit::HEARTS => 	2,
+			Suit::SPADES => 	3
+		}
+	}
+
+	pub fn symbol(&self) -> &'static str {
+		match self {
+			Suit::CLUBS => 		"\u{2663}",
+			Suit::DIAMONDS => 	"\u{2666}",
+			Suit::HEARTS => 	"\u{2665}",
+			Suit::SPADES => 	"\u{2660}"
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Suit::CLUBS => 		"Clubs",
+			Suit::DIAMONDS => 	"Diamonds",
+			Suit::HEARTS => 	"Hearts",
+			Suit::SPADES => 	"Spades"
+		}
+	}
+
+	// ANSI foreground of the four colour deck, spades in the terminal's own colour
+	pub fn colour(&self) -> &'static str {
+		match self {
+			Suit::CLUBS => 		"\x1b[32m",
+			Suit::DIAMONDS => 	"\x1b[34m",
+			Suit::HEARTS => 	"\x1b[31m",
+			Suit::SPADES => 	"\x1b[39m"
+		}
+	}
+
 	pub fn iterator() -> Iter<'static, Suit> {
         static SUIT: [Suit;  4] = [Suit::CLUBS,Suit::DIAMONDS,Suit::HEARTS,Suit::SPADES];
         SUIT.iter()
@@ -107,6 +146,24 @@ impl Rank {
 		}
 	}
 
+	pub fn name(&self) -> &'static str {
+		match self {
+			Rank::TWO => 	"Two",
+			Rank::THREE => 	"Three",
+			Rank::FOUR => 	"Four",
+			Rank::FIVE => 	"Five",
+			Rank::SIX => 	"Six",
+			Rank::SEVEN => 	"Seven",
+			Rank::EIGHT => 	"Eight",
+			Rank::NINE => 	"Nine",
+			Rank::TEN => 	"Ten",
+			Rank::JACK => 	"Jack",
+			Rank::QUEEN => 	"Queen",
+			Rank::KING => 	"King",
+			Rank::ACE => 	"Ace"
+		}
+	}
+
 	pub fn iterator() -> Iter<'static, Rank> {
         static RANK: [Rank; 13] = [Rank::TWO,Rank::THREE,Rank::FOUR,Rank::FIVE,
         	Rank::SIX,Rank::SEVEN,Rank::EIGHT,Rank::NINE,Rank::TEN,Rank::JACK,
@@ -153,7 +210,60 @@ impl PartialEq for Card {
 
 impl Eq for Card { }
 
+impl Hash for Card {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.to_index().hash(state);
+	}
+}
+
+const RESET: &str = "\x1b[0m";
+
+// Room for the longest card name, so it can be padded without allocating
+struct Buffer {
+	bytes: [u8; 24],
+	len: usize
+}
+
+impl Write for Buffer {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		let end = self.len + s.len();
+		if end > self.bytes.len() {
+			return Err(fmt::Error);
+		}
+
+		self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+		self.len = end;
+		return Ok(());
+	}
+}
+
+// Formatter flags pick the form: "{}" gives As, "{:#}" A\u{2660} and "{:-}"
+// Ace of Spades, while "{:+}" adds four colour ANSI codes to any of them.
+// Width and alignment apply to a single card.
+impl fmt::Display for Card {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut text = Buffer {bytes: [0; 24], len: 0};
+		self.write_plain(&mut text, f.alternate(), f.sign_minus())?;
+		let text = core::str::from_utf8(&text.bytes[..text.len]).map_err(|_| fmt::Error)?;
+
+		if f.sign_plus() {
+			f.write_str(self.suit.colour())?;
+			f.pad(text)?;
+			return f.write_str(RESET);
+		}
+
+		return f.pad(text);
+	}
+}
+
+impl fmt::Debug for Card {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return self.write_plain(f, false, false);
+	}
+}
+
 impl Card {
+	#[cfg(feature = "std")]
 	pub fn new_from_string(s: String) -> Card {
 		let mut card_chars = s.chars();
 		let rank_char = card_chars.next().unwrap();
@@ -186,6 +296,32 @@ impl Card {
 		};
 	}
 
+	// Same format as new_from_string, but returns None on bad input
+	pub fn parse(s: &str) -> Option<Card> {
+		let mut card_chars = s.chars();
+		let rank_char = card_chars.next()?;
+		let suit_char = card_chars.next()?;
+		if card_chars.next().is_some() {
+			return None;
+		}
+
+		let rank = Rank::iterator().find(|r| r.to_string().starts_with(rank_char))?;
+		let suit = Suit::iterator().find(|s| s.to_string().starts_with(suit_char))?;
+
+		return Some(Card {suit: *suit, rank: *rank});
+	}
+
+	// Several cards, written together or apart: "AhKh", "Ah Kh" or "Ah,Kh"
+	#[cfg(feature = "std")]
+	pub fn parse_list(s: &str) -> Option<Vec<Card>> {
+		let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+		if chars.len() % 2 != 0 {
+			return None;
+		}
+
+		return chars.chunks(2).map(|pair| Card::parse(&pair.iter().collect::<String>())).collect();
+	}
+
 	fn to_byte_int(&self) -> (u8, u16) {
 		let mut byte_int_suit = 0u8;
 		let mut byte_int_rank = 0u16;
@@ -217,9 +353,24 @@ impl Card {
 		return (byte_int_suit, byte_int_rank);
 	}
 	
-	#[allow(dead_code)]
-	pub fn to_string(&self) -> String {
-		return format!("{}{}", self.rank.to_string(), self.suit.to_string());
+	fn write_plain(&self, out: &mut impl Write, unicode: bool, long: bool) -> fmt::Result {
+		if long {
+			return write!(out, "{} of {}", self.rank.name(), self.suit.name());
+		}
+
+		let suit = if unicode { self.suit.symbol() } else { self.suit.to_string() };
+		return write!(out, "{}{}", self.rank.to_string(), suit);
+	}
+
+	pub fn to_index(&self) -> u8 {
+		return self.suit.to_int() * 13 + self.rank.to_int() - 2;
+	}
+
+	pub fn from_index(index: u8) -> Card {
+		return Card {
+			suit: Suit::iterator().as_slice()[(index / 13) as usize],
+			rank: Rank::iterator().as_slice()[(index % 13) as usize]
+		};
 	}
 
 	fn to_special_int(&self) -> u32 {
@@ -296,6 +447,12 @@ impl Card {
 	}
 }
 
+// Space separated cards such as "As Kd", for the tests of every module
+#[cfg(all(test, feature = "std"))]
+pub(crate) fn cards(s: &str) -> Vec<Card> {
+	return s.split(' ').map(|c| Card::new_from_string(c.to_string())).collect();
+}
+
 #[allow(dead_code)]
 #[allow(non_camel_case_types)]
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -369,18 +526,44 @@ impl PartialOrd for Hand {
 impl PartialEq for Hand {
 	fn eq(&self, other: &Self) -> bool {
 		let exact_match = self.cards.iter().zip(other.cards.iter()).all(|(a,b)| a == b);
-		if exact_match || self.compare(other) == Ordering::Equal {
-			return true;
-		} else {
-			return false;
-		}
+		return exact_match || self.compare(other) == Ordering::Equal;
 	}
 }
 
 impl Eq for Hand { }
 
+// The cards in order with the flags of Card, long names separated by commas
+impl fmt::Display for Hand {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let separator = if f.sign_minus() { ", " } else { " " };
+		for (i, card) in self.cards.iter().enumerate() {
+			if i > 0 {
+				f.write_str(separator)?;
+			}
+
+			if f.sign_plus() {
+				f.write_str(card.suit.colour())?;
+			}
+
+			card.write_plain(f, f.alternate(), f.sign_minus())?;
+			if f.sign_plus() {
+				f.write_str(RESET)?;
+			}
+		}
+
+		return Ok(());
+	}
+}
+
+impl fmt::Debug for Hand {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return write!(f, "{}", self);
+	}
+}
+
 impl Hand {
 	#[allow(dead_code)]
+	#[cfg(feature = "std")]
 	pub fn new_from_string(s: String) -> Hand {
 		let mut cardsv: Vec<Card> = Vec::new();
 		for card_string in s.split(" ") {
@@ -398,21 +581,9 @@ impl Hand {
 		}
 	}
 
-	#[allow(dead_code)]
-	pub fn to_string(&self) -> String {
-		let mut result_string = "".to_string();
-		let mut space = "".to_string();
-
-		for card in &self.cards {
-			result_string += &(space + &card.to_string());
-			space = " ".to_string();
-		}
-
-		return result_string;
-	}
-	
+	#[cfg(feature = "std")]
 	pub fn to_ordered_string(&self) -> String {
-		let mut ordered_cards = self.cards.clone();
+		let mut ordered_cards = self.cards;
 		ordered_cards.sort();
 		let ordered_hand = Hand {cards: ordered_cards};
 		return ordered_hand.to_string();
@@ -429,27 +600,23 @@ impl Hand {
 
 	#[allow(dead_code)]
 	pub fn check_repeating_cards(&self) -> bool {
-		let mut uniq = FnvHashSet::default();
+		let mut uniq = 0u64;
 
 		for card in &self.cards {
-			let card_byte_int = card.to_byte_int();
-			let card_byte_int_combined = ((card_byte_int.0 as u32) << 13) + card_byte_int.1 as u32;
-
-			uniq.insert(card_byte_int_combined);
+			uniq |= 1u64 << card.to_index();
 		}
 
-		if uniq.len() == 5 {
+		if uniq.count_ones() == 5 {
 			return false;
 		}
 
 		return true;
 	}
 
-	pub fn check_same_kind(&self) -> FnvHashMap<u16, u8> {
-		let mut rank_freq = FnvHashMap::with_capacity_and_hasher(5, Default::default());
+	pub fn check_same_kind(&self) -> [u8; 13] {
+		let mut rank_freq = [0u8; 13];
 		for card in &self.cards {
-			let freq = rank_freq.entry(card.to_byte_int().1).or_insert(0);
-    		*freq += 1;
+			rank_freq[(card.rank.to_int() - 2) as usize] += 1;
 		}
 
 		return rank_freq;
@@ -500,7 +667,7 @@ impl Hand {
 					}
 				);
 			} else {
-				straight_pattern = straight_pattern << 1;
+				straight_pattern <<= 1;
 			}
 
 			j += 1;
@@ -514,40 +681,58 @@ impl Hand {
 		return (false, Rank::TWO);
 	}
 
-	fn get_hand_rank(&self) -> (HandRank, Option<FnvHashMap<u16, u8>>) {
+	// Rank masks of the cards appearing four, three, two and one times, in
+	// that order, so that two hands of the same HandRank compare lexicographically.
+	fn get_rank_groups(&self) -> [u16; 4] {
+		let mut seen = [0u16; 4];
+		for card in &self.cards {
+			let rank_byte = card.to_byte_int().1;
+			let mut i = 0;
+			while i < 3 && seen[i] & rank_byte != 0 {
+				i += 1;
+			}
+
+			seen[i] |= rank_byte;
+		}
+
+		return [seen[3], seen[2] & !seen[3], seen[1] & !seen[2], seen[0] & !seen[1]];
+	}
+
+	fn get_hand_rank(&self) -> (HandRank, [u16; 4]) {
+		let mut groups = self.get_rank_groups();
+		let straight = self.check_straight().0;
+
+		// ACE as ONE ranks below SIX-high
+		if straight && groups[3] == 0b1000000001111u16 {
+			groups[3] = 0b1111u16;
+		}
+
 		if self.check_flush() {
-			if self.check_straight().0 {
-				return (HandRank::STRAIGHT_FLUSH, None);
+			if straight {
+				return (HandRank::STRAIGHT_FLUSH, groups);
 			} else {
-				return (HandRank::FLUSH, None);
+				return (HandRank::FLUSH, groups);
 			}
+		} else if straight {
+			return (HandRank::STRAIGHT, groups);
+		} else if groups[0] != 0 {
+			return (HandRank::FOUR_OF_A_KIND, groups);
+		} else if groups[1] != 0 && groups[2] != 0 {
+			return (HandRank::FULL_HOUSE, groups);
+		} else if groups[1] != 0 {
+			return (HandRank::THREE_OF_A_KIND, groups);
+		} else if groups[2].count_ones() == 2 {
+			return (HandRank::TWO_PAIRS, groups);
+		} else if groups[2] != 0 {
+			return (HandRank::PAIR, groups);
 		} else {
-			if self.check_straight().0 {
-				return (HandRank::STRAIGHT, None);
-			} else {
-				let same_kind = self.check_same_kind();
-				let freq = self.get_freq_tuple(&same_kind);
-
-				if freq.0 {
-					return (HandRank::FOUR_OF_A_KIND, Some(same_kind));
-				} else if freq.1 && (freq.2 == 1) {
-					return (HandRank::FULL_HOUSE, Some(same_kind));
-				} else if freq.1 {
-					return (HandRank::THREE_OF_A_KIND, Some(same_kind));
-				} else if freq.2 == 2 {
-					return (HandRank::TWO_PAIRS, Some(same_kind));
-				} else if freq.2 == 1 {
-					return (HandRank::PAIR, Some(same_kind));
-				} else {
-					return (HandRank::HIGH_CARD, Some(same_kind));
-				}
-			}
+			return (HandRank::HIGH_CARD, groups);
 		}
 	}
 
-	fn get_freq_tuple(&self, freq_map: &FnvHashMap<u16, u8>) -> (bool, bool, u8) {
+	fn get_freq_tuple(&self, freq_map: &[u8; 13]) -> (bool, bool, u8) {
 		let mut freq = (false,false,0);
-		for (_, rank) in freq_map {
+		for rank in freq_map {
 			if *rank == 4 {
 				freq.0 = true;
 			}
@@ -565,82 +750,15 @@ impl Hand {
 	}
 
 	fn compare(&self, other: &Hand) -> Ordering {
-		let self_hand_rank = self.get_hand_rank().0;
-		let other_hand_rank = other.get_hand_rank().0;
+		let self_hand_rank = self.get_hand_rank();
+		let other_hand_rank = other.get_hand_rank();
 
-		if self_hand_rank < other_hand_rank {
+		if self_hand_rank.0 < other_hand_rank.0 {
 			return Ordering::Greater;
-		} else if self_hand_rank > other_hand_rank {
+		} else if self_hand_rank.0 > other_hand_rank.0 {
 			return Ordering::Less;
 		} else {
-			if self_hand_rank == HandRank::STRAIGHT_FLUSH ||
-				self_hand_rank == HandRank::FLUSH ||
-				self_hand_rank == HandRank::STRAIGHT ||
-				self_hand_rank == HandRank::HIGH_CARD {
-
-					let mut self_combined_ranks = 0u16;
-					for card in &self.cards {
-						self_combined_ranks |= card.to_byte_int().1;
-					}
-
-					let mut other_combined_ranks = 0u16;
-					for card in &other.cards {
-						other_combined_ranks |= card.to_byte_int().1;
-					}
-
-					if self_combined_ranks > other_combined_ranks {
-						return Ordering::Greater;
-					} else if self_combined_ranks < other_combined_ranks {
-						return Ordering::Less;
-					} else {
-						return Ordering::Equal;
-					}
-			} else {
-				let mut combos = [(0u16, 0u16), (0u16, 0u16), (0u16, 0u16), (0u16, 0u16)];
-
-				for (scard, sval) in self.get_hand_rank().1.unwrap() {
-					combos[(4-sval) as usize].0 |= scard;
-				}
-
-				for (ocard, oval) in other.get_hand_rank().1.unwrap() {
-					combos[(4-oval) as usize].1 |= ocard;
-				}
-
-				let mut i = 0;
-				let mut j;
-				while i < 4 {
-					if combos[i].0 == 0 {
-						i += 1;
-						continue;
-					}
-
-					if combos[i].0 > combos[i].1 {
-						return Ordering::Greater;
-					} else if combos[i].0 < combos[i].1 {
-						return Ordering::Less;
-					} else {
-						j = i + 1;
-						while j < 4 {
-							if combos[j].0 == 0 {
-								j += 1;
-								continue;
-							}
-
-							if combos[j].0 > combos[j].1 {
-								return Ordering::Greater;
-							} else if combos[j].0 < combos[j].1 {
-								return Ordering::Less;
-							}
-
-							j += 1;
-						}
-					}
-
-					i += 1;
-				}
-
-				return Ordering::Equal;
-			}
+			return self_hand_rank.1.cmp(&other_hand_rank.1);
 		}
 	}
-}
\ No newline at end of file
+}
@@ -284,4 +284,90 @@ fn check_hand_compare() {
 	let h = Hand::new_from_string("Ts Th Td Tc Kd".to_string());
 	let o = Hand::new_from_string("As Ah Ad Ac Qs".to_string());
 	assert_eq!(h > o, false);
-}
\ No newline at end of file
+}
+
+#[test]
+fn check_same_kind_counts() {
+	let h = Hand::new_from_string("Ac Ad 5s 5h Ah".to_string());
+	let counts = h.check_same_kind();
+	assert_eq!(counts[12], 3);
+	assert_eq!(counts[3], 2);
+	assert_eq!(counts.iter().map(|c| *c as u32).sum::<u32>(), 5);
+}
+
+#[test]
+fn check_wheel_compare() {
+	let h = Hand::new_from_string("2h 3c 4d 5c 6c".to_string());
+	let o = Hand::new_from_string("2h 3c 4d Ac 5c".to_string());
+	assert_eq!(h > o, true);
+
+	let h = Hand::new_from_string("2c 3c 4c 5c 6c".to_string());
+	let o = Hand::new_from_string("2c 3c 4c Ac 5c".to_string());
+	assert_eq!(h > o, true);
+
+	let h = Hand::new_from_string("2h 3c 4d Ac 5c".to_string());
+	let o = Hand::new_from_string("Ah As Kd Kc Qc".to_string());
+	assert_eq!(h > o, true);
+}
+
+#[test]
+fn check_matches_eval() {
+	let hands = [
+		"2h 3c 4d Ac 5c", "2h 3c 4d 5c 6c", "Tc Jd Qh Ks Ac", "2c 3c 4c Ac 5c",
+		"Ac Ad 5s 5h Ah", "5c 5d As Ah 5h", "7c 7d 7h Ks 2c", "Kc Kd 2h 2s 3c",
+		"Kc Kd 2h 2s 4c", "9c 9d Ah Ks Qc", "Ac Kd Qh Js 9c", "2c 4c 6c 8c Tc"
+	];
+
+	for h in hands.iter() {
+		for o in hands.iter() {
+			let hand = Hand::new_from_string(h.to_string());
+			let other = Hand::new_from_string(o.to_string());
+			assert_eq!(hand.cmp(&other), crate::eval::evaluate_hand(&hand).cmp(&crate::eval::evaluate_hand(&other)));
+		}
+	}
+}
+
+#[test]
+fn check_card_parse() {
+	assert_eq!(Card::parse("As") == Some(Card::new_from_string("As".to_string())), true);
+	assert_eq!(Card::parse("Td") == Some(Card {suit: Suit::DIAMONDS, rank: Rank::TEN}), true);
+	assert_eq!(Card::parse("A").is_none(), true);
+	assert_eq!(Card::parse("Asx").is_none(), true);
+	assert_eq!(Card::parse("1s").is_none(), true);
+	assert_eq!(Card::parse("Ax").is_none(), true);
+
+	let cards = vec![Card::new_from_string("Ah".to_string()), Card::new_from_string("Kh".to_string())];
+	assert_eq!(Card::parse_list("AhKh") == Some(cards.clone()), true);
+	assert_eq!(Card::parse_list(" Ah, Kh ") == Some(cards), true);
+	assert_eq!(Card::parse_list("") == Some(Vec::new()), true);
+	assert_eq!(Card::parse_list("AhK").is_none(), true);
+	assert_eq!(Card::parse_list("AhKx").is_none(), true);
+}
+
+#[test]
+fn check_card_format() {
+	let card = Card::new_from_string("As".to_string());
+	assert_eq!(format!("{}", card), "As");
+	assert_eq!(card.to_string(), "As");
+	assert_eq!(format!("{:?}", card), "As");
+	assert_eq!(format!("{:#}", card), "A\u{2660}");
+	assert_eq!(format!("{:-}", card), "Ace of Spades");
+	assert_eq!(format!("{:+}", Card::new_from_string("Td".to_string())), "\x1b[34mTd\x1b[0m");
+	assert_eq!(format!("{:+#}", Card::new_from_string("7h".to_string())), "\x1b[31m7\u{2665}\x1b[0m");
+	assert_eq!(format!("{:>4}|{:<3}|", card, Card::new_from_string("2c".to_string())), "  As|2c |");
+	assert_eq!(format!("{:<-20}|", Card::new_from_string("Qd".to_string())), "Queen of Diamonds   |");
+
+	let mut set = std::collections::HashSet::new();
+	set.insert(card);
+	set.insert(Card::parse("As").unwrap());
+	set.insert(Card::parse("Ac").unwrap());
+	assert_eq!(set.len(), 2);
+
+	let hand = Hand::new_from_string("Ad Ah 8h 7c Tc".to_string());
+	assert_eq!(hand.to_string(), "Ad Ah 8h 7c Tc");
+	assert_eq!(format!("{:?}", hand), "Ad Ah 8h 7c Tc");
+	assert_eq!(format!("{:#}", hand), "A\u{2666} A\u{2665} 8\u{2665} 7\u{2663} T\u{2663}");
+	assert_eq!(format!("{:-}", hand).starts_with("Ace of Diamonds, Ace of Hearts, Eight"), true);
+	assert_eq!(format!("{:+}", hand).matches("\x1b[0m").count(), 5);
+	assert_eq!(hand.to_ordered_string(), "7c 8h Tc Ad Ah");
+}
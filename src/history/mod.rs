@@ -0,0 +1,867 @@
+use crate::eval;
+use crate::eval::HandValue;
+use crate::hand::Card;
+use crate::hand::HandRank;
+use crate::hand::Rank;
+use crate::pot::Contribution;
+use crate::showdown::Variant;
+use crate::table::BettingStructure;
+use crate::table::Post;
+use crate::table::Street;
+
+#[cfg(test)]
+mod tests;
+
+const HAND_PREFIX: &str = "PokerStars Hand #";
+
+static GAMES: [(&str, Variant, BettingStructure); 6] = [
+	("Hold'em No Limit", Variant::HOLDEM, BettingStructure::NO_LIMIT),
+	("Hold'em Pot Limit", Variant::HOLDEM, BettingStructure::POT_LIMIT),
+	("Hold'em Limit", Variant::HOLDEM, BettingStructure::FIXED_LIMIT),
+	("Omaha No Limit", Variant::OMAHA, BettingStructure::NO_LIMIT),
+	("Omaha Pot Limit", Variant::OMAHA, BettingStructure::POT_LIMIT),
+	("Omaha Limit", Variant::OMAHA, BettingStructure::FIXED_LIMIT)
+];
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Currency {
+	CHIPS,
+	USD,
+	EUR,
+	GBP
+}
+
+impl Currency {
+	pub fn code(&self) -> &str {
+		match self {
+			Currency::CHIPS => 	"",
+			Currency::USD => 	"USD",
+			Currency::EUR => 	"EUR",
+			Currency::GBP => 	"GBP"
+		}
+	}
+
+	pub fn symbol(&self) -> &str {
+		match self {
+			Currency::CHIPS => 	"",
+			Currency::USD => 	"$",
+			Currency::EUR => 	"€",
+			Currency::GBP => 	"£"
+		}
+	}
+
+	// Money is kept in cents, chips as they are.
+	pub fn parse_amount(&self, s: &str) -> Option<u64> {
+		let digits = s.strip_prefix(self.symbol())?;
+		let (whole, cents) = match digits.split_once('.') {
+			Some((whole, cents)) if *self != Currency::CHIPS => (whole, cents),
+			Some(_) => return None,
+			None => (digits, "")
+		};
+
+		let numeric = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+		if whole.is_empty() || !numeric(whole) || !numeric(cents) || cents.len() > 2 {
+			return None;
+		}
+
+		let whole: u64 = whole.parse().ok()?;
+		if *self == Currency::CHIPS {
+			return Some(whole);
+		}
+
+		let cents: u64 = match cents.len() {
+			0 => 0,
+			1 => cents.parse::<u64>().ok()? * 10,
+			_ => cents.parse().ok()?
+		};
+
+		return Some(whole * 100 + cents);
+	}
+
+	pub fn format_amount(&self, amount: u64) -> String {
+		if *self == Currency::CHIPS {
+			return amount.to_string();
+		}
+
+		if amount % 100 == 0 {
+			return format!("{}{}", self.symbol(), amount / 100);
+		}
+
+		return format!("{}{}.{:02}", self.symbol(), amount / 100, amount % 100);
+	}
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ParseErrorKind {
+	EMPTY,
+	INVALID_HEADER,
+	INVALID_TABLE,
+	INVALID_SEAT,
+	INVALID_CARDS,
+	INVALID_AMOUNT,
+	UNKNOWN_PLAYER,
+	UNSUPPORTED,
+	MISSING_SUMMARY
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct ParseError {
+	// 1-based, counted from the start of the parsed text
+	pub line: usize,
+	pub kind: ParseErrorKind
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Tournament {
+	pub id: u64,
+	pub buy_in: String,
+	pub level: String
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SeatRecord {
+	pub seat: usize,
+	pub name: String,
+	pub stack: u64,
+	pub sitting_out: bool
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Eq, PartialEq)]
+pub enum ActionKind {
+	POST(Post, u64),
+	// small and big blind posted together, the small blind going in dead
+	POST_DEAD(u64),
+	FOLD,
+	CHECK,
+	// chips added by the call
+	CALL(u64),
+	BET(u64),
+	// raised by, raised to
+	RAISE(u64, u64),
+	UNCALLED(u64),
+	SHOW(Vec<Card>),
+	// cards are filled in from the summary when the site reveals them
+	MUCK(Vec<Card>),
+	NO_SHOW,
+	// amount and pot, 0 being the main pot
+	COLLECT(u64, usize)
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct ActionRecord {
+	pub street: Street,
+	pub player: String,
+	pub kind: ActionKind,
+	pub all_in: bool
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct HandRecord {
+	pub site: String,
+	pub hand_id: u64,
+	pub tournament: Option<Tournament>,
+	pub variant: Variant,
+	pub structure: BettingStructure,
+	pub currency: Currency,
+	// blinds for big-bet games, small and big bet for fixed limit
+	pub stakes: (u64, u64),
+	pub date: String,
+	pub table: String,
+	pub max_seats: usize,
+	pub button: usize,
+	pub seats: Vec<SeatRecord>,
+	pub dealt: Vec<(String, Vec<Card>)>,
+	pub actions: Vec<ActionRecord>,
+	pub board: Vec<Card>,
+	pub total_pot: u64,
+	// main pot first, a single entry when there were no side pots
+	pub pots: Vec<u64>,
+	pub rake: u64
+}
+
+impl HandRecord {
+	pub fn seat(&self, name: &str) -> Option<&SeatRecord> {
+		return self.seats.iter().find(|s| s.name == name);
+	}
+
+	pub fn player_actions<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a ActionRecord> + 'a {
+		return self.actions.iter().filter(move |a| a.player == name);
+	}
+
+	// Hole cards dealt to, shown or mucked by the player, if known
+	pub fn hole_cards(&self, name: &str) -> Option<Vec<Card>> {
+		if let Some((_, cards)) = self.dealt.iter().find(|d| d.0 == name) {
+			return Some(cards.clone());
+		}
+
+		for action in self.player_actions(name) {
+			match &action.kind {
+				ActionKind::SHOW(cards) | ActionKind::MUCK(cards) if cards.len() == self.variant.hole_cards() => return Some(cards.clone()),
+				_ => ()
+			}
+		}
+
+		return None;
+	}
+
+	pub fn collected(&self, name: &str) -> u64 {
+		return self.player_actions(name).map(|a| match a.kind {
+			ActionKind::COLLECT(amount, _) => amount,
+			_ => 0
+		}).sum();
+	}
+
+	pub fn folded(&self, name: &str) -> Option<Street> {
+		return self.player_actions(name).find(|a| a.kind == ActionKind::FOLD).map(|a| a.street);
+	}
+
+	// The live big blind, which is the small bet in fixed limit
+	pub fn big_blind(&self) -> u64 {
+		match self.structure {
+			BettingStructure::FIXED_LIMIT => return self.stakes.0,
+			_ => return self.stakes.1
+		}
+	}
+
+	// Chips each action puts into the pot, antes and dead blinds included
+	pub fn chips_added(&self) -> Vec<u64> {
+		let mut committed = vec![0u64; self.seats.len()];
+		let mut street = Street::PREFLOP;
+
+		return self.actions.iter().map(|action| {
+			if action.street != street {
+				street = action.street;
+				committed.iter_mut().for_each(|c| *c = 0);
+			}
+
+			let i = match self.seats.iter().position(|s| s.name == action.player) {
+				Some(i) => i,
+				None => return 0
+			};
+
+			let (live, dead) = match action.kind {
+				ActionKind::POST(Post::ANTE, amount) => (0, amount),
+				ActionKind::POST(_, amount) | ActionKind::CALL(amount) | ActionKind::BET(amount) => (amount, 0),
+				ActionKind::POST_DEAD(amount) => (amount.min(self.big_blind()), amount - amount.min(self.big_blind())),
+				ActionKind::RAISE(_, to) => (to.saturating_sub(committed[i]), 0),
+				_ => (0, 0)
+			};
+
+			committed[i] += live;
+			live + dead
+		}).collect();
+	}
+
+	// Chips put in by every player, before any uncalled bet is returned
+	pub fn contributions(&self) -> Vec<Contribution> {
+		let added = self.chips_added();
+
+		return self.seats.iter()
+			.map(|seat| {
+				let total = self.actions.iter().zip(added.iter()).filter(|(a, _)| a.player == seat.name).map(|(_, c)| *c).sum::<u64>();
+				Contribution {seat: seat.seat, amount: total, folded: self.folded(&seat.name).is_some()}
+			})
+			.filter(|c| c.amount > 0)
+			.collect();
+	}
+}
+
+fn parse_cards(s: &str) -> Option<Vec<Card>> {
+	return s.split_whitespace().map(Card::parse).collect();
+}
+
+fn cards_to_string(cards: &[Card]) -> String {
+	return cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ");
+}
+
+// All cards in the [..] groups of a line, in order
+fn bracketed_cards(s: &str) -> Option<Vec<Card>> {
+	let mut cards = Vec::new();
+	let mut rest = s;
+	while let Some(start) = rest.find('[') {
+		let end = start + rest[start..].find(']')?;
+		cards.extend(parse_cards(&rest[start + 1..end])?);
+		rest = &rest[end + 1..];
+	}
+
+	return Some(cards);
+}
+
+// The longest seated player name that starts the line and is followed by the
+// separator, together with the rest of the line
+fn player_prefix<'a>(seats: &[SeatRecord], line: &'a str, separator: &str) -> Option<(String, &'a str)> {
+	let mut best: Option<(String, &'a str)> = None;
+	for seat in seats {
+		if let Some(rest) = line.strip_prefix(seat.name.as_str()).and_then(|r| r.strip_prefix(separator)) {
+			if best.as_ref().map_or(true, |b| b.0.len() < seat.name.len()) {
+				best = Some((seat.name.clone(), rest));
+			}
+		}
+	}
+
+	return best;
+}
+
+fn parse_stakes(s: &str, currency: Currency) -> Option<(u64, u64)> {
+	let (small, big) = s.split_once('/')?;
+	return Some((currency.parse_amount(small)?, currency.parse_amount(big)?));
+}
+
+fn parse_header(line: &str) -> Option<HandRecord> {
+	let (id, rest) = line.strip_prefix(HAND_PREFIX)?.split_once(':')?;
+	let rest = rest.trim_start();
+
+	let mut record = HandRecord {
+		site: "PokerStars".to_string(),
+		hand_id: id.parse().ok()?,
+		tournament: None,
+		variant: Variant::HOLDEM,
+		structure: BettingStructure::NO_LIMIT,
+		currency: Currency::CHIPS,
+		stakes: (0, 0),
+		date: String::new(),
+		table: String::new(),
+		max_seats: 0,
+		button: 0,
+		seats: Vec::new(),
+		dealt: Vec::new(),
+		actions: Vec::new(),
+		board: Vec::new(),
+		total_pot: 0,
+		pots: Vec::new(),
+		rake: 0
+	};
+
+	let stakes_and_date;
+	if let Some(rest) = rest.strip_prefix("Tournament #") {
+		let (id, rest) = rest.split_once(", ")?;
+		let (name, variant, structure) = GAMES.iter().find(|g| rest.contains(&format!(" {} - Level ", g.0)))?;
+		let (buy_in, rest) = rest.split_once(&format!(" {} - Level ", name))?;
+		let (level, rest) = rest.split_once(" (")?;
+
+		record.tournament = Some(Tournament {id: id.parse().ok()?, buy_in: buy_in.to_string(), level: level.to_string()});
+		record.variant = *variant;
+		record.structure = *structure;
+		stakes_and_date = rest;
+	} else {
+		let (name, variant, structure) = GAMES.iter().find(|g| rest.starts_with(&format!("{} (", g.0)))?;
+		record.variant = *variant;
+		record.structure = *structure;
+		stakes_and_date = &rest[name.len() + 2..];
+	}
+
+	let (stakes, date) = stakes_and_date.split_once(") - ")?;
+	let stakes = match stakes.split_once(' ') {
+		Some((stakes, code)) => {
+			record.currency = [Currency::USD, Currency::EUR, Currency::GBP].iter().find(|c| c.code() == code).copied()?;
+			stakes
+		},
+		None => stakes
+	};
+
+	record.stakes = parse_stakes(stakes, record.currency)?;
+	record.date = date.to_string();
+
+	return Some(record);
+}
+
+fn parse_table(line: &str, record: &mut HandRecord) -> Option<()> {
+	let (name, rest) = line.strip_prefix("Table '")?.rsplit_once("' ")?;
+	let (max_seats, rest) = rest.split_once("-max Seat #")?;
+
+	record.table = name.to_string();
+	record.max_seats = max_seats.parse().ok()?;
+	record.button = rest.strip_suffix(" is the button")?.parse().ok()?;
+
+	return Some(());
+}
+
+fn parse_seat(line: &str, currency: Currency) -> Result<SeatRecord, ParseErrorKind> {
+	let (seat, rest) = line.strip_prefix("Seat ").and_then(|l| l.split_once(": ")).ok_or(ParseErrorKind::INVALID_SEAT)?;
+	let chips = rest.find(" in chips").ok_or(ParseErrorKind::INVALID_SEAT)?;
+	let open = rest[..chips].rfind(" (").ok_or(ParseErrorKind::INVALID_SEAT)?;
+	let close = chips + rest[chips..].find(')').ok_or(ParseErrorKind::INVALID_SEAT)?;
+
+	return Ok(SeatRecord {
+		seat: seat.parse().map_err(|_| ParseErrorKind::INVALID_SEAT)?,
+		name: rest[..open].to_string(),
+		stack: currency.parse_amount(&rest[open + 2..chips]).ok_or(ParseErrorKind::INVALID_AMOUNT)?,
+		sitting_out: rest[close..].contains("is sitting out")
+	});
+}
+
+// None for lines about a player that are not part of the hand (chat, time
+// outs, sitting out, ...)
+fn parse_action(s: &str, currency: Currency) -> Result<Option<(ActionKind, bool)>, ParseErrorKind> {
+	let (s, all_in) = match s.strip_suffix(" and is all-in") {
+		Some(s) => (s, true),
+		None => (s, false)
+	};
+
+	let amount = |a: &str| currency.parse_amount(a).ok_or(ParseErrorKind::INVALID_AMOUNT);
+	let posts = [
+		("posts small blind ", Post::SMALL_BLIND),
+		("posts big blind ", Post::BIG_BLIND),
+		("posts the ante ", Post::ANTE),
+		("posts straddle ", Post::STRADDLE)
+	];
+
+	for (prefix, post) in posts.iter() {
+		if let Some(a) = s.strip_prefix(prefix) {
+			return Ok(Some((ActionKind::POST(*post, amount(a)?), all_in)));
+		}
+	}
+
+	let kind = if let Some(a) = s.strip_prefix("posts small & big blinds ") {
+		ActionKind::POST_DEAD(amount(a)?)
+	} else if s == "folds" {
+		ActionKind::FOLD
+	} else if s == "checks" {
+		ActionKind::CHECK
+	} else if let Some(a) = s.strip_prefix("calls ") {
+		ActionKind::CALL(amount(a)?)
+	} else if let Some(a) = s.strip_prefix("bets ") {
+		ActionKind::BET(amount(a)?)
+	} else if let Some(a) = s.strip_prefix("raises ") {
+		let (by, to) = a.split_once(" to ").ok_or(ParseErrorKind::INVALID_AMOUNT)?;
+		ActionKind::RAISE(amount(by)?, amount(to)?)
+	} else if let Some(a) = s.strip_prefix("shows ") {
+		let cards = match a.find(']') {
+			Some(end) => bracketed_cards(&a[..end + 1]),
+			None => None
+		};
+
+		ActionKind::SHOW(cards.ok_or(ParseErrorKind::INVALID_CARDS)?)
+	} else if s == "mucks hand" {
+		ActionKind::MUCK(Vec::new())
+	} else if s == "doesn't show hand" {
+		ActionKind::NO_SHOW
+	} else {
+		return Ok(None);
+	};
+
+	return Ok(Some((kind, all_in)));
+}
+
+fn parse_street(line: &str, record: &mut HandRecord) -> Result<Street, ParseErrorKind> {
+	let streets = [
+		("*** FLOP *** ", Street::FLOP, 3),
+		("*** TURN *** ", Street::TURN, 4),
+		("*** RIVER *** ", Street::RIVER, 5)
+	];
+
+	for (prefix, street, count) in streets.iter() {
+		if let Some(rest) = line.strip_prefix(prefix) {
+			let board = bracketed_cards(rest).ok_or(ParseErrorKind::INVALID_CARDS)?;
+			if board.len() != *count || !board.starts_with(&record.board) {
+				return Err(ParseErrorKind::INVALID_CARDS);
+			}
+
+			record.board = board;
+			return Ok(*street);
+		}
+	}
+
+	match line {
+		"*** HOLE CARDS ***" => return Ok(Street::PREFLOP),
+		"*** SHOW DOWN ***" => return Ok(Street::SHOWDOWN),
+		_ => return Err(ParseErrorKind::UNSUPPORTED)
+	}
+}
+
+fn parse_collect(s: &str, currency: Currency) -> Result<ActionKind, ParseErrorKind> {
+	let (amount, pot) = s.split_once(" from ").ok_or(ParseErrorKind::INVALID_AMOUNT)?;
+	let amount = currency.parse_amount(amount).ok_or(ParseErrorKind::INVALID_AMOUNT)?;
+	let pot = match pot {
+		"pot" | "main pot" => 0,
+		"side pot" => 1,
+		_ => pot.strip_prefix("side pot-").and_then(|p| p.parse().ok()).ok_or(ParseErrorKind::UNSUPPORTED)?
+	};
+
+	return Ok(ActionKind::COLLECT(amount, pot));
+}
+
+fn parse_summary_line(line: &str, record: &mut HandRecord) -> Result<(), ParseErrorKind> {
+	let currency = record.currency;
+	let amount = |a: &str| currency.parse_amount(a).ok_or(ParseErrorKind::INVALID_AMOUNT);
+
+	if let Some(rest) = line.strip_prefix("Total pot ") {
+		let mut parts = rest.split(" | ");
+		let pots = parts.next().unwrap();
+		let (total, side_pots) = pots.split_once(' ').unwrap_or((pots, ""));
+		record.total_pot = amount(total)?;
+		record.pots = vec![record.total_pot];
+
+		if !side_pots.is_empty() {
+			record.pots.clear();
+			for pot in side_pots.trim_end_matches('.').split(". ") {
+				let (_, pot_amount) = pot.rsplit_once(' ').ok_or(ParseErrorKind::INVALID_AMOUNT)?;
+				record.pots.push(amount(pot_amount)?);
+			}
+		}
+
+		for part in parts {
+			if let Some(rake) = part.strip_prefix("Rake ") {
+				record.rake = amount(rake)?;
+			}
+		}
+	} else if let Some(rest) = line.strip_prefix("Board ") {
+		let board = bracketed_cards(rest).ok_or(ParseErrorKind::INVALID_CARDS)?;
+		if record.board.is_empty() {
+			record.board = board;
+		} else if board != record.board {
+			return Err(ParseErrorKind::INVALID_CARDS);
+		}
+	} else if let Some((_, rest)) = line.strip_prefix("Seat ").and_then(|l| l.split_once(": ")) {
+		if let Some((name, rest)) = player_prefix(&record.seats, rest, " ") {
+			if let Some(start) = rest.find("mucked [") {
+				let cards = bracketed_cards(&rest[start..]).ok_or(ParseErrorKind::INVALID_CARDS)?;
+				let muck = record.actions.iter_mut().rev().find(|a| a.player == name && matches!(a.kind, ActionKind::MUCK(_)));
+				if let Some(action) = muck {
+					action.kind = ActionKind::MUCK(cards);
+				}
+			}
+		}
+	}
+
+	return Ok(());
+}
+
+pub fn parse_hand(text: &str) -> Result<HandRecord, ParseError> {
+	let mut lines = text.lines().enumerate().map(|(i, l)| (i + 1, l.trim_end())).filter(|(_, l)| !l.is_empty());
+	let error = |line: usize| move |kind: ParseErrorKind| ParseError {line, kind};
+
+	let (n, header) = lines.next().ok_or(ParseError {line: 1, kind: ParseErrorKind::EMPTY})?;
+	let mut record = parse_header(header).ok_or(ParseErrorKind::INVALID_HEADER).map_err(error(n))?;
+	let (n, table) = lines.next().ok_or(ParseError {line: n + 1, kind: ParseErrorKind::INVALID_TABLE})?;
+	parse_table(table, &mut record).ok_or(ParseErrorKind::INVALID_TABLE).map_err(error(n))?;
+
+	let mut street = Street::PREFLOP;
+	let mut seated = true;
+	let mut summary = false;
+	let mut last = n;
+	for (n, line) in lines {
+		last = n;
+		if summary {
+			parse_summary_line(line, &mut record).map_err(error(n))?;
+			continue;
+		}
+
+		if seated && line.starts_with("Seat ") {
+			let seat = parse_seat(line, record.currency).map_err(error(n))?;
+			record.seats.push(seat);
+			continue;
+		}
+
+		seated = false;
+		if line == "*** SUMMARY ***" {
+			summary = true;
+		} else if line.starts_with("*** ") {
+			street = parse_street(line, &mut record).map_err(error(n))?;
+		} else if let Some(rest) = line.strip_prefix("Dealt to ") {
+			let (name, cards) = player_prefix(&record.seats, rest, " ").ok_or(ParseErrorKind::UNKNOWN_PLAYER).map_err(error(n))?;
+			let cards = bracketed_cards(cards).filter(|c| !c.is_empty()).ok_or(ParseErrorKind::INVALID_CARDS).map_err(error(n))?;
+			record.dealt.push((name, cards));
+		} else if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+			let (amount, name) = rest.split_once(") returned to ").ok_or(ParseErrorKind::INVALID_AMOUNT).map_err(error(n))?;
+			let amount = record.currency.parse_amount(amount).ok_or(ParseErrorKind::INVALID_AMOUNT).map_err(error(n))?;
+			record.seat(name).ok_or(ParseErrorKind::UNKNOWN_PLAYER).map_err(error(n))?;
+			record.actions.push(ActionRecord {street, player: name.to_string(), kind: ActionKind::UNCALLED(amount), all_in: false});
+		} else if let Some((name, rest)) = player_prefix(&record.seats, line, ": ") {
+			if let Some((kind, all_in)) = parse_action(rest, record.currency).map_err(error(n))? {
+				record.actions.push(ActionRecord {street, player: name, kind, all_in});
+			}
+		} else if let Some((name, rest)) = player_prefix(&record.seats, line, " collected ") {
+			let kind = parse_collect(rest, record.currency).map_err(error(n))?;
+			record.actions.push(ActionRecord {street, player: name, kind, all_in: false});
+		}
+	}
+
+	if !summary {
+		return Err(ParseError {line: last, kind: ParseErrorKind::MISSING_SUMMARY});
+	}
+
+	return Ok(record);
+}
+
+// Every hand in a file, each one parsed on its own so that a broken hand
+// does not stop the rest
+pub fn parse_hands(text: &str) -> Vec<Result<HandRecord, ParseError>> {
+	let lines: Vec<&str> = text.lines().collect();
+	let starts: Vec<usize> = (0..lines.len()).filter(|i| lines[*i].starts_with(HAND_PREFIX)).collect();
+
+	return starts.iter().enumerate().map(|(i, start)| {
+		let end = if i + 1 < starts.len() { starts[i + 1] } else { lines.len() };
+		parse_hand(&lines[*start..end].join("\n")).map_err(|e| ParseError {line: e.line + start, kind: e.kind})
+	}).collect();
+}
+
+fn rank_name(rank: Rank, plural: bool) -> &'static str {
+	let names = [
+		("Deuce", "Deuces"), ("Three", "Threes"), ("Four", "Fours"), ("Five", "Fives"),
+		("Six", "Sixes"), ("Seven", "Sevens"), ("Eight", "Eights"), ("Nine", "Nines"),
+		("Ten", "Tens"), ("Jack", "Jacks"), ("Queen", "Queens"), ("King", "Kings"), ("Ace", "Aces")
+	];
+
+	let name = names[(rank.to_int() - 2) as usize];
+	return if plural { name.1 } else { name.0 };
+}
+
+fn rank_at(bit: u32) -> Rank {
+	return Rank::iterator().as_slice()[bit as usize];
+}
+
+fn high_rank(mask: u16) -> Rank {
+	return rank_at(15 - mask.leading_zeros());
+}
+
+fn low_rank(mask: u16) -> Rank {
+	return rank_at(mask.trailing_zeros());
+}
+
+// Straight of the given high card, as "Nine to King"
+fn straight_name(high: Rank) -> String {
+	let low = if high == Rank::FIVE { Rank::ACE } else { rank_at((high.to_int() - 6) as u32) };
+	return format!("{} to {}", rank_name(low, false), rank_name(high, false));
+}
+
+// The hand as the site describes it at showdown
+pub fn describe(value: HandValue) -> String {
+	let major = value.major_ranks();
+	let minor = value.minor_ranks();
+
+	match value.category() {
+		HandRank::STRAIGHT_FLUSH if high_rank(major) == Rank::ACE => "a Royal Flush".to_string(),
+		HandRank::STRAIGHT_FLUSH => format!("a straight flush, {}", straight_name(high_rank(major))),
+		HandRank::FOUR_OF_A_KIND => format!("four of a kind, {}", rank_name(high_rank(major), true)),
+		HandRank::FULL_HOUSE => format!("a full house, {} full of {}", rank_name(high_rank(major), true), rank_name(high_rank(minor), true)),
+		HandRank::FLUSH => format!("a flush, {} high", rank_name(high_rank(major), false)),
+		HandRank::STRAIGHT => format!("a straight, {}", straight_name(high_rank(major))),
+		HandRank::THREE_OF_A_KIND => format!("three of a kind, {}", rank_name(high_rank(major), true)),
+		HandRank::TWO_PAIRS => format!("two pair, {} and {}", rank_name(high_rank(major), true), rank_name(low_rank(major), true)),
+		HandRank::PAIR => format!("a pair of {}", rank_name(high_rank(major), true)),
+		HandRank::HIGH_CARD => format!("high card {}", rank_name(high_rank(major), false))
+	}
+}
+
+fn describe_cards(record: &HandRecord, hole: &[Card]) -> Option<String> {
+	if record.board.len() != 5 || hole.len() != record.variant.hole_cards() {
+		return None;
+	}
+
+	let value = match record.variant {
+		Variant::HOLDEM => eval::evaluate_cards(&[hole, &record.board[..]].concat()),
+		Variant::OMAHA => eval::evaluate_hand(&eval::best_omaha_hand(hole, &record.board))
+	};
+
+	return Some(describe(value));
+}
+
+fn pot_name(record: &HandRecord, pot: usize) -> String {
+	if record.pots.len() <= 1 {
+		return "pot".to_string();
+	} else if pot == 0 {
+		return "main pot".to_string();
+	} else if record.pots.len() == 2 {
+		return "side pot".to_string();
+	}
+
+	return format!("side pot-{}", pot);
+}
+
+fn write_header(record: &HandRecord) -> String {
+	let game = GAMES.iter().find(|g| g.1 == record.variant && g.2 == record.structure).unwrap().0;
+	let mut stakes = format!("{}/{}", record.currency.format_amount(record.stakes.0), record.currency.format_amount(record.stakes.1));
+	if record.currency != Currency::CHIPS {
+		stakes += &format!(" {}", record.currency.code());
+	}
+
+	match &record.tournament {
+		Some(t) => return format!("{}{}: Tournament #{}, {} {} - Level {} ({}) - {}", HAND_PREFIX, record.hand_id, t.id, t.buy_in, game, t.level, stakes, record.date),
+		None => return format!("{}{}:  {} ({}) - {}", HAND_PREFIX, record.hand_id, game, stakes, record.date)
+	}
+}
+
+fn write_action(record: &HandRecord, action: &ActionRecord) -> String {
+	let amount = |a: u64| record.currency.format_amount(a);
+	let text = match &action.kind {
+		ActionKind::POST(Post::SMALL_BLIND, a) => format!("posts small blind {}", amount(*a)),
+		ActionKind::POST(Post::BIG_BLIND, a) => format!("posts big blind {}", amount(*a)),
+		ActionKind::POST(Post::ANTE, a) => format!("posts the ante {}", amount(*a)),
+		ActionKind::POST(Post::STRADDLE, a) => format!("posts straddle {}", amount(*a)),
+		ActionKind::POST_DEAD(a) => format!("posts small & big blinds {}", amount(*a)),
+		ActionKind::FOLD => "folds".to_string(),
+		ActionKind::CHECK => "checks".to_string(),
+		ActionKind::CALL(a) => format!("calls {}", amount(*a)),
+		ActionKind::BET(a) => format!("bets {}", amount(*a)),
+		ActionKind::RAISE(by, to) => format!("raises {} to {}", amount(*by), amount(*to)),
+		ActionKind::SHOW(cards) => match describe_cards(record, cards) {
+			Some(description) => format!("shows [{}] ({})", cards_to_string(cards), description),
+			None => format!("shows [{}]", cards_to_string(cards))
+		},
+		ActionKind::MUCK(_) => "mucks hand".to_string(),
+		ActionKind::NO_SHOW => "doesn't show hand".to_string(),
+		ActionKind::UNCALLED(a) => return format!("Uncalled bet ({}) returned to {}", amount(*a), action.player),
+		ActionKind::COLLECT(a, pot) => return format!("{} collected {} from {}", action.player, amount(*a), pot_name(record, *pot))
+	};
+
+	return format!("{}: {}{}", action.player, text, if action.all_in { " and is all-in" } else { "" });
+}
+
+fn write_street(record: &HandRecord, street: Street) -> Option<String> {
+	let board = &record.board;
+	match street {
+		Street::FLOP if board.len() >= 3 => return Some(format!("*** FLOP *** [{}]", cards_to_string(&board[..3]))),
+		Street::TURN if board.len() >= 4 => return Some(format!("*** TURN *** [{}] [{}]", cards_to_string(&board[..3]), board[3])),
+		Street::RIVER if board.len() >= 5 => return Some(format!("*** RIVER *** [{}] [{}]", cards_to_string(&board[..4]), board[4])),
+		Street::SHOWDOWN => return Some("*** SHOW DOWN ***".to_string()),
+		_ => return None
+	}
+}
+
+fn next_street(street: Street) -> Street {
+	match street {
+		Street::PREFLOP => Street::FLOP,
+		Street::FLOP => Street::TURN,
+		Street::TURN => Street::RIVER,
+		_ => Street::SHOWDOWN
+	}
+}
+
+fn write_seat_summary(record: &HandRecord, seat: &SeatRecord) -> String {
+	let name = seat.name.as_str();
+	let posted = |post: Post| record.player_actions(name).any(|a| match a.kind {
+		ActionKind::POST(p, _) => p == post,
+		ActionKind::POST_DEAD(_) => post == Post::BIG_BLIND,
+		_ => false
+	});
+
+	let mut line = format!("Seat {}: {}", seat.seat, name);
+	if seat.seat == record.button {
+		line += " (button)";
+	}
+	if posted(Post::SMALL_BLIND) {
+		line += " (small blind)";
+	}
+	if posted(Post::BIG_BLIND) {
+		line += " (big blind)";
+	}
+
+	let collected = record.collected(name);
+	let shown = record.player_actions(name).find_map(|a| match &a.kind {
+		ActionKind::SHOW(cards) => Some(cards.clone()),
+		_ => None
+	});
+	let mucked = record.player_actions(name).find_map(|a| match &a.kind {
+		ActionKind::MUCK(cards) => Some(cards.clone()),
+		_ => None
+	});
+
+	if let Some(street) = record.folded(name) {
+		line += match street {
+			Street::PREFLOP => " folded before Flop",
+			Street::FLOP => " folded on the Flop",
+			Street::TURN => " folded on the Turn",
+			_ => " folded on the River"
+		};
+
+		let bet = record.player_actions(name).any(|a| match a.kind {
+			ActionKind::POST(post, _) => post != Post::ANTE,
+			ActionKind::POST_DEAD(_) | ActionKind::CALL(_) | ActionKind::BET(_) | ActionKind::RAISE(_, _) => true,
+			_ => false
+		});
+		if street == Street::PREFLOP && !bet {
+			line += " (didn't bet)";
+		}
+	} else if let Some(cards) = shown {
+		line += &format!(" showed [{}] and ", cards_to_string(&cards));
+		line += &if collected > 0 { format!("won ({})", record.currency.format_amount(collected)) } else { "lost".to_string() };
+		if let Some(description) = describe_cards(record, &cards) {
+			line += &format!(" with {}", description);
+		}
+	} else if let Some(cards) = mucked {
+		line += " mucked";
+		if !cards.is_empty() {
+			line += &format!(" [{}]", cards_to_string(&cards));
+		}
+	} else if collected > 0 {
+		line += &format!(" collected ({})", record.currency.format_amount(collected));
+	} else {
+		line += " didn't show";
+	}
+
+	return line;
+}
+
+pub fn write_hand(record: &HandRecord) -> String {
+	let mut lines = vec![write_header(record)];
+	lines.push(format!("Table '{}' {}-max Seat #{} is the button", record.table, record.max_seats, record.button));
+
+	for seat in &record.seats {
+		let amount = record.currency.format_amount(seat.stack);
+		lines.push(format!("Seat {}: {} ({} in chips){}", seat.seat, seat.name, amount, if seat.sitting_out { " is sitting out" } else { "" }));
+	}
+
+	let posts = record.actions.iter().take_while(|a| matches!(a.kind, ActionKind::POST(_, _) | ActionKind::POST_DEAD(_))).count();
+	for action in &record.actions[..posts] {
+		lines.push(write_action(record, action));
+	}
+
+	lines.push("*** HOLE CARDS ***".to_string());
+	for (name, cards) in &record.dealt {
+		lines.push(format!("Dealt to {} [{}]", name, cards_to_string(cards)));
+	}
+
+	let mut street = Street::PREFLOP;
+	for action in &record.actions[posts..] {
+		while street < action.street {
+			street = next_street(street);
+			lines.extend(write_street(record, street));
+		}
+
+		lines.push(write_action(record, action));
+	}
+
+	let last_street = match record.board.len() {
+		0..=2 => Street::PREFLOP,
+		3 => Street::FLOP,
+		4 => Street::TURN,
+		_ => Street::RIVER
+	};
+	while street < last_street {
+		street = next_street(street);
+		lines.extend(write_street(record, street));
+	}
+
+	lines.push("*** SUMMARY ***".to_string());
+	let mut total = format!("Total pot {}", record.currency.format_amount(record.total_pot));
+	if record.pots.len() > 1 {
+		for (i, pot) in record.pots.iter().enumerate() {
+			let name = match i {
+				0 => "Main pot".to_string(),
+				_ if record.pots.len() == 2 => "Side pot".to_string(),
+				_ => format!("Side pot-{}", i)
+			};
+
+			total += &format!(" {} {}.", name, record.currency.format_amount(*pot));
+		}
+	}
+	lines.push(format!("{} | Rake {}", total, record.currency.format_amount(record.rake)));
+
+	if !record.board.is_empty() {
+		lines.push(format!("Board [{}]", cards_to_string(&record.board)));
+	}
+
+	for seat in record.seats.iter().filter(|s| !s.sitting_out) {
+		lines.push(write_seat_summary(record, seat));
+	}
+
+	return lines.join("\n") + "\n";
+}
+
+pub fn write_hands(records: &[HandRecord]) -> String {
+	return records.iter().map(write_hand).collect::<Vec<String>>().join("\n\n");
+}
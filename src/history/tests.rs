@@ -0,0 +1,246 @@
+use super::*;
+
+use crate::eval::evaluate_cards;
+use crate::hand::cards;
+
+const CASH_HAND: &str = "\
+PokerStars Hand #210000000001:  Hold'em No Limit ($0.05/$0.10 USD) - 2020/01/01 12:00:00 ET
+Table 'Alpha II' 6-max Seat #1 is the button
+Seat 1: Alice ($10 in chips)
+Seat 2: Bob Jr ($12.50 in chips)
+Seat 3: Carol ($8 in chips)
+Seat 4: Dave ($5 in chips) is sitting out
+Bob Jr: posts small blind $0.05
+Carol: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Alice [Ah Kd]
+Alice: raises $0.20 to $0.30
+Bob Jr: folds
+Carol: calls $0.20
+*** FLOP *** [2c 7d 9h]
+Carol: checks
+Alice: bets $0.40
+Carol: calls $0.40
+*** TURN *** [2c 7d 9h] [Js]
+Carol: checks
+Alice: checks
+*** RIVER *** [2c 7d 9h Js] [3d]
+Carol: bets $1
+Alice: calls $1
+*** SHOW DOWN ***
+Carol: shows [Qs Qh] (a pair of Queens)
+Alice: mucks hand
+Carol collected $3.40 from pot
+*** SUMMARY ***
+Total pot $3.45 | Rake $0.05
+Board [2c 7d 9h Js 3d]
+Seat 1: Alice (button) mucked [Ah Kd]
+Seat 2: Bob Jr (small blind) folded before Flop
+Seat 3: Carol (big blind) showed [Qs Qh] and won ($3.40) with a pair of Queens
+";
+
+const TOURNAMENT_HAND: &str = "\
+PokerStars Hand #210000000002: Tournament #3000000, $10+$1 USD Omaha Pot Limit - Level III (25/50) - 2020/01/01 13:00:00 ET
+Table '3000000 1' 9-max Seat #3 is the button
+Seat 1: Ann (1000 in chips)
+Seat 3: Ben (400 in chips)
+Seat 5: Cat (1500 in chips)
+Seat 7: Dan (200 in chips)
+Ann: posts the ante 5
+Ben: posts the ante 5
+Cat: posts the ante 5
+Dan: posts the ante 5
+Cat: posts small blind 25
+Dan: posts big blind 50
+*** HOLE CARDS ***
+Ann: raises 125 to 175
+Ben: raises 220 to 395 and is all-in
+Cat: folds
+Dan: calls 145 and is all-in
+Ann: calls 220
+*** FLOP *** [Ts 8c 3h]
+*** TURN *** [Ts 8c 3h] [2d]
+*** RIVER *** [Ts 8c 3h 2d] [7c]
+*** SHOW DOWN ***
+Ann: shows [As Ad 9c 6h] (a straight, Six to Ten)
+Ben: shows [Kd Kh 7s 6s] (a pair of Kings)
+Ann collected 400 from side pot
+Dan: shows [Jc 9d 5s 4s] (a straight, Seven to Jack)
+Dan collected 630 from main pot
+*** SUMMARY ***
+Total pot 1030 Main pot 630. Side pot 400. | Rake 0
+Board [Ts 8c 3h 2d 7c]
+Seat 1: Ann showed [As Ad 9c 6h] and won (400) with a straight, Six to Ten
+Seat 3: Ben (button) showed [Kd Kh 7s 6s] and lost with a pair of Kings
+Seat 5: Cat (small blind) folded before Flop
+Seat 7: Dan (big blind) showed [Jc 9d 5s 4s] and won (630) with a straight, Seven to Jack
+";
+
+const FOLDED_HAND: &str = "\
+PokerStars Hand #210000000003:  Hold'em Limit ($0.10/$0.20 USD) - 2020/01/01 12:05:00 ET
+Table 'Alpha II' 6-max Seat #2 is the button
+Seat 1: Alice ($10.30 in chips)
+Seat 2: Bob Jr ($12.45 in chips)
+Seat 3: Carol ($9.75 in chips)
+Carol: posts small blind $0.05
+Alice: posts big blind $0.10
+*** HOLE CARDS ***
+Bob Jr: raises $0.10 to $0.20
+Carol: folds
+Alice: folds
+Uncalled bet ($0.10) returned to Bob Jr
+Bob Jr collected $0.25 from pot
+Bob Jr: doesn't show hand
+*** SUMMARY ***
+Total pot $0.25 | Rake $0
+Seat 1: Alice (big blind) folded before Flop
+Seat 2: Bob Jr (button) collected ($0.25)
+Seat 3: Carol (small blind) folded before Flop
+";
+
+#[test]
+fn check_amounts() {
+	assert_eq!(Currency::USD.parse_amount("$12.50"), Some(1250));
+	assert_eq!(Currency::USD.parse_amount("$0.05"), Some(5));
+	assert_eq!(Currency::USD.parse_amount("$3"), Some(300));
+	assert_eq!(Currency::USD.parse_amount("$1.5"), Some(150));
+	assert_eq!(Currency::EUR.parse_amount("€2.25"), Some(225));
+	assert_eq!(Currency::CHIPS.parse_amount("1500"), Some(1500));
+	assert_eq!(Currency::USD.parse_amount("12.50"), None);
+	assert_eq!(Currency::USD.parse_amount("$1.234"), None);
+	assert_eq!(Currency::CHIPS.parse_amount("15.5"), None);
+	assert_eq!(Currency::CHIPS.parse_amount(""), None);
+
+	assert_eq!(Currency::USD.format_amount(1250), "$12.50");
+	assert_eq!(Currency::USD.format_amount(300), "$3");
+	assert_eq!(Currency::USD.format_amount(5), "$0.05");
+	assert_eq!(Currency::CHIPS.format_amount(1500), "1500");
+}
+
+#[test]
+fn check_parse_cash_hand() {
+	let record = parse_hand(CASH_HAND).unwrap();
+
+	assert_eq!(record.hand_id, 210000000001);
+	assert_eq!(record.tournament, None);
+	assert_eq!(record.variant, Variant::HOLDEM);
+	assert_eq!(record.structure, BettingStructure::NO_LIMIT);
+	assert_eq!(record.currency, Currency::USD);
+	assert_eq!(record.stakes, (5, 10));
+	assert_eq!(record.date, "2020/01/01 12:00:00 ET");
+	assert_eq!(record.table, "Alpha II");
+	assert_eq!(record.max_seats, 6);
+	assert_eq!(record.button, 1);
+
+	assert_eq!(record.seats.len(), 4);
+	assert_eq!(record.seat("Bob Jr"), Some(&SeatRecord {seat: 2, name: "Bob Jr".to_string(), stack: 1250, sitting_out: false}));
+	assert_eq!(record.seat("Dave").unwrap().sitting_out, true);
+
+	assert_eq!(record.actions.len(), 15);
+	assert_eq!(record.actions[0] == ActionRecord {street: Street::PREFLOP, player: "Bob Jr".to_string(), kind: ActionKind::POST(Post::SMALL_BLIND, 5), all_in: false}, true);
+	assert_eq!(record.actions[2].kind == ActionKind::RAISE(20, 30), true);
+	assert_eq!(record.actions[6].street, Street::FLOP);
+	assert_eq!(record.actions[12].kind == ActionKind::SHOW(cards("Qs Qh")), true);
+	assert_eq!(record.actions[13].kind == ActionKind::MUCK(cards("Ah Kd")), true);
+	assert_eq!(record.actions[14].kind == ActionKind::COLLECT(340, 0), true);
+
+	assert_eq!(record.board == cards("2c 7d 9h Js 3d"), true);
+	assert_eq!(record.hole_cards("Alice") == Some(cards("Ah Kd")), true);
+	assert_eq!(record.hole_cards("Carol") == Some(cards("Qs Qh")), true);
+	assert_eq!(record.hole_cards("Bob Jr").is_none(), true);
+	assert_eq!(record.collected("Carol"), 340);
+	assert_eq!(record.folded("Bob Jr"), Some(Street::PREFLOP));
+	assert_eq!(record.total_pot, 345);
+	assert_eq!(record.pots, vec![345]);
+	assert_eq!(record.rake, 5);
+
+	let contributions: Vec<(usize, u64, bool)> = record.contributions().iter().map(|c| (c.seat, c.amount, c.folded)).collect();
+	assert_eq!(contributions, vec![(1, 170, false), (2, 5, true), (3, 170, false)]);
+}
+
+#[test]
+fn check_parse_tournament_hand() {
+	let record = parse_hand(TOURNAMENT_HAND).unwrap();
+
+	assert_eq!(record.tournament, Some(Tournament {id: 3000000, buy_in: "$10+$1 USD".to_string(), level: "III".to_string()}));
+	assert_eq!(record.variant, Variant::OMAHA);
+	assert_eq!(record.structure, BettingStructure::POT_LIMIT);
+	assert_eq!(record.currency, Currency::CHIPS);
+	assert_eq!(record.stakes, (25, 50));
+	assert_eq!(record.table, "3000000 1");
+
+	assert_eq!(record.actions.iter().filter(|a| matches!(a.kind, ActionKind::POST(Post::ANTE, 5))).count(), 4);
+	assert_eq!(record.actions.iter().filter(|a| a.all_in).count(), 2);
+	assert_eq!(record.board.len(), 5);
+	assert_eq!(record.pots, vec![630, 400]);
+	assert_eq!(record.total_pot, 1030);
+	assert_eq!(record.collected("Ann"), 400);
+	assert_eq!(record.collected("Dan"), 630);
+	assert_eq!(record.collected("Ben"), 0);
+	assert_eq!(record.hole_cards("Dan") == Some(cards("Jc 9d 5s 4s")), true);
+
+	let contributions: Vec<(usize, u64, bool)> = record.contributions().iter().map(|c| (c.seat, c.amount, c.folded)).collect();
+	assert_eq!(contributions, vec![(1, 400, false), (3, 400, false), (5, 30, true), (7, 200, false)]);
+}
+
+#[test]
+fn check_round_trip() {
+	for text in [CASH_HAND, TOURNAMENT_HAND, FOLDED_HAND].iter() {
+		let record = parse_hand(text).unwrap();
+		assert_eq!(write_hand(&record), *text);
+		assert_eq!(parse_hand(&write_hand(&record)).unwrap() == record, true);
+	}
+
+	let file = [CASH_HAND, TOURNAMENT_HAND, FOLDED_HAND].join("\n\n");
+	let records: Vec<HandRecord> = parse_hands(&file).into_iter().map(|r| r.unwrap()).collect();
+	assert_eq!(records.len(), 3);
+	assert_eq!(write_hands(&records), file);
+}
+
+#[test]
+fn check_skips_noise() {
+	let noisy = CASH_HAND
+		.replace("Bob Jr: folds\n", "Bob Jr: folds\nBob Jr said, \"nice: hand\"\nCarol has timed out\n")
+		.replace("*** FLOP ***", "Dave: sits out\n*** FLOP ***");
+
+	assert_eq!(parse_hand(&noisy).unwrap() == parse_hand(CASH_HAND).unwrap(), true);
+}
+
+#[test]
+fn check_parse_errors() {
+	let error = |text: &str| parse_hand(text).err().unwrap();
+
+	assert_eq!(error(""), ParseError {line: 1, kind: ParseErrorKind::EMPTY});
+	assert_eq!(error(&CASH_HAND.replace("Hold'em No Limit", "Razz")), ParseError {line: 1, kind: ParseErrorKind::INVALID_HEADER});
+	assert_eq!(error(&CASH_HAND.replace("6-max", "six-max")), ParseError {line: 2, kind: ParseErrorKind::INVALID_TABLE});
+	assert_eq!(error(&CASH_HAND.replace("($8 in chips)", "($8.x in chips)")), ParseError {line: 5, kind: ParseErrorKind::INVALID_AMOUNT});
+	assert_eq!(error(&CASH_HAND.replace("[2c 7d 9h]", "[2c 7d 1h]")), ParseError {line: 14, kind: ParseErrorKind::INVALID_CARDS});
+	assert_eq!(error(&CASH_HAND.replace("[2c 7d 9h] [Js]", "[2c 7d 9c] [Js]")), ParseError {line: 18, kind: ParseErrorKind::INVALID_CARDS});
+	assert_eq!(error(&CASH_HAND.replace("Dealt to Alice", "Dealt to Zed")), ParseError {line: 10, kind: ParseErrorKind::UNKNOWN_PLAYER});
+	assert_eq!(error(&CASH_HAND.replace("*** SHOW DOWN ***", "*** FIRST SHOW DOWN ***")), ParseError {line: 24, kind: ParseErrorKind::UNSUPPORTED});
+	assert_eq!(error(&CASH_HAND.replace("*** SUMMARY ***", "")).kind, ParseErrorKind::MISSING_SUMMARY);
+
+	let file = [CASH_HAND, FOLDED_HAND, &TOURNAMENT_HAND.replace("[Ts 8c 3h]", "[Ts 8c]")].join("\n\n");
+	let results = parse_hands(&file);
+	assert_eq!(results.len(), 3);
+	assert_eq!(results[0].is_ok(), true);
+	assert_eq!(results[1].is_ok(), true);
+	assert_eq!(results[2].as_ref().err(), Some(&ParseError {line: 75, kind: ParseErrorKind::INVALID_CARDS}));
+}
+
+#[test]
+fn check_describe() {
+	let describe_cards = |s: &str| describe(evaluate_cards(&cards(s)));
+
+	assert_eq!(describe_cards("As Ks Qs Js Ts"), "a Royal Flush");
+	assert_eq!(describe_cards("5d 6d 7d 8d 9d"), "a straight flush, Five to Nine");
+	assert_eq!(describe_cards("Ac 2d 3h 4s 5c"), "a straight, Ace to Five");
+	assert_eq!(describe_cards("2c 3d 4h 5s 6c"), "a straight, Deuce to Six");
+	assert_eq!(describe_cards("9c 9d 9h 9s 2c"), "four of a kind, Nines");
+	assert_eq!(describe_cards("Kc Kd Ks 6c 6d"), "a full house, Kings full of Sixes");
+	assert_eq!(describe_cards("2h 7h 9h Jh Ah"), "a flush, Ace high");
+	assert_eq!(describe_cards("3c 3d 3h Ks 2c"), "three of a kind, Threes");
+	assert_eq!(describe_cards("Tc Td 4h 4s 2c"), "two pair, Tens and Fours");
+	assert_eq!(describe_cards("2c 2d 4h 7s 9c"), "a pair of Deuces");
+	assert_eq!(describe_cards("Qc Td 4h 7s 9c"), "high card Queen");
+}
@@ -0,0 +1,223 @@
+use rand::Rng;
+
+use crate::equity;
+use crate::equity::EquityError;
+use crate::hand::Card;
+use crate::range::Range;
+
+#[cfg(test)]
+mod tests;
+
+// Independent Chip Model: the chance of finishing in each place follows the
+// stacks, first place going to a player in proportion to their chips and each
+// next place the same way among the players left (Malmuth-Harville).
+
+// Fields up to this size are worked out exactly, larger ones by sampling
+pub const EXACT_PLAYERS: usize = 10;
+// the exact model keeps a probability for every set of players
+pub const MAX_EXACT_PLAYERS: usize = 20;
+pub const DEFAULT_TRIALS: usize = 100_000;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum IcmError {
+	NO_PLAYERS,
+	// more than the exact model takes
+	TOO_MANY_PLAYERS,
+	// stacks must be finite and not negative, with some chips in play
+	INVALID_STACKS,
+	// at least one payout, none negative
+	INVALID_PAYOUTS,
+	// an all-in needs two different seats with chips and posts they can cover
+	INVALID_SPOT,
+	EQUITY(EquityError)
+}
+
+fn check(stacks: &[f64], payouts: &[f64]) -> Result<(), IcmError> {
+	if stacks.is_empty() {
+		return Err(IcmError::NO_PLAYERS);
+	}
+
+	if stacks.iter().any(|s| !s.is_finite() || *s < 0.0) || stacks.iter().sum::<f64>() <= 0.0 {
+		return Err(IcmError::INVALID_STACKS);
+	}
+
+	if payouts.is_empty() || payouts.iter().any(|p| !p.is_finite() || *p < 0.0) {
+		return Err(IcmError::INVALID_PAYOUTS);
+	}
+
+	return Ok(());
+}
+
+// The prize equity of every player, going through each set of players that
+// can fill the paid places above the rest. Players without chips share the
+// places left once everyone else has finished.
+pub fn malmuth_harville(stacks: &[f64], payouts: &[f64]) -> Result<Vec<f64>, IcmError> {
+	check(stacks, payouts)?;
+	if stacks.len() > MAX_EXACT_PLAYERS {
+		return Err(IcmError::TOO_MANY_PLAYERS);
+	}
+
+	let players = stacks.len();
+	let paid = payouts.len().min(players);
+
+	let mut equities = vec![0.0; players];
+	let mut chance = vec![0.0; 1 << players];
+	chance[0] = 1.0;
+	for mask in 0usize..1 << players {
+		let place = mask.count_ones() as usize;
+		if chance[mask] == 0.0 || place >= paid {
+			continue;
+		}
+
+		let rest: f64 = (0..players).filter(|p| mask & (1 << p) == 0).map(|p| stacks[p]).sum();
+		let left = players - place;
+		for player in (0..players).filter(|p| mask & (1 << p) == 0) {
+			let share = if rest > 0.0 { stacks[player] / rest } else { 1.0 / left as f64 };
+			let next = chance[mask] * share;
+			equities[player] += next * payouts[place];
+			chance[mask | (1 << player)] += next;
+		}
+	}
+
+	return Ok(equities);
+}
+
+// The same model sampled: each trial orders the players by exponential
+// finishing times with rates given by the stacks
+pub fn monte_carlo(stacks: &[f64], payouts: &[f64], trials: usize, rng: &mut impl Rng) -> Result<Vec<f64>, IcmError> {
+	check(stacks, payouts)?;
+	let players = stacks.len();
+	let trials = trials.max(1);
+
+	let mut equities = vec![0.0; players];
+	let mut times: Vec<(f64, usize)> = Vec::with_capacity(players);
+	for _ in 0..trials {
+		times.clear();
+		for (player, stack) in stacks.iter().enumerate() {
+			let uniform: f64 = rng.gen_range(f64::MIN_POSITIVE, 1.0);
+			let time = if *stack > 0.0 { -uniform.ln() / stack } else { f64::INFINITY };
+			times.push((time, player));
+		}
+
+		times.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+		// players without chips split what is left
+		let busted = stacks.iter().filter(|s| **s <= 0.0).count();
+		let shared: f64 = payouts.iter().skip(players - busted).take(busted).sum::<f64>() / busted.max(1) as f64;
+		for (place, (_, player)) in times.iter().enumerate() {
+			equities[*player] += if place < players - busted { payouts.get(place).copied().unwrap_or(0.0) } else { shared };
+		}
+	}
+
+	return Ok(equities.iter().map(|e| e / trials as f64).collect());
+}
+
+// Exact for small fields, sampled for large ones
+pub fn equities(stacks: &[f64], payouts: &[f64], rng: &mut impl Rng) -> Result<Vec<f64>, IcmError> {
+	if stacks.len() <= EXACT_PLAYERS {
+		return malmuth_harville(stacks, payouts);
+	}
+
+	return monte_carlo(stacks, payouts, DEFAULT_TRIALS, rng);
+}
+
+// A player all in and another deciding whether to call, everyone else having
+// folded. Stacks are from before the hand; posted is what each has put in the
+// pot already, such as blinds and antes.
+pub struct AllInSpot {
+	pub stacks: Vec<f64>,
+	pub posted: Vec<f64>,
+	pub shover: usize,
+	pub caller: usize
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct IcmDecision {
+	// all-in equity of the hand against the shoving range
+	pub win: f64,
+	pub tie: f64,
+	// prize equity of the caller after folding, and on average after calling
+	pub fold_ev: f64,
+	pub call_ev: f64,
+	// showdown equity the call needs to break even, in prizes and in chips
+	pub required_equity: f64,
+	pub chip_required_equity: f64,
+	pub should_call: bool
+}
+
+impl AllInSpot {
+	fn check(&self) -> Result<(), IcmError> {
+		let players = self.stacks.len();
+		if self.posted.len() != players || self.shover >= players || self.caller >= players || self.shover == self.caller {
+			return Err(IcmError::INVALID_SPOT);
+		}
+
+		if self.posted.iter().zip(self.stacks.iter()).any(|(p, s)| !p.is_finite() || *p < 0.0 || p > s) {
+			return Err(IcmError::INVALID_SPOT);
+		}
+
+		if self.stacks[self.shover] <= 0.0 || self.stacks[self.caller] <= 0.0 {
+			return Err(IcmError::INVALID_SPOT);
+		}
+
+		return Ok(());
+	}
+
+	// Stacks after the caller folds
+	pub fn fold_stacks(&self) -> Vec<f64> {
+		let mut stacks: Vec<f64> = self.stacks.iter().zip(self.posted.iter()).map(|(s, p)| s - p).collect();
+		stacks[self.shover] = self.stacks[self.shover] + self.posted.iter().enumerate().filter(|(i, _)| *i != self.shover).map(|(_, p)| p).sum::<f64>();
+		return stacks;
+	}
+
+	// Stacks after a call when the caller wins, splits or loses the pot
+	pub fn call_stacks(&self) -> [Vec<f64>; 3] {
+		let (shover, caller) = (self.shover, self.caller);
+		let covered = self.stacks[shover].min(self.stacks[caller]);
+		let dead: f64 = self.posted.iter().enumerate().filter(|(i, _)| *i != shover && *i != caller).map(|(_, p)| p).sum();
+		let pot = dead + 2.0 * covered;
+
+		let mut base: Vec<f64> = self.stacks.iter().zip(self.posted.iter()).map(|(s, p)| s - p).collect();
+		base[shover] = self.stacks[shover] - covered;
+		base[caller] = self.stacks[caller] - covered;
+
+		let mut outcomes = [base.clone(), base.clone(), base];
+		outcomes[0][caller] += pot;
+		outcomes[1][caller] += pot / 2.0;
+		outcomes[1][shover] += pot / 2.0;
+		outcomes[2][shover] += pot;
+		return outcomes;
+	}
+}
+
+// Whether calling an all-in is worth it in prizes, with the hand's equity
+// against the shoving range on the given board
+pub fn call_decision(spot: &AllInSpot, payouts: &[f64], hand: [Card; 2], range: &Range, board: &[Card], rng: &mut impl Rng) -> Result<IcmDecision, IcmError> {
+	spot.check()?;
+	check(&spot.stacks, payouts)?;
+
+	let result = equity::calculate(&[Range::from_combos(&[hand]), range.clone()], board, &[], rng).map_err(IcmError::EQUITY)?;
+	let (win, tie) = (result.players[0].win, result.players[0].tie);
+	let lose = 1.0 - win - tie;
+
+	let caller = spot.caller;
+	let fold_stacks = spot.fold_stacks();
+	let [won, split, lost] = spot.call_stacks();
+	let fold_ev = equities(&fold_stacks, payouts, rng)?[caller];
+	let prizes = [won.clone(), split, lost.clone()].iter().map(|s| equities(s, payouts, rng).map(|e| e[caller])).collect::<Result<Vec<f64>, IcmError>>()?;
+	let call_ev = win * prizes[0] + tie * prizes[1] + lose * prizes[2];
+
+	// ties left out: the share of wins at which calling and folding are worth the same
+	let breaking_even = |fold: f64, win: f64, lose: f64| if win > lose { ((fold - lose) / (win - lose)).clamp(0.0, 1.0) } else { 1.0 };
+
+	return Ok(IcmDecision {
+		win,
+		tie,
+		fold_ev,
+		call_ev,
+		required_equity: breaking_even(fold_ev, prizes[0], prizes[2]),
+		chip_required_equity: breaking_even(fold_stacks[caller], won[caller], lost[caller]),
+		should_call: call_ev > fold_ev
+	});
+}
@@ -0,0 +1,89 @@
+use super::*;
+
+use std::convert::TryInto;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[test]
+fn check_malmuth_harville() {
+	let equities = malmuth_harville(&[50.0, 30.0, 20.0], &[0.5, 0.3, 0.2]).unwrap();
+	// first 50%, second 0.3 * 50/70 + 0.2 * 50/80, third what is left
+	let second = 0.3 * 50.0 / 70.0 + 0.2 * 50.0 / 80.0;
+	let expected = 0.5 * 0.5 + second * 0.3 + (0.5 - second) * 0.2;
+	assert_eq!((equities[0] - expected).abs() < 1e-12, true);
+	assert_eq!((equities.iter().sum::<f64>() - 1.0).abs() < 1e-12, true);
+	assert_eq!(equities[0] > equities[1] && equities[1] > equities[2], true);
+
+	let equal = malmuth_harville(&[10.0; 6], &[60.0, 40.0]).unwrap();
+	assert_eq!(equal.iter().all(|e| (e - 100.0 / 6.0).abs() < 1e-9), true);
+
+	// a player without chips only gets the places nobody else can take
+	let busted = malmuth_harville(&[30.0, 0.0, 70.0], &[5.0, 3.0, 2.0]).unwrap();
+	assert_eq!(busted[1], 2.0);
+	assert_eq!(malmuth_harville(&[100.0], &[7.0, 3.0]).unwrap(), vec![7.0]);
+
+	assert_eq!(malmuth_harville(&[], &[1.0]).err(), Some(IcmError::NO_PLAYERS));
+	assert_eq!(malmuth_harville(&[10.0, -1.0], &[1.0]).err(), Some(IcmError::INVALID_STACKS));
+	assert_eq!(malmuth_harville(&[10.0, 5.0], &[]).err(), Some(IcmError::INVALID_PAYOUTS));
+	assert_eq!(malmuth_harville(&[1.0; 21], &[1.0]).err(), Some(IcmError::TOO_MANY_PLAYERS));
+}
+
+#[test]
+fn check_monte_carlo() {
+	let mut rng = StdRng::seed_from_u64(5);
+	let stacks = [12.0, 30.0, 7.5, 18.0, 25.0, 4.0, 9.0, 15.0, 40.0, 22.0];
+	let payouts = [40.0, 25.0, 15.0, 10.0];
+	let exact = malmuth_harville(&stacks, &payouts).unwrap();
+	let sampled = monte_carlo(&stacks, &payouts, 50_000, &mut rng).unwrap();
+	for (e, s) in exact.iter().zip(sampled.iter()) {
+		assert_eq!((e - s).abs() < 0.5, true);
+	}
+
+	let sampled = monte_carlo(&[30.0, 0.0, 70.0], &[5.0, 3.0, 2.0], 1000, &mut rng).unwrap();
+	assert_eq!(sampled[1], 2.0);
+
+	// large fields are sampled
+	let stacks: Vec<f64> = (1..=100).map(|s| s as f64).collect();
+	let payouts: Vec<f64> = (1..=15).rev().map(|p| p as f64).collect();
+	let large = equities(&stacks, &payouts, &mut rng).unwrap();
+	assert_eq!((large.iter().sum::<f64>() - payouts.iter().sum::<f64>()).abs() < 1e-6, true);
+	assert_eq!(large[99] > large[0], true);
+}
+
+#[test]
+fn check_call_decision() {
+	let mut rng = StdRng::seed_from_u64(5);
+	// on the bubble of a four player, three paid sit and go, the big blind
+	// faces a shove from the chip leader in the small blind
+	let spot = AllInSpot {
+		stacks: vec![2000.0, 4000.0, 2000.0, 2000.0],
+		posted: vec![0.0, 100.0, 200.0, 0.0],
+		shover: 1,
+		caller: 2
+	};
+
+	let payouts = [50.0, 30.0, 20.0];
+	let fold = spot.fold_stacks();
+	assert_eq!(fold, vec![2000.0, 4200.0, 1800.0, 2000.0]);
+	let [won, split, lost] = spot.call_stacks();
+	assert_eq!(won, vec![2000.0, 2000.0, 4000.0, 2000.0]);
+	assert_eq!(split, vec![2000.0, 4000.0, 2000.0, 2000.0]);
+	assert_eq!(lost, vec![2000.0, 6000.0, 0.0, 2000.0]);
+
+	let range = Range::parse("22+,A2+,K2+,Q2+,J2+,T2+,92+,82+,72+,62+,52+,42+,32").unwrap();
+	let decision = call_decision(&spot, &payouts, Card::parse_list("Ah Qd").unwrap()[..].try_into().unwrap(), &range, &[], &mut rng).unwrap();
+	assert_eq!(decision.win > 0.6, true);
+	assert_eq!((decision.chip_required_equity - 1800.0 / 4000.0).abs() < 1e-9, true);
+	assert_eq!(decision.required_equity > decision.chip_required_equity, true);
+	assert_eq!(decision.should_call, decision.call_ev > decision.fold_ev);
+	assert_eq!(decision.should_call, true);
+
+	// a hand that is fine for chips folds for prizes
+	let decision = call_decision(&spot, &payouts, Card::parse_list("8h 7h").unwrap()[..].try_into().unwrap(), &range, &[], &mut rng).unwrap();
+	assert_eq!(decision.win + decision.tie / 2.0 > decision.chip_required_equity, true);
+	assert_eq!(decision.should_call, false);
+
+	let bad = AllInSpot {stacks: vec![100.0, 100.0], posted: vec![0.0, 0.0], shover: 1, caller: 1};
+	assert_eq!(call_decision(&bad, &payouts, Card::parse_list("8h 7h").unwrap()[..].try_into().unwrap(), &range, &[], &mut rng).err(), Some(IcmError::INVALID_SPOT));
+}
@@ -0,0 +1,462 @@
+use crate::hand::Card;
+use crate::hand::Rank;
+use crate::hand::Suit;
+
+#[cfg(test)]
+mod tests;
+
+const MAX_ROUNDS: usize = 8;
+
+fn binomial(n: u64, k: u64) -> u64 {
+	if k > n {
+		return 0;
+	}
+
+	let k = if k > n - k { n - k } else { k };
+	let mut result = 1u128;
+	for i in 0..k {
+		result = result * (n - i) as u128 / (i + 1) as u128;
+	}
+
+	return result as u64;
+}
+
+// Colexicographic rank of a strictly increasing combination.
+fn rank_combination(elements: &[u64]) -> u64 {
+	let mut result = 0;
+	for (i, e) in elements.iter().enumerate() {
+		result += binomial(*e, i as u64 + 1);
+	}
+
+	return result;
+}
+
+fn unrank_combination(mut index: u64, k: usize, elements: &mut Vec<u64>) {
+	elements.clear();
+	elements.resize(k, 0);
+
+	let mut j = k;
+	while j > 0 {
+		let mut low = j as u64 - 1;
+		let mut high = j as u64;
+		while binomial(high, j as u64) <= index {
+			low = high;
+			high *= 2;
+		}
+
+		while high - low > 1 {
+			let mid = (low + high) / 2;
+			if binomial(mid, j as u64) <= index {
+				low = mid;
+			} else {
+				high = mid;
+			}
+		}
+
+		elements[j - 1] = low;
+		index -= binomial(low, j as u64);
+		j -= 1;
+	}
+}
+
+fn compress(mask: u64, used: u64) -> Vec<u64> {
+	let mut positions = Vec::new();
+	let mut bits = mask;
+	while bits != 0 {
+		let bit = bits.trailing_zeros() as u64;
+		positions.push(bit - (used & ((1u64 << bit) - 1)).count_ones() as u64);
+		bits &= bits - 1;
+	}
+
+	return positions;
+}
+
+fn expand(position: u64, used: u64) -> u64 {
+	let mut remaining = position;
+	let mut bit = 0u64;
+	loop {
+		if used & (1u64 << bit) == 0 {
+			if remaining == 0 {
+				return bit;
+			}
+
+			remaining -= 1;
+		}
+
+		bit += 1;
+	}
+}
+
+// Per-suit card counts for every round, packed four bits per round with the
+// first round most significant, so that packed values order lexicographically.
+type SuitShape = u32;
+
+#[derive(Clone)]
+struct Configuration {
+	shapes: [SuitShape; 4],
+	offset: u64,
+	// (first suit, number of suits, configurations per suit) for each run of equal shapes
+	groups: Vec<(usize, usize, u64)>
+}
+
+pub struct HandIndexer {
+	cards_per_round: Vec<usize>,
+	isomorphic: bool,
+	configurations: Vec<Vec<Configuration>>,
+	sizes: Vec<u64>
+}
+
+impl HandIndexer {
+	pub fn new(cards_per_round: &[usize], isomorphic: bool) -> HandIndexer {
+		assert!(!cards_per_round.is_empty() && cards_per_round.len() <= MAX_ROUNDS, "Invalid number of rounds provided!");
+		assert!(cards_per_round.iter().sum::<usize>() <= 52, "Too many cards provided!");
+
+		let mut indexer = HandIndexer {
+			cards_per_round: cards_per_round.to_vec(),
+			isomorphic,
+			configurations: Vec::new(),
+			sizes: Vec::new()
+		};
+
+		let mut dealt = 0u64;
+		for (round, cards) in cards_per_round.iter().enumerate() {
+			if isomorphic {
+				let configurations = indexer.build_configurations(round);
+				let last = configurations.last().unwrap();
+				indexer.sizes.push(last.offset + HandIndexer::configuration_size(last));
+				indexer.configurations.push(configurations);
+			} else {
+				let previous = if round == 0 { 1 } else { indexer.sizes[round - 1] };
+				indexer.sizes.push(previous * binomial(52 - dealt, *cards as u64));
+			}
+
+			dealt += *cards as u64;
+		}
+
+		return indexer;
+	}
+
+	pub fn holdem(isomorphic: bool) -> HandIndexer {
+		return HandIndexer::new(&[2, 3, 1, 1], isomorphic);
+	}
+
+	pub fn rounds(&self) -> usize {
+		return self.cards_per_round.len();
+	}
+
+	pub fn size(&self, round: usize) -> u64 {
+		return self.sizes[round];
+	}
+
+	pub fn index(&self, hole: &[Card], board: &[Card]) -> u64 {
+		let mut cards = hole.to_vec();
+		cards.extend_from_slice(board);
+		assert!(hole.len() == self.cards_per_round[0], "Invalid number of hole cards provided!");
+
+		let round = self.round_for(cards.len());
+		if self.isomorphic {
+			return self.index_isomorphic(&cards, round);
+		} else {
+			return self.index_plain(&cards, round);
+		}
+	}
+
+	pub fn unindex(&self, round: usize, index: u64) -> (Vec<Card>, Vec<Card>) {
+		assert!(index < self.sizes[round], "Index out of range!");
+
+		let mut cards = if self.isomorphic {
+			self.unindex_isomorphic(round, index)
+		} else {
+			self.unindex_plain(round, index)
+		};
+
+		let board = cards.split_off(self.cards_per_round[0]);
+		return (cards, board);
+	}
+
+	fn round_for(&self, total: usize) -> usize {
+		let mut dealt = 0;
+		for (round, cards) in self.cards_per_round.iter().enumerate() {
+			dealt += cards;
+			if dealt == total {
+				return round;
+			}
+		}
+
+		panic!("Card count does not match any round!");
+	}
+
+	fn round_slices<'a>(&self, cards: &'a [Card], round: usize) -> Vec<&'a [Card]> {
+		let mut slices = Vec::new();
+		let mut start = 0;
+		for r in 0..=round {
+			slices.push(&cards[start..start + self.cards_per_round[r]]);
+			start += self.cards_per_round[r];
+		}
+
+		return slices;
+	}
+
+	fn index_plain(&self, cards: &[Card], round: usize) -> u64 {
+		let mut used = 0u64;
+		let mut dealt = 0u64;
+		let mut result = 0u64;
+
+		for (r, round_cards) in self.round_slices(cards, round).iter().enumerate() {
+			let mut mask = 0u64;
+			for card in round_cards.iter() {
+				let bit = 1u64 << card.to_index();
+				assert!(mask & bit == 0 && used & bit == 0, "Repeating cards provided!");
+				mask |= bit;
+			}
+
+			let positions = compress(mask, used);
+			result = result * binomial(52 - dealt, self.cards_per_round[r] as u64) + rank_combination(&positions);
+			used |= mask;
+			dealt += self.cards_per_round[r] as u64;
+		}
+
+		return result;
+	}
+
+	fn unindex_plain(&self, round: usize, index: u64) -> Vec<Card> {
+		let mut radices = Vec::new();
+		let mut dealt = 0u64;
+		for r in 0..=round {
+			radices.push(binomial(52 - dealt, self.cards_per_round[r] as u64));
+			dealt += self.cards_per_round[r] as u64;
+		}
+
+		let mut round_ranks = vec![0u64; round + 1];
+		let mut remaining = index;
+		for (rank, radix) in round_ranks.iter_mut().zip(radices.iter()).rev() {
+			*rank = remaining % radix;
+			remaining /= radix;
+		}
+
+		let mut cards = Vec::new();
+		let mut used = 0u64;
+		let mut positions = Vec::new();
+		for (r, rank) in round_ranks.iter().enumerate() {
+			unrank_combination(*rank, self.cards_per_round[r], &mut positions);
+			let mut mask = 0u64;
+			for position in &positions {
+				mask |= 1u64 << expand(*position, used);
+			}
+
+			let mut bits = mask;
+			while bits != 0 {
+				cards.push(Card::from_index(bits.trailing_zeros() as u8));
+				bits &= bits - 1;
+			}
+
+			used |= mask;
+		}
+
+		return cards;
+	}
+
+	fn shape_size(&self, shape: SuitShape, round: usize) -> u64 {
+		let mut size = 1u64;
+		let mut used = 0u64;
+		for r in 0..=round {
+			let count = ((shape >> (4 * (round - r))) & 0xf) as u64;
+			size *= binomial(13 - used, count);
+			used += count;
+		}
+
+		return size;
+	}
+
+	fn configuration_size(configuration: &Configuration) -> u64 {
+		let mut size = 1u64;
+		for group in &configuration.groups {
+			size *= binomial(group.2 + group.1 as u64 - 1, group.1 as u64);
+		}
+
+		return size;
+	}
+
+	fn build_configurations(&self, round: usize) -> Vec<Configuration> {
+		let mut shapes_list: Vec<[SuitShape; 4]> = vec![[0; 4]];
+		for r in 0..=round {
+			let mut next = Vec::new();
+			for shapes in &shapes_list {
+				let used: Vec<u32> = (0..4).map(|s| {
+					let mut total = 0;
+					for p in 0..r {
+						total += (shapes[s] >> (4 * (r - 1 - p))) & 0xf;
+					}
+
+					total
+				}).collect();
+
+				for a in 0..=self.cards_per_round[r] {
+					for b in 0..=self.cards_per_round[r] - a {
+						for c in 0..=self.cards_per_round[r] - a - b {
+							let d = self.cards_per_round[r] - a - b - c;
+							let counts = [a as u32, b as u32, c as u32, d as u32];
+							if (0..4).any(|s| used[s] + counts[s] > 13) {
+								continue;
+							}
+
+							let mut extended = [0; 4];
+							for s in 0..4 {
+								extended[s] = (shapes[s] << 4) | counts[s];
+							}
+
+							extended.sort_by(|x, y| y.cmp(x));
+							next.push(extended);
+						}
+					}
+				}
+			}
+
+			next.sort();
+			next.dedup();
+			shapes_list = next;
+		}
+
+		let mut configurations = Vec::new();
+		let mut offset = 0u64;
+		for shapes in shapes_list {
+			let mut groups = Vec::new();
+			let mut start = 0;
+			while start < 4 {
+				let mut end = start + 1;
+				while end < 4 && shapes[end] == shapes[start] {
+					end += 1;
+				}
+
+				groups.push((start, end - start, self.shape_size(shapes[start], round)));
+				start = end;
+			}
+
+			let configuration = Configuration {shapes, offset, groups};
+			offset += HandIndexer::configuration_size(&configuration);
+			configurations.push(configuration);
+		}
+
+		return configurations;
+	}
+
+	fn index_isomorphic(&self, cards: &[Card], round: usize) -> u64 {
+		let mut suits = [(0 as SuitShape, 0u64); 4];
+		let mut masks = vec![[0u16; 4]; round + 1];
+		let mut seen = 0u64;
+
+		for (r, round_cards) in self.round_slices(cards, round).iter().enumerate() {
+			for card in round_cards.iter() {
+				let bit = 1u64 << card.to_index();
+				assert!(seen & bit == 0, "Repeating cards provided!");
+				seen |= bit;
+				masks[r][card.suit.to_int() as usize] |= 1u16 << (card.rank.to_int() - 2);
+			}
+		}
+
+		for (s, suit) in suits.iter_mut().enumerate() {
+			let mut shape = 0 as SuitShape;
+			let mut suit_index = 0u64;
+			let mut used = 0u64;
+			for round_masks in &masks {
+				let mask = round_masks[s] as u64;
+				let count = mask.count_ones() as u64;
+				shape = (shape << 4) | count as SuitShape;
+				suit_index = suit_index * binomial(13 - used.count_ones() as u64, count) + rank_combination(&compress(mask, used));
+				used |= mask;
+			}
+
+			*suit = (shape, suit_index);
+		}
+
+		suits.sort_by(|x, y| y.cmp(x));
+		let shapes = [suits[0].0, suits[1].0, suits[2].0, suits[3].0];
+		let configurations = &self.configurations[round];
+		let position = configurations.binary_search_by(|c| c.shapes.cmp(&shapes)).unwrap();
+		let configuration = &configurations[position];
+
+		let mut result = 0u64;
+		let mut multiset = Vec::new();
+		for group in &configuration.groups {
+			multiset.clear();
+			for i in (0..group.1).rev() {
+				multiset.push(suits[group.0 + i].1 + (group.1 - 1 - i) as u64);
+			}
+
+			result = result * binomial(group.2 + group.1 as u64 - 1, group.1 as u64) + rank_combination(&multiset);
+		}
+
+		return configuration.offset + result;
+	}
+
+	fn unindex_isomorphic(&self, round: usize, index: u64) -> Vec<Card> {
+		let configurations = &self.configurations[round];
+		let position = match configurations.binary_search_by(|c| c.offset.cmp(&index)) {
+			Ok(position) => position,
+			Err(position) => position - 1
+		};
+		let configuration = &configurations[position];
+
+		let mut group_ranks = vec![0u64; configuration.groups.len()];
+		let mut remaining = index - configuration.offset;
+		for (g, group) in configuration.groups.iter().enumerate().rev() {
+			let radix = binomial(group.2 + group.1 as u64 - 1, group.1 as u64);
+			group_ranks[g] = remaining % radix;
+			remaining /= radix;
+		}
+
+		let mut suit_indices = [0u64; 4];
+		let mut multiset = Vec::new();
+		for (g, group) in configuration.groups.iter().enumerate() {
+			unrank_combination(group_ranks[g], group.1, &mut multiset);
+			for i in 0..group.1 {
+				suit_indices[group.0 + group.1 - 1 - i] = multiset[i] - i as u64;
+			}
+		}
+
+		let mut round_cards: Vec<Vec<Card>> = vec![Vec::new(); round + 1];
+		let mut positions = Vec::new();
+		for (s, suit) in Suit::iterator().enumerate() {
+			let shape = configuration.shapes[s];
+			let mut counts = Vec::new();
+			for r in 0..=round {
+				counts.push(((shape >> (4 * (round - r))) & 0xf) as u64);
+			}
+
+			let mut radices = Vec::new();
+			let mut used_count = 0u64;
+			for count in &counts {
+				radices.push(binomial(13 - used_count, *count));
+				used_count += count;
+			}
+
+			let mut subset_ranks = vec![0u64; round + 1];
+			let mut remaining = suit_indices[s];
+			for r in (0..=round).rev() {
+				subset_ranks[r] = remaining % radices[r];
+				remaining /= radices[r];
+			}
+
+			let mut used = 0u64;
+			for r in 0..=round {
+				unrank_combination(subset_ranks[r], counts[r] as usize, &mut positions);
+				let mut mask = 0u64;
+				for position in &positions {
+					let rank = expand(*position, used);
+					mask |= 1u64 << rank;
+					round_cards[r].push(Card {suit: *suit, rank: Rank::iterator().as_slice()[rank as usize]});
+				}
+
+				used |= mask;
+			}
+		}
+
+		let mut cards = Vec::new();
+		for mut round in round_cards {
+			round.sort_by_key(|c| c.to_index());
+			cards.extend(round);
+		}
+
+		return cards;
+	}
+}
@@ -0,0 +1,138 @@
+use super::*;
+
+use std::collections::HashSet;
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::eval::CardSet;
+use crate::iso;
+
+fn random_cards(rng: &mut StdRng, n: usize) -> Vec<Card> {
+	let mut cards = Vec::new();
+	let mut used = 0u64;
+	while cards.len() < n {
+		let index = rng.gen_range(0, 52);
+		if used & (1u64 << index) == 0 {
+			used |= 1u64 << index;
+			cards.push(Card::from_index(index));
+		}
+	}
+
+	return cards;
+}
+
+#[test]
+fn check_sizes() {
+	let plain = HandIndexer::holdem(false);
+	assert_eq!(plain.size(0), 1326);
+	assert_eq!(plain.size(1), 25989600);
+	assert_eq!(plain.size(2), 1221511200);
+	assert_eq!(plain.size(3), 56189515200);
+
+	let isomorphic = HandIndexer::holdem(true);
+	assert_eq!(isomorphic.size(0), 169);
+	assert_eq!(isomorphic.size(1), 1286792);
+	assert_eq!(isomorphic.size(2), 55190538);
+	assert_eq!(isomorphic.size(3), 2428287420);
+}
+
+#[test]
+fn check_preflop_is_dense() {
+	let deck: Vec<Card> = CardSet::FULL_DECK.iter().collect();
+	for isomorphic in [false, true].iter() {
+		let indexer = HandIndexer::holdem(*isomorphic);
+		let mut indices = HashSet::new();
+		for a in 0..deck.len() {
+			for b in a+1..deck.len() {
+				let index = indexer.index(&[deck[a], deck[b]], &[]);
+				assert_eq!(index < indexer.size(0), true);
+				indices.insert(index);
+			}
+		}
+
+		assert_eq!(indices.len() as u64, indexer.size(0));
+	}
+}
+
+#[test]
+fn check_roundtrip() {
+	for isomorphic in [false, true].iter() {
+		let indexer = HandIndexer::holdem(*isomorphic);
+		for round in 0..indexer.rounds() {
+			let step = indexer.size(round) / 2000 + 1;
+			let mut index = 0;
+			while index < indexer.size(round) {
+				let (hole, board) = indexer.unindex(round, index);
+				assert_eq!(indexer.index(&hole, &board), index);
+				index += step;
+			}
+		}
+	}
+}
+
+#[test]
+fn check_plain_unindex_returns_same_cards() {
+	let indexer = HandIndexer::holdem(false);
+	let mut rng = StdRng::seed_from_u64(29);
+	for _ in 0..500 {
+		let cards = random_cards(&mut rng, 7);
+		let index = indexer.index(&cards[..2], &cards[2..]);
+		let (hole, board) = indexer.unindex(3, index);
+		assert_eq!(CardSet::from_cards(&hole), CardSet::from_cards(&cards[..2]));
+		assert_eq!(CardSet::from_cards(&board), CardSet::from_cards(&cards[2..]));
+	}
+}
+
+// The 24 ways of relabelling the suits
+fn suit_permutations() -> Vec<iso::SuitPermutation> {
+	let suits = Suit::iterator().as_slice();
+	let mut permutations = Vec::new();
+	for a in suits {
+		for b in suits.iter().filter(|s| *s != a) {
+			for c in suits.iter().filter(|s| *s != a && *s != b) {
+				let d = suits.iter().find(|s| *s != a && *s != b && *s != c).unwrap();
+				permutations.push(iso::SuitPermutation([*a, *b, *c, *d]));
+			}
+		}
+	}
+
+	return permutations;
+}
+
+fn rounds_of(cards: &[Card], round: usize) -> Vec<CardSet> {
+	return [0..2, 2..5, 5..6, 6..7].iter().take(round + 1).map(|r| CardSet::from_cards(&cards[r.clone()])).collect();
+}
+
+#[test]
+fn check_isomorphic_hands_share_index() {
+	let indexer = HandIndexer::holdem(true);
+	let permutations = suit_permutations();
+	assert_eq!(permutations.len(), 24);
+
+	let mut rng = StdRng::seed_from_u64(28);
+	for _ in 0..200 {
+		let a = random_cards(&mut rng, 7);
+		let b = random_cards(&mut rng, 7);
+		for (round, n) in [(0, 2), (1, 5), (2, 6), (3, 7)].iter() {
+			let index = indexer.index(&a[..2], &a[2..*n]);
+			let class = iso::canonicalize_rounds(&rounds_of(&a, *round)).0;
+
+			// every relabelling of the suits is the same hand
+			for permutation in &permutations {
+				let relabelled: Vec<Card> = a[..*n].iter().map(|c| permutation.apply(*c)).collect();
+				assert_eq!(indexer.index(&relabelled[..2], &relabelled[2..]), index);
+			}
+
+			// the hand given back is one of the class
+			let (hole, board) = indexer.unindex(*round, index);
+			let unindexed: Vec<Card> = hole.iter().chain(board.iter()).copied().collect();
+			assert_eq!(iso::canonicalize_rounds(&rounds_of(&unindexed, *round)).0 == class, true);
+
+			// and other classes get other indexes
+			let same_class = iso::canonicalize_rounds(&rounds_of(&b, *round)).0 == class;
+			assert_eq!(indexer.index(&b[..2], &b[2..*n]) == index, same_class);
+		}
+	}
+}
@@ -0,0 +1,104 @@
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::hand::Suit;
+
+#[cfg(test)]
+mod tests;
+
+pub const MAX_ROUNDS: usize = 4;
+
+// Maps each original suit (indexed by Suit::to_int) to its canonical suit.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct SuitPermutation(pub [Suit; 4]);
+
+impl SuitPermutation {
+	pub fn identity() -> SuitPermutation {
+		return SuitPermutation([Suit::CLUBS, Suit::DIAMONDS, Suit::HEARTS, Suit::SPADES]);
+	}
+
+	pub fn apply(&self, card: Card) -> Card {
+		return Card {suit: self.0[card.suit.to_int() as usize], rank: card.rank};
+	}
+
+	pub fn apply_set(&self, cards: CardSet) -> CardSet {
+		let mut result = CardSet::EMPTY;
+		for suit in Suit::iterator() {
+			let target = self.0[suit.to_int() as usize];
+			result.0 |= (cards.suit_mask(*suit) as u64) << (target.to_int() as u64 * 16);
+		}
+
+		return result;
+	}
+
+	pub fn inverse(&self) -> SuitPermutation {
+		let mut inverse = SuitPermutation::identity();
+		for suit in Suit::iterator() {
+			inverse.0[self.0[suit.to_int() as usize].to_int() as usize] = *suit;
+		}
+
+		return inverse;
+	}
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct CanonicalHand {
+	pub hole: CardSet,
+	pub board: CardSet,
+	pub permutation: SuitPermutation
+}
+
+// Orders suits by their rank masks round by round (highest masks first), so
+// that isomorphic inputs always relabel to the same suits.
+pub fn canonical_permutation(rounds: &[CardSet]) -> SuitPermutation {
+	assert!(rounds.len() <= MAX_ROUNDS, "Too many rounds provided!");
+
+	let mut keys = [(0u64, 0usize); 4];
+	for suit in Suit::iterator() {
+		let mut key = 0u64;
+		for round in rounds {
+			key = (key << 16) | round.suit_mask(*suit) as u64;
+		}
+
+		keys[suit.to_int() as usize] = (key, suit.to_int() as usize);
+	}
+
+	let mut i = 1;
+	while i < 4 {
+		let mut j = i;
+		while j > 0 && keys[j].0 > keys[j - 1].0 {
+			keys.swap(j, j - 1);
+			j -= 1;
+		}
+
+		i += 1;
+	}
+
+	let canonical_suits = Suit::iterator().as_slice();
+	let mut permutation = SuitPermutation::identity();
+	for (canonical, key) in keys.iter().enumerate() {
+		permutation.0[key.1] = canonical_suits[canonical];
+	}
+
+	return permutation;
+}
+
+pub fn canonicalize_rounds(rounds: &[CardSet]) -> ([CardSet; MAX_ROUNDS], SuitPermutation) {
+	let permutation = canonical_permutation(rounds);
+	let mut canonical = [CardSet::EMPTY; MAX_ROUNDS];
+	for (i, round) in rounds.iter().enumerate() {
+		canonical[i] = permutation.apply_set(*round);
+	}
+
+	return (canonical, permutation);
+}
+
+pub fn canonicalize(hole: &[Card], board: &[Card]) -> CanonicalHand {
+	let rounds = [CardSet::from_cards(hole), CardSet::from_cards(board)];
+	let (canonical, permutation) = canonicalize_rounds(&rounds);
+
+	return CanonicalHand {
+		hole: canonical[0],
+		board: canonical[1],
+		permutation
+	};
+}
@@ -0,0 +1,68 @@
+use super::*;
+
+use std::collections::HashSet;
+
+use crate::hand::cards;
+
+fn count_classes(n: usize) -> usize {
+	let deck: Vec<Card> = CardSet::FULL_DECK.iter().collect();
+	let mut classes = HashSet::new();
+	let mut indices: Vec<usize> = (0..n).collect();
+
+	loop {
+		let set = CardSet::from_cards(&indices.iter().map(|i| deck[*i]).collect::<Vec<Card>>());
+		classes.insert(canonicalize_rounds(&[set]).0[0]);
+
+		let mut i = n;
+		while i > 0 && indices[i - 1] == deck.len() - n + i - 1 {
+			i -= 1;
+		}
+
+		if i == 0 {
+			break;
+		}
+
+		indices[i - 1] += 1;
+		for j in i..n {
+			indices[j] = indices[j - 1] + 1;
+		}
+	}
+
+	return classes.len();
+}
+
+#[test]
+fn check_class_counts() {
+	assert_eq!(count_classes(2), 169);
+	assert_eq!(count_classes(3), 1755);
+	assert_eq!(count_classes(4), 16432);
+}
+
+#[test]
+fn check_canonical_form() {
+	let a = canonicalize(&cards("Ah Kh"), &cards("2h 7s 9d"));
+	let b = canonicalize(&cards("As Ks"), &cards("2s 7c 9h"));
+	assert_eq!(a.hole == b.hole && a.board == b.board, true);
+
+	let c = canonicalize(&cards("As Ks"), &cards("2c 7s 9h"));
+	assert_eq!(a.hole == c.hole && a.board == c.board, false);
+
+	let d = canonicalize(&cards("Ah Kd"), &[]);
+	assert_eq!(d.hole, CardSet::from_cards(&cards("Ac Kd")));
+}
+
+#[test]
+fn check_permutation() {
+	let hole = cards("Qd 5s");
+	let board = cards("Qs 8s 2h Jd");
+	let canonical = canonicalize(&hole, &board);
+	let inverse = canonical.permutation.inverse();
+
+	for card in &hole {
+		assert_eq!(canonical.hole.contains(canonical.permutation.apply(*card)), true);
+	}
+
+	assert_eq!(inverse.apply_set(canonical.hole), CardSet::from_cards(&hole));
+	assert_eq!(inverse.apply_set(canonical.board), CardSet::from_cards(&board));
+	assert_eq!(canonical.permutation.inverse().inverse(), canonical.permutation);
+}
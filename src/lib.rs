@@ -0,0 +1,49 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod hand;
+pub mod eval;
+pub mod iso;
+#[cfg(feature = "std")]
+pub mod indexer;
+#[cfg(feature = "std")]
+pub mod preflop;
+#[cfg(feature = "std")]
+pub mod range;
+#[cfg(feature = "std")]
+pub mod equity;
+#[cfg(feature = "std")]
+pub mod outs;
+#[cfg(feature = "std")]
+pub mod texture;
+#[cfg(feature = "std")]
+pub mod nuts;
+#[cfg(feature = "std")]
+pub mod strength;
+#[cfg(feature = "std")]
+pub mod abstraction;
+#[cfg(feature = "std")]
+pub mod cfr;
+#[cfg(feature = "std")]
+pub mod river;
+#[cfg(feature = "std")]
+pub mod pushfold;
+#[cfg(feature = "std")]
+pub mod icm;
+#[cfg(feature = "std")]
+pub mod ev;
+#[cfg(feature = "std")]
+pub mod showdown;
+#[cfg(feature = "std")]
+pub mod pot;
+#[cfg(feature = "std")]
+pub mod table;
+#[cfg(feature = "std")]
+pub mod history;
+#[cfg(feature = "std")]
+pub mod ohh;
+#[cfg(feature = "std")]
+pub mod audit;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod session;
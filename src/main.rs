@@ -1,24 +1,534 @@
-mod hand;
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::process;
 
-use hand::Hand;
-use hand::Rank;
-use hand::Suit;
-use hand::Card;
+use poker_eval::cfr;
+use poker_eval::cfr::Game;
+use poker_eval::cfr::Solver;
+use poker_eval::cfr::Variant;
+use poker_eval::equity;
+use poker_eval::equity::EquityResult;
+use poker_eval::ev;
+use poker_eval::ev::CallSpot;
+use poker_eval::eval;
+use poker_eval::eval::CardSet;
+use poker_eval::hand::Hand;
+use poker_eval::hand::Rank;
+use poker_eval::hand::Suit;
+use poker_eval::hand::Card;
+use poker_eval::history;
+use poker_eval::preflop::HandClass;
+use poker_eval::preflop::PreflopTable;
+use poker_eval::icm;
+use poker_eval::pushfold;
+use poker_eval::range::Range;
+use poker_eval::range::RangeError;
+use poker_eval::river;
+use poker_eval::river::RiverSolver;
+use poker_eval::river::RiverSpot;
+use poker_eval::session;
+use poker_eval::session::Command;
+use poker_eval::session::Session;
 
 use rand::Rng;
 use chrono::DateTime;
 use chrono::Utc;
+use serde_json::json;
+use serde_json::Value;
+
+#[cfg(test)]
+mod tests;
+
+const USAGE: &str = "\
+Usage: poker_eval [--json] <command> [arguments]
+
+Commands:
+  eval <cards>                          category, strength and description of 5 to 7 cards
+  equity <hand|range>... [--board <cards>] [--dead <cards>] [--trials <n>]
+                                        all-in equity of two or more hands or ranges
+  range expand <range>                  every class and combo of a range such as \"TT+,AKs\"
+  table build --out <file>              generate the 169x169 preflop equity table
+  cfr <kuhn|leduc> [--variant vanilla|cfr+|mccfr] [--iterations <n>] [--every <n>] [--out <file>]
+                                        solve a toy game, reporting exploitability and
+                                        writing the average strategy as JSON
+  river <oop range> <ip range> --board <cards> --pot <chips> --stack <chips>
+        [--sizes <shares of pot>] [--raises <n>] [--iterations <n>]
+                                        solve a heads-up river spot, e.g. --sizes 0.5,1
+  pushfold --stack <bb> [--ante <bb>] [--iterations <n>]
+                                        heads-up push/fold Nash chart for the small blind
+                                        and the big blind
+  icm --stacks <chips,...> --payouts <prizes,...>
+                                        prize equity of every stack
+  ev <hand> <range> --pot <chips> --call <chips> [--board <cards>] [--dead <cards>]
+                                        expected value of calling an all-in against a range,
+                                        combo by combo; the pot includes the bet
+  bench [--hands <n>]                   lookup benchmark over random five card hands
+  repl                                  interactive shell for exploring a spot
+  help                                  show this message
+
+Exit codes: 0 on success, 1 on invalid input, 2 on invalid usage, 3 on I/O errors.";
+
+#[allow(non_camel_case_types)]
+enum CliError {
+	INVALID_INPUT(String),
+	USAGE(String),
+	IO(String)
+}
+
+impl CliError {
+	fn exit_code(&self) -> i32 {
+		match self {
+			CliError::INVALID_INPUT(_) => 1,
+			CliError::USAGE(_) => 2,
+			CliError::IO(_) => 3
+		}
+	}
+
+	fn message(&self) -> &str {
+		match self {
+			CliError::INVALID_INPUT(m) | CliError::USAGE(m) | CliError::IO(m) => m
+		}
+	}
+}
+
+// Human readable text, and the same result for scripts
+struct Output {
+	text: String,
+	json: Value
+}
+
+// Removes "--name value" from the arguments
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, CliError> {
+	let position = match args.iter().position(|a| a == name) {
+		Some(position) => position,
+		None => return Ok(None)
+	};
+
+	if position + 1 >= args.len() {
+		return Err(CliError::USAGE(format!("{} needs a value", name)));
+	}
+
+	let value = args.remove(position + 1);
+	args.remove(position);
+	return Ok(Some(value));
+}
+
+fn parse_number(value: &str, name: &str) -> Result<u64, CliError> {
+	return value.parse::<u64>().ok().filter(|n| *n > 0).ok_or_else(|| CliError::INVALID_INPUT(format!("{} must be a positive number, got '{}'", name, value)));
+}
+
+fn parse_cards(s: &str) -> Result<Vec<Card>, CliError> {
+	let cards = Card::parse_list(s).ok_or_else(|| CliError::INVALID_INPUT(format!("invalid cards '{}'", s)))?;
+	if CardSet::from_cards(&cards).len() != cards.len() {
+		return Err(CliError::INVALID_INPUT(format!("repeating cards in '{}'", s)));
+	}
+
+	return Ok(cards);
+}
+
+fn parse_range(s: &str) -> Result<Range, CliError> {
+	return Range::parse(s).map_err(|e| match e {
+		RangeError::INVALID_TOKEN(i) => CliError::INVALID_INPUT(format!("invalid range '{}' at '{}'", s, s.split(',').nth(i).unwrap_or("").trim())),
+		RangeError::EMPTY => CliError::INVALID_INPUT("empty range".to_string())
+	});
+}
+
+fn cards_to_string(cards: &[Card]) -> String {
+	return cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ");
+}
+
+fn percent(value: f64) -> String {
+	return format!("{:.2}%", value * 100.0);
+}
+
+fn eval_command(args: &[String]) -> Result<Output, CliError> {
+	if args.is_empty() {
+		return Err(CliError::USAGE("eval needs 5 to 7 cards".to_string()));
+	}
+
+	let cards = parse_cards(&args.join(" "))?;
+	if cards.len() < 5 || cards.len() > 7 {
+		return Err(CliError::INVALID_INPUT(format!("eval needs 5 to 7 cards, got {}", cards.len())));
+	}
+
+	let value = eval::evaluate_cards(&cards);
+	let mut best = eval::best_hand(&cards).cards;
+	best.sort();
+	best.reverse();
+
+	let category = format!("{:?}", value.category()).replace('_', " ").to_lowercase();
+	let strength = eval::equivalence_class(value);
+	let description = history::describe(value);
+
+	let text = format!("Cards:       {}\nBest hand:   {}\nCategory:    {}\nStrength:    {} of 7462\nDescription: {}",
+		cards_to_string(&cards), cards_to_string(&best), category, strength, description);
+	let json = json!({
+		"cards": cards.iter().map(|c| c.to_string()).collect::<Vec<String>>(),
+		"best_hand": best.iter().map(|c| c.to_string()).collect::<Vec<String>>(),
+		"category": category,
+		"strength": strength,
+		"description": description
+	});
+
+	return Ok(Output {text, json});
+}
+
+fn equity_command(args: &[String]) -> Result<Output, CliError> {
+	let mut args = args.to_vec();
+	let board = take_option(&mut args, "--board")?.map_or(Ok(Vec::new()), |b| parse_cards(&b))?;
+	let dead = take_option(&mut args, "--dead")?.map_or(Ok(Vec::new()), |d| parse_cards(&d))?;
+	let trials = take_option(&mut args, "--trials")?.map(|t| parse_number(&t, "--trials")).transpose()?;
+
+	if let Some(unknown) = args.iter().find(|a| a.starts_with("--")) {
+		return Err(CliError::USAGE(format!("unknown option {}", unknown)));
+	}
+
+	if args.len() < 2 {
+		return Err(CliError::USAGE("equity needs at least two hands or ranges".to_string()));
+	}
+
+	let ranges = args.iter().map(|a| parse_range(a)).collect::<Result<Vec<Range>, CliError>>()?;
+	let result = match trials {
+		Some(trials) => equity::monte_carlo(&ranges, &board, &dead, trials, &mut rand::thread_rng()),
+		None => equity::calculate(&ranges, &board, &dead, &mut rand::thread_rng())
+	};
+	let result: EquityResult = result.map_err(|e| CliError::INVALID_INPUT(format!("cannot compute equity: {:?}", e)))?;
+
+	let mut text = String::new();
+	if !board.is_empty() {
+		text += &format!("Board: {}\n", cards_to_string(&board));
+	}
+
+	text += &format!("{:<4}{:<24}{:>9}{:>9}{:>9}\n", "", "Hand", "Equity", "Win", "Tie");
+	for (i, (arg, player)) in args.iter().zip(result.players.iter()).enumerate() {
+		text += &format!("{:<4}{:<24}{:>9}{:>9}{:>9}\n", i + 1, arg, percent(player.equity), percent(player.win), percent(player.tie));
+	}
+	text += &format!("{} deals, {}", result.deals, if result.exact { "exact" } else { "sampled" });
+
+	let json = json!({
+		"board": board.iter().map(|c| c.to_string()).collect::<Vec<String>>(),
+		"dead": dead.iter().map(|c| c.to_string()).collect::<Vec<String>>(),
+		"exact": result.exact,
+		"deals": result.deals,
+		"players": args.iter().zip(ranges.iter()).zip(result.players.iter()).map(|((arg, range), player)| json!({
+			"hand": arg,
+			"combos": range.len(),
+			"equity": player.equity,
+			"win": player.win,
+			"tie": player.tie
+		})).collect::<Vec<Value>>()
+	});
+
+	return Ok(Output {text, json});
+}
+
+fn range_command(args: &[String]) -> Result<Output, CliError> {
+	if args.len() != 2 || args[0] != "expand" {
+		return Err(CliError::USAGE("usage: range expand <range>".to_string()));
+	}
+
+	let range = parse_range(&args[1])?;
+	let (classes, combos) = range.expand();
+	let combo_string = |c: &[Card; 2]| format!("{}{}", c[0], c[1]);
+
+	let mut text = String::new();
+	let mut groups: Vec<Value> = Vec::new();
+	for class in &classes {
+		let class_combos: Vec<String> = class.combos().iter().map(combo_string).collect();
+		text += &format!("{:<4} {:>2}  {}\n", class.to_string(), class_combos.len(), class_combos.join(" "));
+		groups.push(json!({"class": class.to_string(), "combos": class_combos}));
+	}
+
+	// combos of classes that are only partly in the range
+	for class in HandClass::iterator() {
+		let class_combos: Vec<String> = combos.iter().filter(|c| HandClass::from_cards(c[0], c[1]) == class).map(combo_string).collect();
+		if !class_combos.is_empty() {
+			text += &format!("{:<4} {:>2}  {}\n", class.to_string(), class_combos.len(), class_combos.join(" "));
+			groups.push(json!({"class": class.to_string(), "combos": class_combos}));
+		}
+	}
+
+	let share = range.len() as f64 / 1326.0;
+	text += &format!("{} combos, {} of all hands", range.len(), percent(share));
+	let json = json!({
+		"range": range.to_string(),
+		"classes": groups,
+		"combos": range.len(),
+		"share": share
+	});
+
+	return Ok(Output {text, json});
+}
+
+fn table_command(args: &[String]) -> Result<Output, CliError> {
+	let mut args = args.to_vec();
+	let out = take_option(&mut args, "--out")?;
+	if args.len() != 1 || args[0] != "build" || out.is_none() {
+		return Err(CliError::USAGE("usage: table build --out <file>".to_string()));
+	}
+
+	// fail on a bad path before the minutes long generation, not after
+	let path = out.unwrap();
+	let io_error = |e: io::Error| CliError::IO(format!("cannot write {}: {}", path, e));
+	let mut file = File::create(&path).map_err(io_error)?;
+	let table = PreflopTable::generate();
+	file.write_all(table.to_string().as_bytes()).map_err(io_error)?;
+
+	return Ok(Output {
+		text: format!("Wrote 169x169 preflop equity table to {}", path),
+		json: json!({"path": path, "classes": HandClass::COUNT})
+	});
+}
+
+fn solve<G: Game>(game: G, variant: Variant, iterations: u64, every: u64, out: Option<String>) -> Result<Output, CliError> {
+	let mut solver = Solver::new(game, variant);
+	let report = solver.solve(iterations, every, &mut rand::thread_rng());
+
+	let mut lines: Vec<String> = report.iter().map(|(i, e)| format!("Iteration {:>8}: exploitability {:.6}", i, e)).collect();
+	lines.push(format!("Game value:         {:.6}", solver.game_value()));
+	if let Some(path) = &out {
+		fs::write(path, solver.to_json()).map_err(|e| CliError::IO(format!("cannot write {}: {}", path, e)))?;
+		lines.push(format!("Wrote average strategy to {}", path));
+	}
+
+	return Ok(Output {
+		text: lines.join("\n"),
+		json: json!({
+			"game": solver.game.name(),
+			"variant": variant.to_string(),
+			"iterations": iterations,
+			"exploitability": report.iter().map(|(i, e)| json!({"iteration": i, "exploitability": e})).collect::<Vec<Value>>(),
+			"game_value": solver.game_value(),
+			"path": out
+		})
+	});
+}
+
+fn cfr_command(args: &[String]) -> Result<Output, CliError> {
+	let mut args = args.to_vec();
+	let variant = take_option(&mut args, "--variant")?;
+	let iterations = take_option(&mut args, "--iterations")?.map(|n| parse_number(&n, "--iterations")).transpose()?.unwrap_or(1000);
+	let every = take_option(&mut args, "--every")?.map(|n| parse_number(&n, "--every")).transpose()?.unwrap_or(iterations.div_ceil(10));
+	let out = take_option(&mut args, "--out")?;
+	if args.len() != 1 {
+		return Err(CliError::USAGE("usage: cfr <kuhn|leduc> [--variant vanilla|cfr+|mccfr] [--iterations <n>] [--every <n>] [--out <file>]".to_string()));
+	}
+
+	let variant = match variant {
+		Some(v) => Variant::parse(&v).ok_or_else(|| CliError::INVALID_INPUT(format!("unknown variant '{}'", v)))?,
+		None => Variant::CFR_PLUS
+	};
+
+	match args[0].as_str() {
+		"kuhn" => return solve(cfr::Kuhn, variant, iterations, every, out),
+		"leduc" => return solve(cfr::Leduc, variant, iterations, every, out),
+		game => return Err(CliError::INVALID_INPUT(format!("unknown game '{}'", game)))
+	}
+}
+
+fn river_command(args: &[String]) -> Result<Output, CliError> {
+	let mut args = args.to_vec();
+	let board = take_option(&mut args, "--board")?;
+	let pot = take_option(&mut args, "--pot")?;
+	let stack = take_option(&mut args, "--stack")?;
+	let sizes = take_option(&mut args, "--sizes")?.unwrap_or("0.5,1".to_string());
+	let raises = take_option(&mut args, "--raises")?.map(|n| n.parse::<usize>().map_err(|_| CliError::INVALID_INPUT(format!("--raises must be a number, got '{}'", n)))).transpose()?.unwrap_or(1);
+	let iterations = take_option(&mut args, "--iterations")?.map(|n| parse_number(&n, "--iterations")).transpose()?.unwrap_or(500);
+	if args.len() != 2 || board.is_none() || pot.is_none() || stack.is_none() {
+		return Err(CliError::USAGE("usage: river <oop range> <ip range> --board <cards> --pot <chips> --stack <chips> [--sizes <shares>] [--raises <n>] [--iterations <n>]".to_string()));
+	}
+
+	let bet_sizes = sizes.split(',').map(|s| s.trim().parse::<f64>().ok().filter(|s| *s > 0.0)).collect::<Option<Vec<f64>>>()
+		.ok_or_else(|| CliError::INVALID_INPUT(format!("invalid bet sizes '{}'", sizes)))?;
+	let spot = RiverSpot {
+		board: parse_cards(&board.unwrap())?,
+		ranges: [river::uniform(&parse_range(&args[0])?), river::uniform(&parse_range(&args[1])?)],
+		pot: parse_number(&pot.unwrap(), "--pot")?,
+		stack: stack.as_deref().unwrap().parse::<u64>().map_err(|_| CliError::INVALID_INPUT(format!("--stack must be a number, got '{}'", stack.as_deref().unwrap())))?,
+		bet_sizes,
+		max_raises: raises
+	};
+
+	let mut solver = RiverSolver::new(spot).map_err(|e| CliError::INVALID_INPUT(format!("invalid spot: {:?}", e)))?;
+	solver.solve(iterations, 0);
+	let exploitability = solver.exploitability();
+
+	let mut lines = vec![
+		format!("EV out of position: {:.2}", solver.ev(0)),
+		format!("EV in position:     {:.2}", solver.ev(1)),
+		format!("Exploitability:     {:.3} ({} of the pot)", exploitability, percent(exploitability / solver.spot.pot as f64))
+	];
+
+	// the first decision of each player, in position after a check
+	let mut players = Vec::new();
+	for (player, line) in [(0, ""), (1, "x")] {
+		let mut combos = Vec::new();
+		lines.push(format!("\n{} at '{}':", if player == 0 { "Out of position" } else { "In position" }, line));
+		for result in solver.combo_results(player) {
+			let strategy = solver.strategy(line, result.combo).unwrap();
+			let shares: Vec<String> = strategy.iter().map(|(a, s)| format!("{} {}", a, percent(*s))).collect();
+			lines.push(format!("  {}  EV {:>8.2}  {}", cards_to_string(&result.combo), result.ev, shares.join("  ")));
+			combos.push(json!({
+				"combo": cards_to_string(&result.combo),
+				"ev": result.ev,
+				"strategy": strategy.iter().map(|(a, s)| (a.clone(), json!(s))).collect::<serde_json::Map<String, Value>>()
+			}));
+		}
+
+		players.push(json!({"line": line, "combos": combos}));
+	}
+
+	return Ok(Output {
+		text: lines.join("\n"),
+		json: json!({
+			"iterations": iterations,
+			"ev": [solver.ev(0), solver.ev(1)],
+			"exploitability": exploitability,
+			"players": players
+		})
+	});
+}
+
+fn pushfold_command(args: &[String]) -> Result<Output, CliError> {
+	let mut args = args.to_vec();
+	let parse_float = |value: String, name: &str| value.parse::<f64>().map_err(|_| CliError::INVALID_INPUT(format!("{} must be a number, got '{}'", name, value)));
+	let stack = take_option(&mut args, "--stack")?.map(|s| parse_float(s, "--stack")).transpose()?;
+	let ante = take_option(&mut args, "--ante")?.map(|a| parse_float(a, "--ante")).transpose()?.unwrap_or(0.0);
+	let iterations = take_option(&mut args, "--iterations")?.map(|n| parse_number(&n, "--iterations")).transpose()?.unwrap_or(pushfold::DEFAULT_ITERATIONS as u64);
+	if !args.is_empty() || stack.is_none() {
+		return Err(CliError::USAGE("usage: pushfold --stack <bb> [--ante <bb>] [--iterations <n>]".to_string()));
+	}
+
+	let chart = pushfold::solve(stack.unwrap(), ante, iterations as usize).map_err(|e| CliError::INVALID_INPUT(format!("invalid spot: {:?}", e)))?;
+	let text = format!("Small blind pushes {} of hands:\n{}\n\nBig blind calls {} of hands:\n{}\n\nSmall blind EV: {:.3} bb, exploitability {:.4} bb",
+		percent(chart.push_share()), chart.push_grid(), percent(chart.call_share()), chart.call_grid(), chart.ev, chart.exploitability);
+
+	return Ok(Output {
+		text,
+		json: json!({
+			"stack": chart.stack,
+			"ante": chart.ante,
+			"push": chart.push_range().to_string(),
+			"call": chart.call_range().to_string(),
+			"push_share": chart.push_share(),
+			"call_share": chart.call_share(),
+			"ev": chart.ev,
+			"exploitability": chart.exploitability
+		})
+	});
+}
+
+fn parse_list(value: &str, name: &str) -> Result<Vec<f64>, CliError> {
+	return value.split(',').map(|v| v.trim().parse::<f64>().ok()).collect::<Option<Vec<f64>>>()
+		.ok_or_else(|| CliError::INVALID_INPUT(format!("{} must be numbers separated by commas, got '{}'", name, value)));
+}
+
+fn icm_command(args: &[String]) -> Result<Output, CliError> {
+	let mut args = args.to_vec();
+	let stacks = take_option(&mut args, "--stacks")?;
+	let payouts = take_option(&mut args, "--payouts")?;
+	if !args.is_empty() || stacks.is_none() || payouts.is_none() {
+		return Err(CliError::USAGE("usage: icm --stacks <chips,...> --payouts <prizes,...>".to_string()));
+	}
+
+	let stacks = parse_list(&stacks.unwrap(), "--stacks")?;
+	let payouts = parse_list(&payouts.unwrap(), "--payouts")?;
+	let equities = icm::equities(&stacks, &payouts, &mut rand::thread_rng()).map_err(|e| CliError::INVALID_INPUT(format!("invalid stacks or payouts: {:?}", e)))?;
+
+	let chips: f64 = stacks.iter().sum();
+	let prizes: f64 = payouts.iter().take(stacks.len()).sum();
+	let mut lines = vec![if stacks.len() <= icm::EXACT_PLAYERS { "Malmuth-Harville".to_string() } else { "Monte Carlo".to_string() }];
+	for (i, (stack, equity)) in stacks.iter().zip(equities.iter()).enumerate() {
+		lines.push(format!("Player {:>3}: {:>10} chips ({})  equity {:.4} ({})", i + 1, stack, percent(stack / chips), equity, percent(equity / prizes)));
+	}
+
+	return Ok(Output {
+		text: lines.join("\n"),
+		json: json!({"exact": stacks.len() <= icm::EXACT_PLAYERS, "stacks": stacks, "payouts": payouts, "equities": equities})
+	});
+}
+
+fn ev_command(args: &[String]) -> Result<Output, CliError> {
+	let mut args = args.to_vec();
+	let pot = take_option(&mut args, "--pot")?.map(|p| parse_number(&p, "--pot")).transpose()?;
+	let to_call = take_option(&mut args, "--call")?.map(|c| parse_number(&c, "--call")).transpose()?;
+	let board = take_option(&mut args, "--board")?.map_or(Ok(Vec::new()), |b| parse_cards(&b))?;
+	let dead = take_option(&mut args, "--dead")?.map_or(Ok(Vec::new()), |d| parse_cards(&d))?;
+	if args.len() != 2 || args.iter().any(|a| a.starts_with("--")) || pot.is_none() || to_call.is_none() {
+		return Err(CliError::USAGE("usage: ev <hand> <range> --pot <chips> --call <chips> [--board <cards>] [--dead <cards>]".to_string()));
+	}
+
+	let hand = parse_cards(&args[0])?;
+	if hand.len() != 2 {
+		return Err(CliError::INVALID_INPUT(format!("'{}' is not a two card hand", args[0])));
+	}
+
+	let spot = CallSpot {
+		pot: pot.unwrap() as f64,
+		to_call: to_call.unwrap() as f64,
+		hand: [hand[0], hand[1]],
+		range: parse_range(&args[1])?,
+		board,
+		dead
+	};
+	let analysis = ev::analyze_call(&spot, &mut rand::thread_rng()).map_err(|e| CliError::INVALID_INPUT(format!("cannot analyze the call: {:?}", e)))?;
+	let combo_string = |c: &[Card; 2]| format!("{}{}", c[0], c[1]);
+
+	let mut lines = Vec::new();
+	if !spot.board.is_empty() {
+		lines.push(format!("Board: {}", cards_to_string(&spot.board)));
+	}
+	lines.push(format!("Equity {} against {} combos, {} needed (pot odds {:.2} to 1)", percent(analysis.equity), analysis.combos.len(), percent(analysis.required_equity), analysis.pot_odds));
+	lines.push(format!("Call EV {:+.2}, fold EV {:+.2}: {}", analysis.call_ev, analysis.fold_ev, if analysis.should_call() { "call" } else { "fold" }));
+	match &analysis.breakeven {
+		Some(range) => lines.push(format!("Break-even against the strongest {} of the range: {}", percent(analysis.breakeven_share().unwrap()), range.to_string())),
+		None => lines.push("Calling loses against the whole range".to_string())
+	}
+	lines.push(format!("Profitable against: {}", if analysis.profitable.is_empty() { "nothing".to_string() } else { analysis.profitable.to_string() }));
+	lines.push(format!("{:<8}{:>9}{:>10}", "Combo", "Equity", "EV"));
+	for combo in &analysis.combos {
+		lines.push(format!("{:<8}{:>9}{:>+10.2}", combo_string(&combo.combo), percent(combo.equity), combo.ev));
+	}
+
+	return Ok(Output {
+		text: lines.join("\n"),
+		json: json!({
+			"board": spot.board.iter().map(|c| c.to_string()).collect::<Vec<String>>(),
+			"pot": spot.pot,
+			"to_call": spot.to_call,
+			"equity": analysis.equity,
+			"required_equity": analysis.required_equity,
+			"pot_odds": analysis.pot_odds,
+			"call_ev": analysis.call_ev,
+			"fold_ev": analysis.fold_ev,
+			"should_call": analysis.should_call(),
+			"profitable": analysis.profitable.to_string(),
+			"breakeven": analysis.breakeven.as_ref().map(|r| r.to_string()),
+			"combos": analysis.combos.iter().map(|c| json!({"combo": combo_string(&c.combo), "equity": c.equity, "ev": c.ev})).collect::<Vec<Value>>()
+		})
+	});
+}
+
+fn bench_command(args: &[String]) -> Result<Output, CliError> {
+	let mut args = args.to_vec();
+	let nr_h = take_option(&mut args, "--hands")?.map(|h| parse_number(&h, "--hands")).transpose()?.unwrap_or(10_000_000) as usize;
+	if !args.is_empty() {
+		return Err(CliError::USAGE("usage: bench [--hands <n>]".to_string()));
+	}
 
-fn main() {
 	let mut hands: Vec<Hand> = Vec::with_capacity(2598960);
-	
+
 	let mut deck = Vec::new();
 	for s in Suit::iterator() {
 		for r in Rank::iterator() {
 			deck.push(Card {rank:*r, suit: *s});
 		}
 	}
-	
+
 	for a in 0..deck.len() {
 		for b in a+1..deck.len() {
 			for c in b+1..deck.len() {
@@ -30,10 +540,10 @@ fn main() {
 			}
 		}
 	}
-	
+
 	hands.sort();
 	hands.reverse();
-	
+
 	let mut hands_ranked: Vec<(String, u32)> = Vec::with_capacity(2598960);
 	for i in 0..hands.len() {
 		if i == 0 {
@@ -46,12 +556,11 @@ fn main() {
 	}
 
 	let mut hands_ranked_int = vec![0; 134217728];
-	for i in 0..hands_ranked.len() {
-		let h = Hand::new_from_string(hands_ranked[i].0.to_string());
-		hands_ranked_int[h.to_int()] = hands_ranked[i].1;
+	for hand_ranked in &hands_ranked {
+		let h = Hand::new_from_string(hand_ranked.0.to_string());
+		hands_ranked_int[h.to_int()] = hand_ranked.1;
 	}
-	
-	let nr_h = 10_000_000;
+
 	let mut rng = rand::thread_rng();
 	let mut hands_test: Vec<Hand> = Vec::with_capacity(nr_h);
 	let mut i = 0;
@@ -83,14 +592,146 @@ fn main() {
 		hands_test.push(Hand {cards: hand_array});
 		i += 1;
 	}
-	
+
 	let utc_start: DateTime<Utc> = Utc::now();
 	for h in hands_test {
-		hands_ranked_int[h.to_int()];
+		std::hint::black_box(hands_ranked_int[h.to_int()]);
 	}
 	let utc_end: DateTime<Utc> = Utc::now();
 
-	println!("Total Hands: {}\nMH/s: {}\n{:?}", nr_h, 
-		((nr_h as f64/1000000 as f64) / (utc_end.signed_duration_since(utc_start).num_milliseconds() as f64 / 1000 as f64)), 
-		utc_end.signed_duration_since(utc_start));
-}
\ No newline at end of file
+	let duration = utc_end.signed_duration_since(utc_start);
+	let mhs = (nr_h as f64/1_000_000f64) / (duration.num_milliseconds().max(1) as f64 / 1000f64);
+	return Ok(Output {
+		text: format!("Total Hands: {}\nMH/s: {}\n{:?}", nr_h, mhs, duration),
+		json: json!({"hands": nr_h, "mhs": mhs, "milliseconds": duration.num_milliseconds()})
+	});
+}
+
+fn equity_lines(result: &EquityResult) -> String {
+	let mut lines = Vec::new();
+	for (i, player) in result.players.iter().enumerate() {
+		let name = if i == 0 { "Hero".to_string() } else { format!("Villain {}", i) };
+		lines.push(format!("{:<12}{:>9}", name, percent(player.equity)));
+	}
+
+	return lines.join("\n");
+}
+
+fn repl_command(args: &[String]) -> Result<Output, CliError> {
+	if !args.is_empty() {
+		return Err(CliError::USAGE("usage: repl".to_string()));
+	}
+
+	let mut session = Session::new();
+	let mut rng = rand::thread_rng();
+	let stdin = io::stdin();
+	println!("Type help for the list of commands.");
+
+	loop {
+		print!("> ");
+		io::stdout().flush().map_err(|e| CliError::IO(e.to_string()))?;
+
+		let mut line = String::new();
+		if stdin.lock().read_line(&mut line).map_err(|e| CliError::IO(e.to_string()))? == 0 {
+			break;
+		}
+
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		let command = match Command::parse(&line) {
+			Ok(command) => command,
+			Err(e) => {
+				println!("error: {:?}", e);
+				continue;
+			}
+		};
+
+		let result = match &command {
+			Command::QUIT => break,
+			Command::HELP => Ok(session::HELP.to_string()),
+			Command::SHOW => Ok(session.to_string()),
+			Command::EQUITY => session.equity(&mut rng).map(|result| {
+				format!("{}\n{} deals, {}", equity_lines(&result), result.deals, if result.exact { "exact" } else { "sampled" })
+			}),
+			Command::OUTS => session.outs().map(|outs| {
+				let cards: Vec<String> = outs.iter().map(|c| c.to_string()).collect();
+				format!("{} outs: {}", cards.len(), cards.join(" "))
+			}),
+			Command::SAVE(path) => match fs::write(path, session.to_json()) {
+				Ok(()) => Ok(format!("Saved to {}", path)),
+				Err(e) => Ok(format!("error: cannot write {}: {}", path, e))
+			},
+			Command::LOAD(path) => match fs::read_to_string(path) {
+				Ok(json) => session.load_json(&json).map(|_| session.to_string()),
+				Err(e) => Ok(format!("error: cannot read {}: {}", path, e))
+			},
+			_ => session.apply(&command, &mut rng).map(|_| {
+				// the spot changed, so show it with the live equity
+				match session.equity(&mut rand::thread_rng()) {
+					Ok(result) => format!("{}\n{}", session.to_string(), equity_lines(&result)),
+					Err(_) => session.to_string()
+				}
+			})
+		};
+
+		match result {
+			Ok(text) => println!("{}", text),
+			Err(e) => println!("error: {:?}", e)
+		}
+	}
+
+	return Ok(Output {text: String::new(), json: Value::Null});
+}
+
+fn run(args: &[String]) -> Result<Output, CliError> {
+	let command = match args.first() {
+		Some(command) => command.as_str(),
+		None => return Err(CliError::USAGE("no command given".to_string()))
+	};
+
+	match command {
+		"eval" => return eval_command(&args[1..]),
+		"equity" => return equity_command(&args[1..]),
+		"range" => return range_command(&args[1..]),
+		"table" => return table_command(&args[1..]),
+		"cfr" => return cfr_command(&args[1..]),
+		"river" => return river_command(&args[1..]),
+		"pushfold" => return pushfold_command(&args[1..]),
+		"icm" => return icm_command(&args[1..]),
+		"ev" => return ev_command(&args[1..]),
+		"bench" => return bench_command(&args[1..]),
+		"repl" => return repl_command(&args[1..]),
+		"help" | "--help" | "-h" => return Ok(Output {text: USAGE.to_string(), json: json!({"usage": USAGE})}),
+		_ => return Err(CliError::USAGE(format!("unknown command '{}'", command)))
+	}
+}
+
+fn main() {
+	let mut args: Vec<String> = env::args().skip(1).collect();
+	let json = args.iter().any(|a| a == "--json");
+	args.retain(|a| a != "--json");
+
+	match run(&args) {
+		Ok(output) => {
+			if json {
+				println!("{}", serde_json::to_string_pretty(&output.json).unwrap());
+			} else if !output.text.is_empty() {
+				println!("{}", output.text);
+			}
+		},
+		Err(e) => {
+			if json {
+				println!("{}", serde_json::to_string_pretty(&json!({"error": e.message(), "code": e.exit_code()})).unwrap());
+			} else {
+				eprintln!("error: {}", e.message());
+				if let CliError::USAGE(_) = e {
+					eprintln!("\n{}", USAGE);
+				}
+			}
+
+			process::exit(e.exit_code());
+		}
+	}
+}
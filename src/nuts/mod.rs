@@ -0,0 +1,127 @@
+use crate::eval;
+use crate::eval::CardSet;
+use crate::eval::HandValue;
+use crate::hand::Card;
+
+#[cfg(test)]
+mod tests;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum NutsError {
+	// hands are ranked on 3 to 5 board cards
+	INVALID_BOARD,
+	INVALID_HOLE_CARDS,
+	REPEATING_CARDS
+}
+
+// Every holding that makes the same hand with the board
+#[derive(Clone, PartialEq)]
+pub struct NutClass {
+	pub value: HandValue,
+	pub combos: Vec<[Card; 2]>
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RelativeStrength {
+	// opponent holdings that beat, tie and lose to the hand
+	pub better: usize,
+	pub tied: usize,
+	pub worse: usize,
+	// 1 for the nuts, counting every distinct better hand once
+	pub rank: usize,
+	// share of the opponent holdings beaten, ties counting half
+	pub percentile: f64
+}
+
+impl RelativeStrength {
+	pub fn is_nuts(&self) -> bool {
+		return self.better == 0;
+	}
+}
+
+fn check_board(board: &[Card], hole: &[Card]) -> Result<CardSet, NutsError> {
+	if board.len() < 3 || board.len() > 5 {
+		return Err(NutsError::INVALID_BOARD);
+	}
+
+	let known = CardSet::from_cards(board).union(CardSet::from_cards(hole));
+	if known.len() != board.len() + hole.len() {
+		return Err(NutsError::REPEATING_CARDS);
+	}
+
+	return Ok(known);
+}
+
+// All two card holdings left next to the known cards, with their value on the board
+fn holdings(board: CardSet, known: CardSet) -> Vec<([Card; 2], HandValue)> {
+	let deck: Vec<Card> = CardSet::FULL_DECK.difference(known).iter().collect();
+	let mut holdings = Vec::with_capacity(deck.len() * (deck.len() - 1) / 2);
+	for a in 0..deck.len() {
+		for b in a+1..deck.len() {
+			let combo = [deck[b], deck[a]];
+			holdings.push((combo, eval::evaluate(board.union(CardSet::from_cards(&combo)))));
+		}
+	}
+
+	return holdings;
+}
+
+// The strongest possible hands on the board, best first, up to the given count
+pub fn top_holdings(board: &[Card], count: usize) -> Result<Vec<NutClass>, NutsError> {
+	let board_set = check_board(board, &[])?;
+	let mut holdings = holdings(board_set, board_set);
+	holdings.sort_by_key(|h| std::cmp::Reverse(h.1));
+
+	let mut classes: Vec<NutClass> = Vec::new();
+	for (combo, value) in holdings {
+		if classes.last().is_some_and(|c| c.value == value) {
+			classes.last_mut().unwrap().combos.push(combo);
+		} else if classes.len() == count {
+			break;
+		} else {
+			classes.push(NutClass {value, combos: vec![combo]});
+		}
+	}
+
+	return Ok(classes);
+}
+
+pub fn nuts(board: &[Card]) -> Result<NutClass, NutsError> {
+	return Ok(top_holdings(board, 1)?.remove(0));
+}
+
+// Where the hand stands against every holding an opponent can have
+pub fn relative_strength(hole: &[Card], board: &[Card]) -> Result<RelativeStrength, NutsError> {
+	if hole.len() != 2 {
+		return Err(NutsError::INVALID_HOLE_CARDS);
+	}
+
+	let known = check_board(board, hole)?;
+	let board_set = CardSet::from_cards(board);
+	let value = eval::evaluate(known);
+
+	let mut better_values: Vec<HandValue> = Vec::new();
+	let (mut better, mut tied, mut worse) = (0, 0, 0);
+	for (_, other) in holdings(board_set, known) {
+		if other > value {
+			better += 1;
+			if !better_values.contains(&other) {
+				better_values.push(other);
+			}
+		} else if other == value {
+			tied += 1;
+		} else {
+			worse += 1;
+		}
+	}
+
+	let total = (better + tied + worse) as f64;
+	return Ok(RelativeStrength {
+		better,
+		tied,
+		worse,
+		rank: better_values.len() + 1,
+		percentile: (worse as f64 + tied as f64 / 2.0) / total
+	});
+}
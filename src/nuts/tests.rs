@@ -0,0 +1,57 @@
+use super::*;
+
+use crate::hand::HandRank;
+
+fn combo_names(class: &NutClass) -> Vec<String> {
+	return class.combos.iter().map(|c| format!("{}{}", c[0], c[1])).collect();
+}
+
+#[test]
+fn check_nuts() {
+	let board = Card::parse_list("Ah Kh Qh 7c 2d").unwrap();
+	let best = nuts(&board).unwrap();
+	assert_eq!(best.value.category(), HandRank::STRAIGHT_FLUSH);
+	assert_eq!(combo_names(&best), vec!["JhTh"]);
+
+	// flushes are told apart by their kickers
+	let top = top_holdings(&board, 3).unwrap();
+	assert_eq!(top.len(), 3);
+	assert_eq!(top[1].value.category(), HandRank::FLUSH);
+	assert_eq!(combo_names(&top[1]), vec!["Jh9h"]);
+	assert_eq!(combo_names(&top[2]), vec!["Jh8h"]);
+
+	let board = Card::parse_list("9c 9d 4h").unwrap();
+	let best = nuts(&board).unwrap();
+	assert_eq!(best.value.category(), HandRank::FOUR_OF_A_KIND);
+	assert_eq!(combo_names(&best), vec!["9s9h"]);
+	assert_eq!(top_holdings(&board, 2).unwrap()[1].value.category(), HandRank::FULL_HOUSE);
+}
+
+#[test]
+fn check_relative_strength() {
+	let board = Card::parse_list("Ah Kh Qh 7c 2d").unwrap();
+	let strength = relative_strength(&Card::parse_list("Ac Ad").unwrap(), &board).unwrap();
+	assert_eq!(strength.better, 60);
+	assert_eq!(strength.tied, 0);
+	assert_eq!(strength.worse, 930);
+	assert_eq!(strength.percentile, 930.0 / 990.0);
+	assert_eq!(strength.is_nuts(), false);
+
+	let strength = relative_strength(&Card::parse_list("Jh Th").unwrap(), &board).unwrap();
+	assert_eq!(strength.is_nuts(), true);
+	assert_eq!(strength.rank, 1);
+
+	// every other straight ties, every flush is a different better hand
+	let strength = relative_strength(&Card::parse_list("Jc Td").unwrap(), &board).unwrap();
+	assert_eq!(strength.tied, 8);
+	assert_eq!(strength.better, 45);
+	assert_eq!(strength.rank, 46);
+}
+
+#[test]
+fn check_errors() {
+	assert_eq!(nuts(&Card::parse_list("Ah Kh").unwrap()).err(), Some(NutsError::INVALID_BOARD));
+	assert_eq!(nuts(&Card::parse_list("Ah Ah 2c").unwrap()).err(), Some(NutsError::REPEATING_CARDS));
+	assert_eq!(relative_strength(&Card::parse_list("Ac").unwrap(), &Card::parse_list("Ah Kh Qh").unwrap()).err(), Some(NutsError::INVALID_HOLE_CARDS));
+	assert_eq!(relative_strength(&Card::parse_list("Ac Ah").unwrap(), &Card::parse_list("Ah Kh Qh").unwrap()).err(), Some(NutsError::REPEATING_CARDS));
+}
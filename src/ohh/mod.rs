@@ -0,0 +1,509 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::hand::Card;
+use crate::history::ActionKind;
+use crate::history::ActionRecord;
+use crate::history::Currency;
+use crate::history::HandRecord;
+use crate::history::SeatRecord;
+use crate::history::Tournament;
+use crate::pot;
+use crate::pot::PotError;
+use crate::showdown::split_pot;
+use crate::showdown::Showdown;
+use crate::showdown::Variant;
+use crate::table::BettingStructure;
+use crate::table::Post;
+use crate::table::Street;
+
+#[cfg(test)]
+mod tests;
+
+pub const SPEC_VERSION: &str = "1.4.6";
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum OhhError {
+	INVALID_JSON,
+	UNKNOWN_GAME,
+	UNKNOWN_CURRENCY,
+	UNKNOWN_STREET,
+	UNKNOWN_PLAYER,
+	INVALID_CARDS,
+	INVALID_AMOUNT,
+	INVALID_GAME_NUMBER,
+	INVALID_TOURNAMENT_NUMBER,
+	// the recorded pots do not match the ones built from the actions
+	POT_COUNT,
+	// index of the pot whose recorded winners are wrong
+	WRONG_WINNERS(usize),
+	// index of the pot whose winners were paid the wrong amounts
+	WRONG_AMOUNT(usize),
+	UNVERIFIABLE(PotError)
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhFile {
+	pub ohh: OhhHand
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhHand {
+	pub spec_version: String,
+	pub site_name: String,
+	pub tournament: bool,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tournament_info: Option<OhhTournamentInfo>,
+	pub game_number: String,
+	pub start_date_utc: String,
+	pub table_name: String,
+	pub game_type: String,
+	pub bet_limit: OhhBetLimit,
+	pub table_size: usize,
+	pub currency: String,
+	pub dealer_seat: usize,
+	pub small_blind_amount: f64,
+	pub big_blind_amount: f64,
+	pub ante_amount: f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub hero_player_id: Option<u32>,
+	pub players: Vec<OhhPlayer>,
+	pub rounds: Vec<OhhRound>,
+	pub pots: Vec<OhhPot>
+}
+
+// The standard has no blind level, so it does not survive the export.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhTournamentInfo {
+	pub tournament_number: String,
+	pub name: String,
+	pub currency: String,
+	pub buyin_amount: f64,
+	pub fee_amount: f64,
+	pub bounty_fee_amount: f64
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhBetLimit {
+	pub bet_type: String,
+	pub bet_cap: f64
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhPlayer {
+	pub id: u32,
+	pub seat: usize,
+	pub name: String,
+	pub starting_stack: f64,
+	pub is_sitting_out: bool
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhRound {
+	pub id: u32,
+	pub street: String,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub cards: Vec<String>,
+	pub actions: Vec<OhhAction>
+}
+
+// Amounts are the chips the action adds to the pot, raises included.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhAction {
+	pub action_number: u32,
+	pub player_id: u32,
+	pub action: String,
+	pub amount: f64,
+	pub is_allin: bool,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub cards: Vec<String>
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhPot {
+	pub number: u32,
+	pub amount: f64,
+	pub rake: f64,
+	pub player_wins: Vec<OhhWin>
+}
+
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OhhWin {
+	pub player_id: u32,
+	pub win_amount: f64
+}
+
+static GAME_TYPES: [(&str, Variant); 2] = [
+	("Holdem", Variant::HOLDEM),
+	("Omaha", Variant::OMAHA)
+];
+
+static BET_TYPES: [(&str, BettingStructure); 3] = [
+	("NL", BettingStructure::NO_LIMIT),
+	("PL", BettingStructure::POT_LIMIT),
+	("FL", BettingStructure::FIXED_LIMIT)
+];
+
+static STREETS: [(&str, Street); 5] = [
+	("Preflop", Street::PREFLOP),
+	("Flop", Street::FLOP),
+	("Turn", Street::TURN),
+	("River", Street::RIVER),
+	("Showdown", Street::SHOWDOWN)
+];
+
+static POSTS: [(&str, Post); 4] = [
+	("Post SB", Post::SMALL_BLIND),
+	("Post BB", Post::BIG_BLIND),
+	("Post Ante", Post::ANTE),
+	("Straddle", Post::STRADDLE)
+];
+
+pub fn parse(json: &str) -> Result<OhhHand, OhhError> {
+	return serde_json::from_str::<OhhFile>(json).map(|f| f.ohh).map_err(|_| OhhError::INVALID_JSON);
+}
+
+// Files hold one {"ohh": ..} object per hand. Reading stops at the first
+// object that is not valid JSON.
+pub fn parse_all(text: &str) -> Vec<Result<OhhHand, OhhError>> {
+	let mut hands = Vec::new();
+	for file in serde_json::Deserializer::from_str(text).into_iter::<OhhFile>() {
+		match file {
+			Ok(file) => hands.push(Ok(file.ohh)),
+			Err(_) => {
+				hands.push(Err(OhhError::INVALID_JSON));
+				break;
+			}
+		}
+	}
+
+	return hands;
+}
+
+pub fn to_json(hand: &OhhHand) -> String {
+	return serde_json::to_string_pretty(&OhhFile {ohh: hand.clone()}).unwrap();
+}
+
+fn to_units(currency: Currency, amount: f64) -> Result<u64, OhhError> {
+	let units = if currency == Currency::CHIPS { amount } else { amount * 100.0 };
+	if !units.is_finite() || units < 0.0 {
+		return Err(OhhError::INVALID_AMOUNT);
+	}
+
+	return Ok(units.round() as u64);
+}
+
+fn from_units(currency: Currency, amount: u64) -> f64 {
+	return if currency == Currency::CHIPS { amount as f64 } else { amount as f64 / 100.0 };
+}
+
+fn parse_currency(code: &str) -> Result<Currency, OhhError> {
+	return [Currency::CHIPS, Currency::USD, Currency::EUR, Currency::GBP].iter().find(|c| c.code() == code).copied().ok_or(OhhError::UNKNOWN_CURRENCY);
+}
+
+// Buy-in texts look like "$10+$1 USD", or "$10+$5+$1 USD" with a bounty
+fn split_buy_in(text: &str) -> Option<(Currency, u64, u64, u64)> {
+	let (amounts, code) = text.rsplit_once(' ').unwrap_or((text, ""));
+	let currency = parse_currency(code).ok()?;
+	let amounts: Vec<u64> = amounts.split('+').map(|a| currency.parse_amount(a)).collect::<Option<_>>()?;
+
+	return match amounts.as_slice() {
+		[buy_in, fee] => Some((currency, *buy_in, 0, *fee)),
+		[buy_in, bounty, fee] => Some((currency, *buy_in, *bounty, *fee)),
+		_ => None
+	};
+}
+
+fn to_tournament(info: &OhhTournamentInfo) -> Result<Tournament, OhhError> {
+	let id = info.tournament_number.parse().map_err(|_| OhhError::INVALID_TOURNAMENT_NUMBER)?;
+	let currency = parse_currency(&info.currency)?;
+	let units = |amount: f64| to_units(currency, amount);
+	let (buy_in, bounty, fee) = (units(info.buyin_amount)?, units(info.bounty_fee_amount)?, units(info.fee_amount)?);
+
+	let amounts = if bounty > 0 { vec![buy_in, bounty, fee] } else { vec![buy_in, fee] };
+	let text = amounts.iter().map(|a| currency.format_amount(*a)).collect::<Vec<String>>().join("+");
+	let buy_in = match currency {
+		_ if buy_in + bounty + fee == 0 => "Freeroll".to_string(),
+		Currency::CHIPS => text,
+		_ => format!("{} {}", text, currency.code())
+	};
+
+	return Ok(Tournament {id, buy_in, level: String::new()});
+}
+
+// Freerolls and buy-ins in other formats export as a zero buy-in
+fn from_tournament(tournament: &Tournament) -> OhhTournamentInfo {
+	let (currency, buy_in, bounty, fee) = split_buy_in(&tournament.buy_in).unwrap_or((Currency::CHIPS, 0, 0, 0));
+	return OhhTournamentInfo {
+		tournament_number: tournament.id.to_string(),
+		name: String::new(),
+		currency: currency.code().to_string(),
+		buyin_amount: from_units(currency, buy_in),
+		fee_amount: from_units(currency, fee),
+		bounty_fee_amount: from_units(currency, bounty)
+	};
+}
+
+fn parse_cards(cards: &[String]) -> Result<Vec<Card>, OhhError> {
+	return cards.iter().map(|c| Card::parse(c).ok_or(OhhError::INVALID_CARDS)).collect();
+}
+
+fn card_strings(cards: &[Card]) -> Vec<String> {
+	return cards.iter().map(|c| c.to_string()).collect();
+}
+
+pub fn to_record(hand: &OhhHand) -> Result<HandRecord, OhhError> {
+	let variant = GAME_TYPES.iter().find(|g| g.0 == hand.game_type).ok_or(OhhError::UNKNOWN_GAME)?.1;
+	let structure = BET_TYPES.iter().find(|b| b.0 == hand.bet_limit.bet_type).ok_or(OhhError::UNKNOWN_GAME)?.1;
+	let currency = if hand.tournament { Currency::CHIPS } else { parse_currency(&hand.currency)? };
+	let units = |amount: f64| to_units(currency, amount);
+
+	let small_blind = units(hand.small_blind_amount)?;
+	let big_blind = units(hand.big_blind_amount)?;
+	let mut record = HandRecord {
+		site: hand.site_name.clone(),
+		hand_id: hand.game_number.parse().map_err(|_| OhhError::INVALID_GAME_NUMBER)?,
+		tournament: hand.tournament_info.as_ref().map(to_tournament).transpose()?,
+		variant,
+		structure,
+		currency,
+		stakes: if structure == BettingStructure::FIXED_LIMIT { (big_blind, big_blind * 2) } else { (small_blind, big_blind) },
+		date: hand.start_date_utc.clone(),
+		table: hand.table_name.clone(),
+		max_seats: hand.table_size,
+		button: hand.dealer_seat,
+		seats: Vec::new(),
+		dealt: Vec::new(),
+		actions: Vec::new(),
+		board: Vec::new(),
+		total_pot: 0,
+		pots: Vec::new(),
+		rake: 0
+	};
+
+	for player in &hand.players {
+		record.seats.push(SeatRecord {seat: player.seat, name: player.name.clone(), stack: units(player.starting_stack)?, sitting_out: player.is_sitting_out});
+	}
+
+	let name = |id: u32| hand.players.iter().find(|p| p.id == id).map(|p| p.name.clone()).ok_or(OhhError::UNKNOWN_PLAYER);
+	let mut street = Street::PREFLOP;
+	for round in &hand.rounds {
+		street = STREETS.iter().find(|s| s.0 == round.street).ok_or(OhhError::UNKNOWN_STREET)?.1;
+		record.board.extend(parse_cards(&round.cards)?);
+
+		// raises are given as chips added, the record keeps "raise to" totals
+		let mut committed: Vec<(String, u64)> = Vec::new();
+		let mut current_bet = 0u64;
+		for action in &round.actions {
+			let player = name(action.player_id)?;
+			let amount = units(action.amount)?;
+			let already = committed.iter().find(|c| c.0 == player).map_or(0, |c| c.1);
+
+			let kind = match action.action.as_str() {
+				"Dealt Cards" => {
+					record.dealt.push((player, parse_cards(&action.cards)?));
+					continue;
+				},
+				"Post Dead" => ActionKind::POST_DEAD(amount),
+				"Fold" => ActionKind::FOLD,
+				"Check" => ActionKind::CHECK,
+				"Call" => ActionKind::CALL(amount),
+				"Bet" => ActionKind::BET(amount),
+				"Raise" => ActionKind::RAISE(already + amount - current_bet.min(already + amount), already + amount),
+				"Shows Cards" => ActionKind::SHOW(parse_cards(&action.cards)?),
+				"Mucks Cards" => ActionKind::MUCK(parse_cards(&action.cards)?),
+				other => match POSTS.iter().find(|p| p.0 == other) {
+					Some((_, post)) => ActionKind::POST(*post, amount),
+					None => continue
+				}
+			};
+
+			let live = match kind {
+				ActionKind::POST(Post::ANTE, _) => 0,
+				ActionKind::POST_DEAD(a) => a.min(record.big_blind()),
+				ActionKind::POST(_, a) | ActionKind::CALL(a) | ActionKind::BET(a) => a,
+				ActionKind::RAISE(_, _) => amount,
+				_ => 0
+			};
+
+			match committed.iter_mut().find(|c| c.0 == player) {
+				Some(c) => c.1 += live,
+				None => committed.push((player.clone(), live))
+			}
+			current_bet = current_bet.max(already + live);
+
+			record.actions.push(ActionRecord {street, player, kind, all_in: action.is_allin});
+		}
+	}
+
+	// The standard has no uncalled bet action; it is implied by the pots.
+	if let Some((seat, amount)) = pot::uncalled_bet(&record.contributions()) {
+		let last_bet = record.actions.iter().rposition(|a| !matches!(a.kind, ActionKind::SHOW(_) | ActionKind::MUCK(_)));
+		let position = last_bet.map_or(0, |i| i + 1);
+		let street = last_bet.map_or(Street::PREFLOP, |i| record.actions[i].street);
+		let player = record.seats.iter().find(|s| s.seat == seat).unwrap().name.clone();
+		record.actions.insert(position, ActionRecord {street, player, kind: ActionKind::UNCALLED(amount), all_in: false});
+	}
+
+	for (i, pot) in hand.pots.iter().enumerate() {
+		record.pots.push(units(pot.amount)?);
+		record.rake += units(pot.rake)?;
+		for win in &pot.player_wins {
+			let player = name(win.player_id)?;
+			record.actions.push(ActionRecord {street, player, kind: ActionKind::COLLECT(units(win.win_amount)?, i), all_in: false});
+		}
+	}
+
+	record.total_pot = record.pots.iter().sum();
+	return Ok(record);
+}
+
+pub fn from_record(record: &HandRecord) -> OhhHand {
+	let currency = record.currency;
+	let amount = |units: u64| from_units(currency, units);
+	let (small_blind, big_blind) = match record.structure {
+		BettingStructure::FIXED_LIMIT => (record.stakes.0 / 2, record.stakes.0),
+		_ => record.stakes
+	};
+	let ante = record.actions.iter().find_map(|a| match a.kind {
+		ActionKind::POST(Post::ANTE, ante) => Some(ante),
+		_ => None
+	});
+
+	let id = |name: &str| record.seats.iter().position(|s| s.name == name).unwrap() as u32;
+	let mut hand = OhhHand {
+		spec_version: SPEC_VERSION.to_string(),
+		site_name: record.site.clone(),
+		tournament: record.tournament.is_some(),
+		tournament_info: record.tournament.as_ref().map(from_tournament),
+		game_number: record.hand_id.to_string(),
+		start_date_utc: record.date.clone(),
+		table_name: record.table.clone(),
+		game_type: GAME_TYPES.iter().find(|g| g.1 == record.variant).unwrap().0.to_string(),
+		bet_limit: OhhBetLimit {bet_type: BET_TYPES.iter().find(|b| b.1 == record.structure).unwrap().0.to_string(), bet_cap: 0.0},
+		table_size: record.max_seats,
+		currency: record.currency.code().to_string(),
+		dealer_seat: record.button,
+		small_blind_amount: amount(small_blind),
+		big_blind_amount: amount(big_blind),
+		ante_amount: amount(ante.unwrap_or(0)),
+		hero_player_id: if record.dealt.len() == 1 { Some(id(&record.dealt[0].0)) } else { None },
+		players: record.seats.iter().enumerate().map(|(i, s)| OhhPlayer {
+			id: i as u32,
+			seat: s.seat,
+			name: s.name.clone(),
+			starting_stack: amount(s.stack),
+			is_sitting_out: s.sitting_out
+		}).collect(),
+		rounds: Vec::new(),
+		pots: Vec::new()
+	};
+
+	let added = record.chips_added();
+	let posts = record.actions.iter().take_while(|a| matches!(a.kind, ActionKind::POST(_, _) | ActionKind::POST_DEAD(_))).count();
+	let mut action_number = 0u32;
+	let mut next_action = |player_id: u32, action: &str, amount: f64, is_allin: bool, cards: Vec<String>| {
+		action_number += 1;
+		OhhAction {action_number, player_id, action: action.to_string(), amount, is_allin, cards}
+	};
+
+	for (street_name, street) in STREETS.iter() {
+		let cards = match street {
+			Street::FLOP if record.board.len() >= 3 => card_strings(&record.board[..3]),
+			Street::TURN if record.board.len() >= 4 => card_strings(&record.board[3..4]),
+			Street::RIVER if record.board.len() >= 5 => card_strings(&record.board[4..5]),
+			_ => Vec::new()
+		};
+
+		let mut actions = Vec::new();
+		for (i, (action, chips)) in record.actions.iter().zip(added.iter()).enumerate().filter(|(_, (a, _))| a.street == *street) {
+			if i == posts {
+				for (name, dealt) in &record.dealt {
+					actions.push(next_action(id(name), "Dealt Cards", 0.0, false, card_strings(dealt)));
+				}
+			}
+
+			let player_id = id(&action.player);
+			let (name, cards) = match &action.kind {
+				ActionKind::POST(post, _) => (POSTS.iter().find(|p| p.1 == *post).unwrap().0, Vec::new()),
+				ActionKind::POST_DEAD(_) => ("Post Dead", Vec::new()),
+				ActionKind::FOLD => ("Fold", Vec::new()),
+				ActionKind::CHECK => ("Check", Vec::new()),
+				ActionKind::CALL(_) => ("Call", Vec::new()),
+				ActionKind::BET(_) => ("Bet", Vec::new()),
+				ActionKind::RAISE(_, _) => ("Raise", Vec::new()),
+				ActionKind::SHOW(shown) => ("Shows Cards", card_strings(shown)),
+				ActionKind::MUCK(mucked) => ("Mucks Cards", card_strings(mucked)),
+				ActionKind::UNCALLED(_) | ActionKind::NO_SHOW | ActionKind::COLLECT(_, _) => continue
+			};
+
+			actions.push(next_action(player_id, name, amount(*chips), action.all_in, cards));
+		}
+
+		if *street == Street::PREFLOP || !cards.is_empty() || !actions.is_empty() {
+			hand.rounds.push(OhhRound {id: hand.rounds.len() as u32, street: street_name.to_string(), cards, actions});
+		}
+	}
+
+	for (i, pot) in record.pots.iter().enumerate() {
+		let player_wins = record.actions.iter().filter_map(|a| match a.kind {
+			ActionKind::COLLECT(won, p) if p == i => Some(OhhWin {player_id: id(&a.player), win_amount: amount(won)}),
+			_ => None
+		}).collect();
+
+		hand.pots.push(OhhPot {number: i as u32, amount: amount(*pot), rake: if i == 0 { amount(record.rake) } else { 0.0 }, player_wins});
+	}
+
+	return hand;
+}
+
+// Rebuilds the pots from the actions and re-evaluates the shown hands to
+// check that every pot went to the right players for the right amounts.
+// A player who never shows concedes to the shown hands.
+pub fn validate(hand: &OhhHand) -> Result<(), OhhError> {
+	let record = to_record(hand)?;
+	let players = record.seats.iter()
+		.filter(|s| record.folded(&s.name).is_none())
+		.filter_map(|s| record.hole_cards(&s.name).map(|cards| (s.seat, cards)))
+		.collect();
+
+	let showdown = Showdown {variant: record.variant, board: record.board.clone(), button: record.button, players};
+	let resolution = pot::settle_conceding(&record.contributions(), &showdown).map_err(OhhError::UNVERIFIABLE)?;
+	if resolution.pots.len() != record.pots.len() {
+		return Err(OhhError::POT_COUNT);
+	}
+
+	for (i, pot) in resolution.pots.iter().enumerate() {
+		let mut expected: Vec<usize> = resolution.awards.iter().filter(|a| a.pot == i).map(|a| a.seat).collect();
+		let mut recorded: Vec<(usize, u64)> = record.actions.iter().filter_map(|a| match a.kind {
+			ActionKind::COLLECT(amount, pot) if pot == i => record.seat(&a.player).map(|s| (s.seat, amount)),
+			_ => None
+		}).collect();
+
+		expected.sort();
+		recorded.sort();
+		if expected != recorded.iter().map(|r| r.0).collect::<Vec<usize>>() {
+			return Err(OhhError::WRONG_WINNERS(i));
+		}
+
+		// each pot carries its own rake, taken before the split
+		let rake = to_units(record.currency, hand.pots[i].rake)?;
+		let mut shares = split_pot(pot.amount.saturating_sub(rake), &expected, record.button);
+		shares.sort();
+		if shares != recorded {
+			return Err(OhhError::WRONG_AMOUNT(i));
+		}
+	}
+
+	return Ok(());
+}
@@ -0,0 +1,226 @@
+use super::*;
+
+use crate::history::parse_hand;
+use crate::showdown::ShowdownError;
+
+const CASH_HAND: &str = "\
+PokerStars Hand #220000000001:  Hold'em No Limit ($0.25/$0.50 USD) - 2021/06/01 20:00:00 ET
+Table 'Beta' 6-max Seat #3 is the button
+Seat 1: Eve ($50 in chips)
+Seat 3: Finn ($20 in chips)
+Seat 4: Gus ($60 in chips)
+Gus: posts small blind $0.25
+Eve: posts big blind $0.50
+*** HOLE CARDS ***
+Dealt to Finn [Th Tc]
+Finn: raises $1 to $1.50
+Gus: folds
+Eve: calls $1
+*** FLOP *** [Ts 6d 2s]
+Eve: bets $2
+Finn: raises $4 to $6
+Eve: raises $42.50 to $48.50 and is all-in
+Finn: calls $12.50 and is all-in
+Uncalled bet ($30) returned to Eve
+*** TURN *** [Ts 6d 2s] [Kh]
+*** RIVER *** [Ts 6d 2s Kh] [4s]
+*** SHOW DOWN ***
+Eve: shows [As Qs] (a flush, Ace high)
+Finn: shows [Th Tc] (three of a kind, Tens)
+Eve collected $38.60 from pot
+*** SUMMARY ***
+Total pot $40.25 | Rake $1.65
+Board [Ts 6d 2s Kh 4s]
+";
+
+const TOURNAMENT_HAND: &str = "\
+PokerStars Hand #210000000002: Tournament #3000000, $10+$1 USD Omaha Pot Limit - Level III (25/50) - 2020/01/01 13:00:00 ET
+Table '3000000 1' 9-max Seat #3 is the button
+Seat 1: Ann (1000 in chips)
+Seat 3: Ben (400 in chips)
+Seat 5: Cat (1500 in chips)
+Seat 7: Dan (200 in chips)
+Ann: posts the ante 5
+Ben: posts the ante 5
+Cat: posts the ante 5
+Dan: posts the ante 5
+Cat: posts small blind 25
+Dan: posts big blind 50
+*** HOLE CARDS ***
+Ann: raises 125 to 175
+Ben: raises 220 to 395 and is all-in
+Cat: folds
+Dan: calls 145 and is all-in
+Ann: calls 220
+*** FLOP *** [Ts 8c 3h]
+*** TURN *** [Ts 8c 3h] [2d]
+*** RIVER *** [Ts 8c 3h 2d] [7c]
+*** SHOW DOWN ***
+Ann: shows [As Ad 9c 6h] (a straight, Six to Ten)
+Ben: shows [Kd Kh 7s 6s] (a pair of Kings)
+Ann collected 400 from side pot
+Dan: shows [Jc 9d 5s 4s] (a straight, Seven to Jack)
+Dan collected 630 from main pot
+*** SUMMARY ***
+Total pot 1030 Main pot 630. Side pot 400. | Rake 0
+Board [Ts 8c 3h 2d 7c]
+";
+
+#[test]
+fn check_export() {
+	let hand = from_record(&parse_hand(CASH_HAND).unwrap());
+
+	assert_eq!(hand.spec_version, SPEC_VERSION);
+	assert_eq!(hand.site_name, "PokerStars");
+	assert_eq!(hand.game_number, "220000000001");
+	assert_eq!(hand.game_type, "Holdem");
+	assert_eq!(hand.bet_limit.bet_type, "NL");
+	assert_eq!(hand.currency, "USD");
+	assert_eq!(hand.small_blind_amount, 0.25);
+	assert_eq!(hand.big_blind_amount, 0.5);
+	assert_eq!(hand.dealer_seat, 3);
+	assert_eq!(hand.hero_player_id, Some(1));
+	assert_eq!(hand.players[2], OhhPlayer {id: 2, seat: 4, name: "Gus".to_string(), starting_stack: 60.0, is_sitting_out: false});
+
+	let streets: Vec<&str> = hand.rounds.iter().map(|r| r.street.as_str()).collect();
+	assert_eq!(streets, vec!["Preflop", "Flop", "Turn", "River", "Showdown"]);
+	assert_eq!(hand.rounds[1].cards, vec!["Ts", "6d", "2s"]);
+	assert_eq!(hand.rounds[3].cards, vec!["4s"]);
+
+	let preflop: Vec<&str> = hand.rounds[0].actions.iter().map(|a| a.action.as_str()).collect();
+	assert_eq!(preflop, vec!["Post SB", "Post BB", "Dealt Cards", "Raise", "Fold", "Call"]);
+	assert_eq!(hand.rounds[0].actions[2].cards, vec!["Th", "Tc"]);
+
+	let flop: Vec<(&str, f64, bool)> = hand.rounds[1].actions.iter().map(|a| (a.action.as_str(), a.amount, a.is_allin)).collect();
+	assert_eq!(flop, vec![("Bet", 2.0, false), ("Raise", 6.0, false), ("Raise", 46.5, true), ("Call", 12.5, true)]);
+
+	assert_eq!(hand.pots, vec![OhhPot {number: 0, amount: 40.25, rake: 1.65, player_wins: vec![OhhWin {player_id: 0, win_amount: 38.6}]}]);
+}
+
+#[test]
+fn check_export_tournament() {
+	let hand = from_record(&parse_hand(TOURNAMENT_HAND).unwrap());
+	let info = hand.tournament_info.unwrap();
+
+	assert_eq!(hand.tournament, true);
+	assert_eq!(hand.currency, "");
+	assert_eq!(info.tournament_number, "3000000");
+	assert_eq!(info.currency, "USD");
+	assert_eq!(info.buyin_amount, 10.0);
+	assert_eq!(info.fee_amount, 1.0);
+	assert_eq!(info.bounty_fee_amount, 0.0);
+
+	let mut record = parse_hand(TOURNAMENT_HAND).unwrap();
+	let tournament = record.tournament.as_mut().unwrap();
+	tournament.buy_in = "$4.40+$5+$0.60 USD".to_string();
+	let imported = to_record(&from_record(&record)).unwrap().tournament.unwrap();
+	assert_eq!(imported.buy_in, "$4.40+$5+$0.60 USD");
+
+	record.tournament.as_mut().unwrap().buy_in = "Freeroll".to_string();
+	let imported = to_record(&from_record(&record)).unwrap().tournament.unwrap();
+	assert_eq!(imported.buy_in, "Freeroll");
+}
+
+#[test]
+fn check_round_trip() {
+	for text in [CASH_HAND, TOURNAMENT_HAND].iter() {
+		let record = parse_hand(text).unwrap();
+		let hand = parse(&to_json(&from_record(&record))).unwrap();
+		let imported = to_record(&hand).unwrap();
+
+		assert_eq!(hand == from_record(&record), true);
+		assert_eq!(imported.seats == record.seats, true);
+		assert_eq!(imported.dealt == record.dealt, true);
+		assert_eq!(imported.board == record.board, true);
+		assert_eq!(imported.pots, record.pots);
+		assert_eq!(imported.rake, record.rake);
+		assert_eq!(imported.tournament.map(|t| (t.id, t.buy_in)), record.tournament.map(|t| (t.id, t.buy_in)));
+	}
+
+	// everything but the blind level survives for a hand whose collects
+	// follow the shown hands
+	let record = parse_hand(CASH_HAND).unwrap();
+	assert_eq!(to_record(&from_record(&record)).unwrap() == record, true);
+}
+
+#[test]
+fn check_parse_all() {
+	let cash = to_json(&from_record(&parse_hand(CASH_HAND).unwrap()));
+	let tournament = to_json(&from_record(&parse_hand(TOURNAMENT_HAND).unwrap()));
+	let hands = parse_all(&format!("{}\n\n{}\n\n{{\"ohh\": [", cash, tournament));
+
+	assert_eq!(hands.len(), 3);
+	assert_eq!(hands[0].as_ref().unwrap().game_number, "220000000001");
+	assert_eq!(hands[1].as_ref().unwrap().tournament, true);
+	assert_eq!(hands[2], Err(OhhError::INVALID_JSON));
+}
+
+#[test]
+fn check_import_errors() {
+	let hand = from_record(&parse_hand(CASH_HAND).unwrap());
+
+	let mut broken = hand.clone();
+	broken.game_type = "Stud".to_string();
+	assert_eq!(to_record(&broken).err(), Some(OhhError::UNKNOWN_GAME));
+
+	let mut broken = hand.clone();
+	broken.currency = "XYZ".to_string();
+	assert_eq!(to_record(&broken).err(), Some(OhhError::UNKNOWN_CURRENCY));
+
+	let mut broken = hand.clone();
+	broken.rounds[1].cards[0] = "1s".to_string();
+	assert_eq!(to_record(&broken).err(), Some(OhhError::INVALID_CARDS));
+
+	let mut broken = hand.clone();
+	broken.rounds[1].actions[0].player_id = 9;
+	assert_eq!(to_record(&broken).err(), Some(OhhError::UNKNOWN_PLAYER));
+
+	let mut broken = hand.clone();
+	broken.rounds[1].actions[0].amount = -2.0;
+	assert_eq!(to_record(&broken).err(), Some(OhhError::INVALID_AMOUNT));
+
+	let mut broken = from_record(&parse_hand(TOURNAMENT_HAND).unwrap());
+	broken.tournament_info.as_mut().unwrap().tournament_number = "MTT-1".to_string();
+	assert_eq!(to_record(&broken).err(), Some(OhhError::INVALID_TOURNAMENT_NUMBER));
+
+	assert_eq!(parse("{\"ohh\": 3}"), Err(OhhError::INVALID_JSON));
+}
+
+#[test]
+fn check_validate() {
+	let cash = from_record(&parse_hand(CASH_HAND).unwrap());
+	let tournament = from_record(&parse_hand(TOURNAMENT_HAND).unwrap());
+	assert_eq!(validate(&cash), Ok(()));
+	assert_eq!(validate(&tournament), Ok(()));
+
+	let mut wrong = cash.clone();
+	wrong.pots[0].player_wins[0].player_id = 1;
+	assert_eq!(validate(&wrong), Err(OhhError::WRONG_WINNERS(0)));
+
+	let mut wrong = tournament.clone();
+	wrong.pots[1].player_wins[0].player_id = 1;
+	assert_eq!(validate(&wrong), Err(OhhError::WRONG_WINNERS(1)));
+
+	let mut wrong = tournament.clone();
+	wrong.pots.pop();
+	assert_eq!(validate(&wrong), Err(OhhError::POT_COUNT));
+
+	let mut wrong = cash.clone();
+	wrong.pots[0].player_wins[0].win_amount = 40.25;
+	assert_eq!(validate(&wrong), Err(OhhError::WRONG_AMOUNT(0)));
+
+	// the loser mucks, the winner's shown hand takes the pot
+	let mut mucked = cash.clone();
+	mucked.rounds[4].actions.remove(1);
+	assert_eq!(validate(&mucked), Ok(()));
+
+	// the winner does not show and concedes to the loser
+	let mut hidden = cash.clone();
+	hidden.rounds[4].actions.remove(0);
+	assert_eq!(validate(&hidden), Err(OhhError::WRONG_WINNERS(0)));
+
+	let mut hidden = cash.clone();
+	hidden.rounds[0].actions.retain(|a| a.action != "Dealt Cards");
+	hidden.rounds[4].actions.clear();
+	assert_eq!(validate(&hidden), Err(OhhError::UNVERIFIABLE(PotError::SHOWDOWN(ShowdownError::NO_PLAYERS))));
+}
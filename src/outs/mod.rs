@@ -0,0 +1,177 @@
+use crate::eval;
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::hand::HandRank;
+use crate::hand::Suit;
+
+#[cfg(test)]
+mod tests;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum OutsError {
+	INVALID_HOLE_CARDS,
+	// outs are counted on the flop and the turn
+	INVALID_BOARD,
+	REPEATING_CARDS
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Draw {
+	// four to a flush with at least one hole card
+	FLUSH_DRAW,
+	// two ranks complete a straight, double gutshots included
+	OPEN_ENDED,
+	// one rank completes a straight
+	GUTSHOT,
+	// three to a flush on the flop
+	BACKDOOR_FLUSH,
+	// a straight two running cards away on the flop
+	BACKDOOR_STRAIGHT,
+	// number of hole cards above the board, for an unpaired hand
+	OVERCARDS(u8),
+	// a flush draw together with a straight draw
+	COMBO
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Out {
+	pub card: Card,
+	// the category the card improves the hand to
+	pub category: HandRank,
+	// against a known opponent hand: whether the improved hand is then ahead
+	pub clean: Option<bool>
+}
+
+#[derive(Clone, PartialEq)]
+pub struct OutsReport {
+	pub current: HandRank,
+	pub draws: Vec<Draw>,
+	pub outs: Vec<Out>
+}
+
+impl OutsReport {
+	pub fn clean_outs(&self) -> Vec<Card> {
+		return self.outs.iter().filter(|o| o.clean == Some(true)).map(|o| o.card).collect();
+	}
+
+	pub fn tainted_outs(&self) -> Vec<Card> {
+		return self.outs.iter().filter(|o| o.clean == Some(false)).map(|o| o.card).collect();
+	}
+}
+
+// A straight that the hole cards take part in, rather than one on the board
+fn makes_straight(all: u16, board: u16, extra: u16) -> bool {
+	return match eval::straight_high(all | extra) {
+		Some(high) => eval::straight_high(board | extra) != Some(high),
+		None => false
+	};
+}
+
+fn rank_mask(cards: &[Card]) -> u16 {
+	return cards.iter().fold(0u16, |mask, c| mask | (1u16 << (c.rank.to_int() - 2)));
+}
+
+fn check_cards(hole: &[Card], board: &[Card], opponent: Option<&[Card]>) -> Result<(), OutsError> {
+	if hole.len() != 2 || opponent.is_some_and(|o| o.len() != 2) {
+		return Err(OutsError::INVALID_HOLE_CARDS);
+	}
+
+	if board.len() < 3 || board.len() > 4 {
+		return Err(OutsError::INVALID_BOARD);
+	}
+
+	let opponent = opponent.unwrap_or(&[]);
+	let known = CardSet::from_cards(hole).union(CardSet::from_cards(board)).union(CardSet::from_cards(opponent));
+	if known.len() != hole.len() + board.len() + opponent.len() {
+		return Err(OutsError::REPEATING_CARDS);
+	}
+
+	return Ok(());
+}
+
+pub fn draws(hole: &[Card], board: &[Card]) -> Result<Vec<Draw>, OutsError> {
+	check_cards(hole, board, None)?;
+
+	let mut draws = Vec::new();
+	let flop = board.len() == 3;
+
+	let mut flush_draw = false;
+	for suit in Suit::iterator() {
+		let in_hole = hole.iter().filter(|c| c.suit == *suit).count();
+		let total = in_hole + board.iter().filter(|c| c.suit == *suit).count();
+		if in_hole > 0 && total == 4 {
+			flush_draw = true;
+			draws.push(Draw::FLUSH_DRAW);
+		} else if in_hole > 0 && total == 3 && flop {
+			draws.push(Draw::BACKDOOR_FLUSH);
+		}
+	}
+
+	let board_mask = rank_mask(board);
+	let all = board_mask | rank_mask(hole);
+	let mut straight_draw = false;
+	if eval::straight_high(all).is_none() {
+		let completing = (0..13).filter(|r| all & (1u16 << r) == 0 && makes_straight(all, board_mask, 1u16 << r)).count();
+		straight_draw = completing > 0;
+		if completing >= 2 {
+			draws.push(Draw::OPEN_ENDED);
+		} else if completing == 1 {
+			draws.push(Draw::GUTSHOT);
+		} else if flop {
+			let backdoor = (0..13).any(|a| (a + 1..13).any(|b| {
+				let extra = (1u16 << a) | (1u16 << b);
+				all & extra == 0 && makes_straight(all, board_mask, extra)
+			}));
+
+			if backdoor {
+				draws.push(Draw::BACKDOOR_STRAIGHT);
+			}
+		}
+	}
+
+	let cards: Vec<Card> = hole.iter().chain(board.iter()).copied().collect();
+	if eval::evaluate_cards(&cards).category() == HandRank::HIGH_CARD {
+		let top = board.iter().map(|c| c.rank).max().unwrap();
+		let overcards = hole.iter().filter(|c| c.rank > top).count();
+		if overcards > 0 {
+			draws.push(Draw::OVERCARDS(overcards as u8));
+		}
+	}
+
+	if flush_draw && straight_draw {
+		draws.push(Draw::COMBO);
+	}
+
+	return Ok(draws);
+}
+
+// Unseen cards that take the hand to a better category than both the current
+// one and what the board makes with the card alone. With an opponent hand the
+// outs are marked clean when they also put the hand ahead of it.
+pub fn outs(hole: &[Card], board: &[Card], opponent: Option<&[Card]>) -> Result<OutsReport, OutsError> {
+	let draws = draws(hole, board)?;
+	check_cards(hole, board, opponent)?;
+
+	let hole_set = CardSet::from_cards(hole);
+	let board_set = CardSet::from_cards(board);
+	let opponent_set = CardSet::from_cards(opponent.unwrap_or(&[]));
+	let current = eval::evaluate(hole_set.union(board_set)).category();
+
+	let mut outs = Vec::new();
+	for card in CardSet::FULL_DECK.difference(hole_set.union(board_set).union(opponent_set)).iter() {
+		let mut next_board = board_set;
+		next_board.insert(card);
+
+		let value = eval::evaluate(hole_set.union(next_board));
+		if value.category() >= current || value.category() >= eval::evaluate(next_board).category() {
+			continue;
+		}
+
+		let clean = opponent.map(|_| value > eval::evaluate(opponent_set.union(next_board)));
+		outs.push(Out {card, category: value.category(), clean});
+	}
+
+	return Ok(OutsReport {current, draws, outs});
+}
@@ -0,0 +1,54 @@
+use super::*;
+
+fn names(cards: &[Card]) -> Vec<String> {
+	return cards.iter().map(|c| c.to_string()).collect();
+}
+
+#[test]
+fn check_draws() {
+	assert_eq!(draws(&Card::parse_list("8h 9h").unwrap(), &Card::parse_list("6h 7c Kh").unwrap()).unwrap(), vec![Draw::FLUSH_DRAW, Draw::OPEN_ENDED, Draw::COMBO]);
+	assert_eq!(draws(&Card::parse_list("Ac Kd").unwrap(), &Card::parse_list("Qs Jh 4c").unwrap()).unwrap(), vec![Draw::GUTSHOT, Draw::OVERCARDS(2)]);
+	assert_eq!(draws(&Card::parse_list("Ah 5h").unwrap(), &Card::parse_list("Kh 8c 2d").unwrap()).unwrap(), vec![Draw::BACKDOOR_FLUSH, Draw::BACKDOOR_STRAIGHT, Draw::OVERCARDS(1)]);
+
+	// double gutshot, and no backdoors on the turn
+	assert_eq!(draws(&Card::parse_list("9c 7d").unwrap(), &Card::parse_list("Jh 5s Kd 2h").unwrap()).unwrap(), Vec::new());
+	assert_eq!(draws(&Card::parse_list("9c 7d").unwrap(), &Card::parse_list("Jh 5s 8d 2h").unwrap()).unwrap(), vec![Draw::OPEN_ENDED]);
+
+	// a straight on the board alone is not a draw, and pairs have no overcards
+	assert_eq!(draws(&Card::parse_list("2c 2d").unwrap(), &Card::parse_list("9h Ts Jd Qc").unwrap()).unwrap(), Vec::new());
+	assert_eq!(draws(&Card::parse_list("Kc 9d").unwrap(), &Card::parse_list("9h 5s 2d").unwrap()).unwrap(), Vec::new());
+}
+
+#[test]
+fn check_outs() {
+	let report = outs(&Card::parse_list("8h 9h").unwrap(), &Card::parse_list("6h 7c Kh").unwrap(), None).unwrap();
+	assert_eq!(report.current, HandRank::HIGH_CARD);
+	assert_eq!(report.outs.len(), 21);
+	assert_eq!(report.outs.iter().filter(|o| o.category == HandRank::FLUSH).count(), 9);
+	assert_eq!(report.outs.iter().filter(|o| o.category == HandRank::STRAIGHT).count(), 6);
+	assert_eq!(report.outs.iter().filter(|o| o.category == HandRank::PAIR).count(), 6);
+	assert_eq!(report.outs.iter().all(|o| o.clean.is_none()), true);
+
+	// pairing the board does not improve the hand
+	let report = outs(&Card::parse_list("Ac Qd").unwrap(), &Card::parse_list("Ks 7h 2c 3d").unwrap(), None).unwrap();
+	assert_eq!(names(&report.outs.iter().map(|o| o.card).collect::<Vec<Card>>()), vec!["Qc", "Ad", "Qh", "Ah", "Qs", "As"]);
+}
+
+#[test]
+fn check_clean_outs() {
+	let report = outs(&Card::parse_list("8h 9h").unwrap(), &Card::parse_list("6h 7c Kh").unwrap(), Some(&Card::parse_list("Kc Kd").unwrap())).unwrap();
+	assert_eq!(report.clean_outs().len(), 14);
+	assert_eq!(report.tainted_outs().len(), 7);
+	assert_eq!(report.tainted_outs().contains(&Card::new_from_string("7h".to_string())), true);
+	assert_eq!(report.clean_outs().contains(&Card::new_from_string("Ts".to_string())), true);
+	assert_eq!(report.outs.iter().any(|o| o.card == Card::new_from_string("Ks".to_string())), false);
+}
+
+#[test]
+fn check_errors() {
+	assert_eq!(outs(&Card::parse_list("8h").unwrap(), &Card::parse_list("6h 7c Kh").unwrap(), None).err(), Some(OutsError::INVALID_HOLE_CARDS));
+	assert_eq!(outs(&Card::parse_list("8h 9h").unwrap(), &Card::parse_list("6h 7c").unwrap(), None).err(), Some(OutsError::INVALID_BOARD));
+	assert_eq!(outs(&Card::parse_list("8h 9h").unwrap(), &Card::parse_list("6h 7c Kh 2c 3c").unwrap(), None).err(), Some(OutsError::INVALID_BOARD));
+	assert_eq!(outs(&Card::parse_list("8h 9h").unwrap(), &Card::parse_list("6h 7c Kh").unwrap(), Some(&Card::parse_list("8h 2c").unwrap())).err(), Some(OutsError::REPEATING_CARDS));
+	assert_eq!(draws(&Card::parse_list("8h 8h").unwrap(), &Card::parse_list("6h 7c Kh").unwrap()).err(), Some(OutsError::REPEATING_CARDS));
+}
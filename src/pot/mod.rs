@@ -0,0 +1,155 @@
+use std::cmp::Reverse;
+
+use crate::showdown::split_pot;
+use crate::showdown::Showdown;
+use crate::showdown::ShowdownError;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Contribution {
+	pub seat: usize,
+	pub amount: u64,
+	pub folded: bool
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Pot {
+	pub amount: u64,
+	pub eligible: Vec<usize>
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PotAward {
+	pub pot: usize,
+	pub seat: usize,
+	pub amount: u64
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PotError {
+	NO_ACTIVE_PLAYERS,
+	MISSING_HOLE_CARDS,
+	SHOWDOWN(ShowdownError)
+}
+
+// (seat, amount) of a bet nobody called, returned before building the pots
+pub type UncalledBet = (usize, u64);
+
+#[derive(Clone, Debug)]
+pub struct PotResolution {
+	pub pots: Vec<Pot>,
+	pub uncalled: Option<UncalledBet>,
+	pub awards: Vec<PotAward>
+}
+
+impl PotResolution {
+	pub fn total_for(&self, seat: usize) -> u64 {
+		let returned = match self.uncalled {
+			Some((s, amount)) if s == seat => amount,
+			_ => 0
+		};
+
+		return returned + self.awards.iter().filter(|a| a.seat == seat).map(|a| a.amount).sum::<u64>();
+	}
+}
+
+pub fn uncalled_bet(contributions: &[Contribution]) -> Option<UncalledBet> {
+	let mut ordered: Vec<&Contribution> = contributions.iter().collect();
+	ordered.sort_by_key(|c| Reverse(c.amount));
+
+	if ordered.is_empty() {
+		return None;
+	}
+
+	let second = if ordered.len() > 1 { ordered[1].amount } else { 0 };
+	if ordered[0].amount > second {
+		return Some((ordered[0].seat, ordered[0].amount - second));
+	}
+
+	return None;
+}
+
+// Main pot first, then side pots. Every contribution level of a player still
+// in the hand closes a pot; chips of folded players above the last level go
+// into the last pot.
+pub fn build_pots(contributions: &[Contribution]) -> Result<(Vec<Pot>, Option<UncalledBet>), PotError> {
+	let uncalled = uncalled_bet(contributions);
+	let called: Vec<Contribution> = contributions.iter().map(|c| {
+		match uncalled {
+			Some((seat, amount)) if seat == c.seat => Contribution {amount: c.amount - amount, ..*c},
+			_ => *c
+		}
+	}).collect();
+
+	let mut levels: Vec<u64> = called.iter().filter(|c| !c.folded).map(|c| c.amount).collect();
+	if levels.is_empty() {
+		return Err(PotError::NO_ACTIVE_PLAYERS);
+	}
+
+	levels.sort();
+	levels.dedup();
+
+	let mut pots: Vec<Pot> = Vec::new();
+	let mut previous = 0u64;
+	for level in &levels {
+		let amount = called.iter().map(|c| c.amount.min(*level) - c.amount.min(previous)).sum::<u64>();
+		let eligible = called.iter().filter(|c| !c.folded && c.amount >= *level).map(|c| c.seat).collect();
+
+		if amount > 0 || pots.is_empty() {
+			pots.push(Pot {amount, eligible});
+		}
+
+		previous = *level;
+	}
+
+	let remainder = called.iter().map(|c| c.amount - c.amount.min(previous)).sum::<u64>();
+	pots.last_mut().unwrap().amount += remainder;
+
+	return Ok((pots, uncalled));
+}
+
+pub fn settle(contributions: &[Contribution], showdown: &Showdown) -> Result<PotResolution, PotError> {
+	return resolve(contributions, showdown, false);
+}
+
+// For recorded hands: players still in at the end who did not show their
+// cards concede every pot that a shown hand also contests.
+pub fn settle_conceding(contributions: &[Contribution], showdown: &Showdown) -> Result<PotResolution, PotError> {
+	return resolve(contributions, showdown, true);
+}
+
+fn resolve(contributions: &[Contribution], showdown: &Showdown, concede: bool) -> Result<PotResolution, PotError> {
+	let (pots, uncalled) = build_pots(contributions)?;
+	let mut awards = Vec::new();
+
+	for (i, pot) in pots.iter().enumerate() {
+		let shown: Vec<usize> = pot.eligible.iter().copied().filter(|s| showdown.players.iter().any(|p| p.0 == *s)).collect();
+		let eligible = if concede && !shown.is_empty() { &shown } else { &pot.eligible };
+
+		if eligible.len() == 1 {
+			awards.push(PotAward {pot: i, seat: eligible[0], amount: pot.amount});
+			continue;
+		}
+
+		showdown.validate().map_err(PotError::SHOWDOWN)?;
+
+		let mut contenders = Vec::new();
+		for seat in eligible {
+			match showdown.players.iter().find(|p| p.0 == *seat) {
+				Some((_, hole)) => contenders.push((*seat, showdown.evaluate(hole).0)),
+				None => return Err(PotError::MISSING_HOLE_CARDS)
+			}
+		}
+
+		let best = contenders.iter().map(|c| c.1).max().unwrap();
+		let winners: Vec<usize> = contenders.iter().filter(|c| c.1 == best).map(|c| c.0).collect();
+		for (seat, amount) in split_pot(pot.amount, &winners, showdown.button) {
+			awards.push(PotAward {pot: i, seat, amount});
+		}
+	}
+
+	return Ok(PotResolution {pots, uncalled, awards});
+}
@@ -0,0 +1,126 @@
+use super::*;
+
+use crate::hand::cards;
+use crate::showdown::Variant;
+
+fn contribution(seat: usize, amount: u64, folded: bool) -> Contribution {
+	return Contribution {seat, amount, folded};
+}
+
+#[test]
+fn check_single_pot_with_uncalled_bet() {
+	let contributions = [contribution(0, 100, false), contribution(1, 40, false), contribution(2, 40, true)];
+	let (pots, uncalled) = build_pots(&contributions).unwrap();
+
+	assert_eq!(uncalled, Some((0, 60)));
+	assert_eq!(pots, vec![Pot {amount: 120, eligible: vec![0, 1]}]);
+}
+
+#[test]
+fn check_side_pots() {
+	let contributions = [
+		contribution(0, 25, false),
+		contribution(1, 100, false),
+		contribution(2, 60, false),
+		contribution(3, 100, false),
+		contribution(4, 10, true)
+	];
+	let (pots, uncalled) = build_pots(&contributions).unwrap();
+
+	assert_eq!(uncalled, None);
+	assert_eq!(pots, vec![
+		Pot {amount: 110, eligible: vec![0, 1, 2, 3]},
+		Pot {amount: 105, eligible: vec![1, 2, 3]},
+		Pot {amount: 80, eligible: vec![1, 3]}
+	]);
+	assert_eq!(pots.iter().map(|p| p.amount).sum::<u64>(), 295);
+}
+
+#[test]
+fn check_folded_chips_above_last_level() {
+	let contributions = [contribution(0, 30, false), contribution(1, 50, true), contribution(2, 80, true)];
+	let (pots, uncalled) = build_pots(&contributions).unwrap();
+
+	assert_eq!(uncalled, Some((2, 30)));
+	assert_eq!(pots, vec![Pot {amount: 130, eligible: vec![0]}]);
+	assert_eq!(build_pots(&[contribution(0, 10, true)]).err(), Some(PotError::NO_ACTIVE_PLAYERS));
+}
+
+#[test]
+fn check_settle() {
+	let showdown = Showdown {
+		variant: Variant::HOLDEM,
+		board: cards("2c 7d 9h Js 3d"),
+		button: 3,
+		players: vec![(0, cards("Ac Ad")), (1, cards("Kh Ks")), (2, cards("Qh Qs")), (3, cards("Qc Qd"))]
+	};
+	let contributions = [
+		contribution(0, 25, false),
+		contribution(1, 100, false),
+		contribution(2, 61, false),
+		contribution(3, 100, false),
+		contribution(4, 10, true)
+	];
+
+	let resolution = settle(&contributions, &showdown).unwrap();
+	assert_eq!(resolution.awards, vec![
+		PotAward {pot: 0, seat: 0, amount: 110},
+		PotAward {pot: 1, seat: 1, amount: 108},
+		PotAward {pot: 2, seat: 1, amount: 78}
+	]);
+	assert_eq!(resolution.total_for(1), 186);
+	assert_eq!(resolution.total_for(2), 0);
+}
+
+#[test]
+fn check_settle_split_side_pot() {
+	let showdown = Showdown {
+		variant: Variant::HOLDEM,
+		board: cards("2c 7d 9h Js Kd"),
+		button: 0,
+		players: vec![(0, cards("3c 4c")), (1, cards("Ah Qs")), (2, cards("As Qh"))]
+	};
+	let contributions = [contribution(0, 20, false), contribution(1, 51, false), contribution(2, 51, false)];
+
+	let resolution = settle(&contributions, &showdown).unwrap();
+	assert_eq!(resolution.awards, vec![
+		PotAward {pot: 0, seat: 1, amount: 30},
+		PotAward {pot: 0, seat: 2, amount: 30},
+		PotAward {pot: 1, seat: 1, amount: 31},
+		PotAward {pot: 1, seat: 2, amount: 31}
+	]);
+
+	let contributions = [contribution(0, 20, false), contribution(1, 51, false), contribution(2, 50, false)];
+	let resolution = settle(&contributions, &showdown).unwrap();
+	assert_eq!(resolution.uncalled, Some((1, 1)));
+	assert_eq!(resolution.total_for(1), 30 + 30 + 1);
+	assert_eq!(resolution.total_for(2), 30 + 30);
+}
+
+#[test]
+fn check_settle_missing_cards() {
+	let showdown = Showdown {
+		variant: Variant::HOLDEM,
+		board: cards("2c 7d 9h Js Kd"),
+		button: 0,
+		players: vec![(0, cards("3c 4c"))]
+	};
+	let contributions = [contribution(0, 20, false), contribution(1, 20, false)];
+	assert_eq!(settle(&contributions, &showdown).err(), Some(PotError::MISSING_HOLE_CARDS));
+
+	let contributions = [contribution(0, 20, false), contribution(1, 20, true)];
+	assert_eq!(settle(&contributions, &showdown).unwrap().total_for(0), 40);
+}
+
+#[test]
+fn check_settle_conceding() {
+	let showdown = Showdown {
+		variant: Variant::HOLDEM,
+		board: cards("2c 7d 9h Js Kd"),
+		button: 0,
+		players: vec![(0, cards("3c 4c"))]
+	};
+	let contributions = [contribution(0, 40, false), contribution(1, 40, false), contribution(2, 20, false)];
+	assert_eq!(settle_conceding(&contributions, &showdown).unwrap().total_for(0), 100);
+	assert_eq!(settle_conceding(&contributions[1..], &showdown).err(), Some(PotError::MISSING_HOLE_CARDS));
+}
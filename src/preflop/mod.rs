@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+use crate::eval;
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::hand::Rank;
+use crate::hand::Suit;
+use crate::iso;
+
+#[cfg(test)]
+mod tests;
+
+// One of the 169 starting-hand classes, indexed by its cell in the usual
+// 13x13 grid: aces first, suited hands above the diagonal.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Ord, PartialOrd, Hash)]
+pub struct HandClass(pub u8);
+
+impl HandClass {
+	pub const COUNT: usize = 169;
+
+	pub fn new(first: Rank, second: Rank, suited: bool) -> HandClass {
+		let (high, low) = if first >= second { (first, second) } else { (second, first) };
+		let row = 14 - high.to_int();
+		let col = 14 - low.to_int();
+
+		if suited && row != col {
+			return HandClass(row * 13 + col);
+		} else {
+			return HandClass(col * 13 + row);
+		}
+	}
+
+	pub fn from_cards(first: Card, second: Card) -> HandClass {
+		return HandClass::new(first.rank, second.rank, first.suit == second.suit);
+	}
+
+	pub fn parse(s: &str) -> Option<HandClass> {
+		let chars: Vec<char> = s.trim().chars().collect();
+		if chars.len() < 2 || chars.len() > 3 {
+			return None;
+		}
+
+		let first = rank_from_char(chars[0])?;
+		let second = rank_from_char(chars[1])?;
+		let suited = match chars.get(2) {
+			Some('s') => true,
+			Some('o') => false,
+			None => false,
+			_ => return None
+		};
+
+		if (first == second && chars.len() == 3) || (first != second && chars.len() == 2) {
+			return None;
+		}
+
+		return Some(HandClass::new(first, second, suited));
+	}
+
+	pub fn iterator() -> impl Iterator<Item = HandClass> {
+		return (0..HandClass::COUNT as u8).map(HandClass);
+	}
+
+	pub fn high_rank(&self) -> Rank {
+		let (row, col) = (self.0 / 13, self.0 % 13);
+		return rank_from_grid(if row < col { row } else { col });
+	}
+
+	pub fn low_rank(&self) -> Rank {
+		let (row, col) = (self.0 / 13, self.0 % 13);
+		return rank_from_grid(if row < col { col } else { row });
+	}
+
+	pub fn is_pair(&self) -> bool {
+		return self.0 / 13 == self.0 % 13;
+	}
+
+	pub fn is_suited(&self) -> bool {
+		return self.0 / 13 < self.0 % 13;
+	}
+
+	pub fn combo_count(&self) -> usize {
+		if self.is_pair() {
+			return 6;
+		} else if self.is_suited() {
+			return 4;
+		} else {
+			return 12;
+		}
+	}
+
+	pub fn combos(&self) -> Vec<[Card; 2]> {
+		let mut combos = Vec::with_capacity(self.combo_count());
+		for first in Suit::iterator() {
+			for second in Suit::iterator() {
+				let valid = if self.is_pair() {
+					first < second
+				} else if self.is_suited() {
+					first == second
+				} else {
+					first != second
+				};
+
+				if valid {
+					combos.push([
+						Card {rank: self.high_rank(), suit: *first},
+						Card {rank: self.low_rank(), suit: *second}
+					]);
+				}
+			}
+		}
+
+		return combos;
+	}
+
+	pub fn to_string(&self) -> String {
+		let mut result = format!("{}{}", self.high_rank().to_string(), self.low_rank().to_string());
+		if self.is_suited() {
+			result += "s";
+		} else if !self.is_pair() {
+			result += "o";
+		}
+
+		return result;
+	}
+}
+
+fn rank_from_grid(index: u8) -> Rank {
+	return Rank::iterator().as_slice()[(12 - index) as usize];
+}
+
+fn rank_from_char(c: char) -> Option<Rank> {
+	return Rank::iterator().find(|r| r.to_string().starts_with(c.to_ascii_uppercase())).copied();
+}
+
+static SHIPPED_TABLE: &str = include_str!("preflop_equity.txt");
+static SHIPPED: OnceLock<PreflopTable> = OnceLock::new();
+
+// Heads-up all-in equity of the row class against the column class,
+// averaged over every pair of non-overlapping combos and every board.
+pub struct PreflopTable {
+	pub equities: Vec<[f64; 169]>
+}
+
+impl PreflopTable {
+	pub fn shipped() -> &'static PreflopTable {
+		return SHIPPED.get_or_init(|| PreflopTable::parse(SHIPPED_TABLE).expect("Invalid shipped preflop table!"));
+	}
+
+	pub fn equity(&self, hero: HandClass, villain: HandClass) -> f64 {
+		return self.equities[hero.0 as usize][villain.0 as usize];
+	}
+
+	pub fn equity_by_name(&self, hero: &str, villain: &str) -> Option<f64> {
+		return Some(self.equity(HandClass::parse(hero)?, HandClass::parse(villain)?));
+	}
+
+	pub fn equity_vs_random(&self, hero: HandClass) -> f64 {
+		let combo = hero.combos()[0];
+		let dead = CardSet::from_cards(&combo);
+
+		let mut weighted = 0f64;
+		let mut total = 0usize;
+		for villain in HandClass::iterator() {
+			let available = villain.combos().iter()
+				.filter(|c| !dead.intersects(CardSet::from_cards(&c[..])))
+				.count();
+			weighted += available as f64 * self.equity(hero, villain);
+			total += available;
+		}
+
+		return weighted / total as f64;
+	}
+
+	pub fn parse(s: &str) -> Option<PreflopTable> {
+		let mut equities = vec![[0f64; 169]; 169];
+		let mut rows = 0;
+		for line in s.lines().filter(|l| !l.trim().is_empty()) {
+			let mut fields = line.split_whitespace();
+			let class = HandClass::parse(fields.next()?)?;
+			let mut count = 0;
+			for (i, field) in fields.enumerate() {
+				if i >= 169 {
+					return None;
+				}
+
+				equities[class.0 as usize][i] = field.parse().ok()?;
+				count += 1;
+			}
+
+			if count != 169 {
+				return None;
+			}
+
+			rows += 1;
+		}
+
+		if rows != 169 {
+			return None;
+		}
+
+		return Some(PreflopTable {equities});
+	}
+
+	pub fn to_string(&self) -> String {
+		let mut result = String::new();
+		for hero in HandClass::iterator() {
+			result += &hero.to_string();
+			for villain in HandClass::iterator() {
+				write!(result, " {:.6}", self.equity(hero, villain)).unwrap();
+			}
+
+			result += "\n";
+		}
+
+		return result;
+	}
+
+	// Exhaustive enumeration: every suit-isomorphic class of five-card boards
+	// is evaluated once against all hole-card combos, weighted by class size.
+	pub fn generate() -> PreflopTable {
+		let deck: Vec<Card> = CardSet::FULL_DECK.iter().collect();
+
+		let mut combos: Vec<(CardSet, usize, [usize; 2])> = Vec::with_capacity(1326);
+		for a in 0..deck.len() {
+			for b in a+1..deck.len() {
+				combos.push((CardSet::from_cards(&[deck[a], deck[b]]), HandClass::from_cards(deck[a], deck[b]).0 as usize, [a, b]));
+			}
+		}
+
+		let mut combos_with_card: Vec<Vec<usize>> = vec![Vec::new(); 52];
+		for (i, combo) in combos.iter().enumerate() {
+			combos_with_card[combo.2[0]].push(i);
+			combos_with_card[combo.2[1]].push(i);
+		}
+
+		let mut boards: HashMap<CardSet, u64> = HashMap::new();
+		for a in 0..deck.len() {
+			for b in a+1..deck.len() {
+				for c in b+1..deck.len() {
+					for d in c+1..deck.len() {
+						for e in d+1..deck.len() {
+							let board = CardSet::from_cards(&[deck[a], deck[b], deck[c], deck[d], deck[e]]);
+							*boards.entry(iso::canonicalize_rounds(&[board]).0[0]).or_insert(0) += 1;
+						}
+					}
+				}
+			}
+		}
+
+		let mut wins = vec![[0u64; 169]; 169];
+		let mut ties = vec![[0u64; 169]; 169];
+		let mut values = vec![None; combos.len()];
+		let mut ordered: Vec<(eval::HandValue, usize)> = Vec::with_capacity(1326);
+
+		for (board, weight) in boards {
+			ordered.clear();
+			for (i, combo) in combos.iter().enumerate() {
+				if combo.0.intersects(board) {
+					values[i] = None;
+				} else {
+					let value = eval::evaluate(board.union(combo.0));
+					values[i] = Some(value);
+					ordered.push((value, i));
+				}
+			}
+
+			ordered.sort();
+
+			let mut lower = [0u64; 169];
+			let mut start = 0;
+			while start < ordered.len() {
+				let mut end = start;
+				let mut equal = [0u64; 169];
+				while end < ordered.len() && ordered[end].0 == ordered[start].0 {
+					equal[combos[ordered[end].1].1] += 1;
+					end += 1;
+				}
+
+				for &(value, i) in &ordered[start..end] {
+					let mut hero_wins = lower;
+					let mut hero_ties = equal;
+
+					let cards = combos[i].2;
+					for (k, card) in cards.iter().enumerate() {
+						for &o in &combos_with_card[*card] {
+							if k == 1 && combos[o].0.contains(deck[cards[0]]) {
+								continue;
+							}
+
+							if let Some(other) = values[o] {
+								if other < value {
+									hero_wins[combos[o].1] -= 1;
+								} else if other == value {
+									hero_ties[combos[o].1] -= 1;
+								}
+							}
+						}
+					}
+
+					let hero_class = combos[i].1;
+					for villain_class in 0..169 {
+						wins[hero_class][villain_class] += weight * hero_wins[villain_class];
+						ties[hero_class][villain_class] += weight * hero_ties[villain_class];
+					}
+				}
+
+				for class in 0..169 {
+					lower[class] += equal[class];
+				}
+
+				start = end;
+			}
+		}
+
+		let mut equities = vec![[0f64; 169]; 169];
+		for hero in HandClass::iterator() {
+			for villain in HandClass::iterator() {
+				let mut pairs = 0u64;
+				for h in hero.combos() {
+					for v in villain.combos() {
+						if !CardSet::from_cards(&h).intersects(CardSet::from_cards(&v)) {
+							pairs += 1;
+						}
+					}
+				}
+
+				let total = pairs * 1712304;
+				let (h, v) = (hero.0 as usize, villain.0 as usize);
+				equities[h][v] = (wins[h][v] as f64 + ties[h][v] as f64 / 2f64) / total as f64;
+			}
+		}
+
+		return PreflopTable {equities};
+	}
+}
+
+pub fn matchup_equity(hero: [Card; 2], villain: [Card; 2]) -> f64 {
+	let dead = CardSet::from_cards(&[hero[0], hero[1], villain[0], villain[1]]);
+	assert!(dead.len() == 4, "Repeating cards provided!");
+
+	let deck: Vec<Card> = CardSet::FULL_DECK.difference(dead).iter().collect();
+	let hero_set = CardSet::from_cards(&hero);
+	let villain_set = CardSet::from_cards(&villain);
+
+	let mut score = 0u64;
+	let mut total = 0u64;
+	for a in 0..deck.len() {
+		for b in a+1..deck.len() {
+			for c in b+1..deck.len() {
+				for d in c+1..deck.len() {
+					for e in d+1..deck.len() {
+						let board = CardSet::from_cards(&[deck[a], deck[b], deck[c], deck[d], deck[e]]);
+						let hero_value = eval::evaluate(board.union(hero_set));
+						let villain_value = eval::evaluate(board.union(villain_set));
+						if hero_value > villain_value {
+							score += 2;
+						} else if hero_value == villain_value {
+							score += 1;
+						}
+
+						total += 2;
+					}
+				}
+			}
+		}
+	}
+
+	return score as f64 / total as f64;
+}
@@ -0,0 +1,62 @@
+use super::*;
+
+fn cards(s: &str) -> [Card; 2] {
+	let v: Vec<Card> = s.split(" ").map(|c| Card::new_from_string(c.to_string())).collect();
+	return [v[0], v[1]];
+}
+
+#[test]
+fn check_hand_classes() {
+	let mut combos = 0;
+	for class in HandClass::iterator() {
+		assert_eq!(HandClass::parse(&class.to_string()), Some(class));
+		assert_eq!(class.combos().len(), class.combo_count());
+		for combo in class.combos() {
+			assert_eq!(HandClass::from_cards(combo[0], combo[1]), class);
+		}
+
+		combos += class.combo_count();
+	}
+
+	assert_eq!(combos, 1326);
+	assert_eq!(HandClass::parse("AA").unwrap().0, 0);
+	assert_eq!(HandClass::parse("AKs").unwrap().0, 1);
+	assert_eq!(HandClass::parse("AKo").unwrap().0, 13);
+	assert_eq!(HandClass::parse("KAs"), HandClass::parse("AKs"));
+	assert_eq!(HandClass::parse("AAs"), None);
+	assert_eq!(HandClass::parse("AK"), None);
+	assert_eq!(HandClass::parse("AX"), None);
+}
+
+#[test]
+fn check_shipped_table_is_symmetric() {
+	let table = PreflopTable::shipped();
+	for hero in HandClass::iterator() {
+		for villain in HandClass::iterator() {
+			let sum = table.equity(hero, villain) + table.equity(villain, hero);
+			assert_eq!((sum - 1f64).abs() < 1e-5, true);
+		}
+	}
+}
+
+#[test]
+fn check_shipped_table_matches_enumeration() {
+	let table = PreflopTable::shipped();
+	let exact = (matchup_equity(cards("Ac Ad"), cards("Kc Kd")) +
+		4f64 * matchup_equity(cards("Ac Ad"), cards("Kc Kh")) +
+		matchup_equity(cards("Ac Ad"), cards("Kh Ks"))) / 6f64;
+
+	assert_eq!((table.equity_by_name("AA", "KK").unwrap() - exact).abs() < 1e-6, true);
+}
+
+#[test]
+fn check_equity_vs_random() {
+	let table = PreflopTable::shipped();
+	let aces = table.equity_vs_random(HandClass::parse("AA").unwrap());
+	assert_eq!((aces - 0.852).abs() < 0.001, true);
+
+	let weakest = HandClass::iterator()
+		.min_by(|a, b| table.equity_vs_random(*a).partial_cmp(&table.equity_vs_random(*b)).unwrap())
+		.unwrap();
+	assert_eq!(weakest.to_string(), "32o");
+}
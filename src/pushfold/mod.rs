@@ -0,0 +1,203 @@
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::preflop::HandClass;
+use crate::preflop::PreflopTable;
+use crate::range::Range;
+
+#[cfg(test)]
+mod tests;
+
+// Heads-up push or fold: the small blind moves all in or folds, the big blind
+// calls or folds. Stacks, blinds and antes are in big blinds, the small blind
+// posting half of one. Both strategies come from fictitious play over the 169
+// starting hand classes, with the all-in equities of the shipped preflop table.
+
+pub const DEFAULT_ITERATIONS: usize = 2000;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PushFoldError {
+	// the stack must cover the blind and the ante
+	INVALID_STACK,
+	INVALID_ANTE
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PushFoldChart {
+	// effective stack before the blinds and antes are posted
+	pub stack: f64,
+	// posted by each player
+	pub ante: f64,
+	// share of the time each class is pushed by the small blind and called by the big blind
+	pub push: Vec<f64>,
+	pub call: Vec<f64>,
+	// what the small blind wins on average, in big blinds
+	pub ev: f64,
+	// average gain of a best response against each strategy, in big blinds
+	pub exploitability: f64
+}
+
+// Combo pairs of two classes that share no card
+fn pair_counts() -> Vec<Vec<f64>> {
+	let combos: Vec<Vec<CardSet>> = HandClass::iterator().map(|c| c.combos().iter().map(|c| CardSet::from_cards(c)).collect()).collect();
+	let mut counts = vec![vec![0.0; HandClass::COUNT]; HandClass::COUNT];
+	for hero in 0..HandClass::COUNT {
+		for villain in hero..HandClass::COUNT {
+			let count = combos[hero].iter().map(|h| combos[villain].iter().filter(|v| !h.intersects(**v)).count()).sum::<usize>() as f64;
+			counts[hero][villain] = count;
+			counts[villain][hero] = count;
+		}
+	}
+
+	return counts;
+}
+
+struct Game {
+	stack: f64,
+	ante: f64,
+	pairs: Vec<Vec<f64>>,
+	// small blind equity of the row class against the column class
+	equities: Vec<Vec<f64>>
+}
+
+impl Game {
+	// Small blind winnings for a class, against every class of the big blind
+	fn push_values(&self, call: &[f64]) -> Vec<f64> {
+		return (0..HandClass::COUNT).map(|h| {
+			let mut value = 0.0;
+			for (v, share) in call.iter().enumerate() {
+				let called = self.stack * (2.0 * self.equities[h][v] - 1.0);
+				value += self.pairs[h][v] * (share * called + (1.0 - share) * (1.0 + self.ante));
+			}
+
+			value / self.pairs[h].iter().sum::<f64>()
+		}).collect();
+	}
+
+	fn fold_value(&self) -> f64 {
+		return -(0.5 + self.ante);
+	}
+
+	// Big blind gain of calling over folding for a class, against the pushes
+	fn call_gains(&self, push: &[f64]) -> Vec<f64> {
+		return (0..HandClass::COUNT).map(|v| {
+			let (mut gain, mut weight) = (0.0, 0.0);
+			for (h, share) in push.iter().enumerate() {
+				let pushed = self.pairs[h][v] * share;
+				gain += pushed * (self.stack * (1.0 - 2.0 * self.equities[h][v]) + 1.0 + self.ante);
+				weight += pushed;
+			}
+
+			if weight > 0.0 { gain / weight } else { 0.0 }
+		}).collect();
+	}
+
+	// Small blind winnings over all deals
+	fn ev(&self, push: &[f64], call: &[f64]) -> f64 {
+		let values = self.push_values(call);
+		let (mut total, mut weight) = (0.0, 0.0);
+		for h in 0..HandClass::COUNT {
+			let deals = self.pairs[h].iter().sum::<f64>();
+			total += deals * (push[h] * values[h] + (1.0 - push[h]) * self.fold_value());
+			weight += deals;
+		}
+
+		return total / weight;
+	}
+
+	fn best_push(&self, call: &[f64]) -> Vec<f64> {
+		return self.push_values(call).iter().map(|v| if *v > self.fold_value() { 1.0 } else { 0.0 }).collect();
+	}
+
+	fn best_call(&self, push: &[f64]) -> Vec<f64> {
+		return self.call_gains(push).iter().map(|g| if *g > 0.0 { 1.0 } else { 0.0 }).collect();
+	}
+}
+
+pub fn solve(stack: f64, ante: f64, iterations: usize) -> Result<PushFoldChart, PushFoldError> {
+	if !(ante >= 0.0 && ante.is_finite()) {
+		return Err(PushFoldError::INVALID_ANTE);
+	}
+
+	if !(stack > 1.0 + ante && stack.is_finite()) {
+		return Err(PushFoldError::INVALID_STACK);
+	}
+
+	let table = PreflopTable::shipped();
+	let equities = HandClass::iterator().map(|h| HandClass::iterator().map(|v| table.equity(h, v)).collect()).collect();
+	let game = Game {stack, ante, pairs: pair_counts(), equities};
+
+	// each side answers the other's average so far
+	let mut push = vec![1.0; HandClass::COUNT];
+	let mut call = game.best_call(&push);
+	for t in 1..iterations.max(1) {
+		let step = 1.0 / (t + 1) as f64;
+		let best_push = game.best_push(&call);
+		for (p, b) in push.iter_mut().zip(best_push) {
+			*p += step * (b - *p);
+		}
+
+		let best_call = game.best_call(&push);
+		for (c, b) in call.iter_mut().zip(best_call) {
+			*c += step * (b - *c);
+		}
+	}
+
+	let ev = game.ev(&push, &call);
+	let small_blind_gain = game.ev(&game.best_push(&call), &call) - ev;
+	let big_blind_gain = ev - game.ev(&push, &game.best_call(&push));
+	let exploitability = (small_blind_gain + big_blind_gain) / 2.0;
+	return Ok(PushFoldChart {stack, ante, push, call, ev, exploitability});
+}
+
+fn range_of(shares: &[f64]) -> Range {
+	let combos: Vec<[Card; 2]> = HandClass::iterator().filter(|c| shares[c.0 as usize] >= 0.5).flat_map(|c| c.combos()).collect();
+	return Range::from_combos(&combos);
+}
+
+// 13 rows of the class grid, pairs on the diagonal and suited hands above it,
+// with the classes played at least half the time and dots for the rest
+fn grid(shares: &[f64]) -> String {
+	let mut rows = Vec::new();
+	for row in 0..13u8 {
+		let cells: Vec<String> = (0..13u8).map(|col| {
+			let class = HandClass(row * 13 + col);
+			if shares[class.0 as usize] >= 0.5 { format!("{:<4}", class.to_string()) } else { ".   ".to_string() }
+		}).collect();
+		rows.push(cells.join("").trim_end().to_string());
+	}
+
+	return rows.join("\n");
+}
+
+// Share of all starting hands in the strategy
+fn combo_share(shares: &[f64]) -> f64 {
+	return HandClass::iterator().map(|c| shares[c.0 as usize] * c.combo_count() as f64).sum::<f64>() / 1326.0;
+}
+
+impl PushFoldChart {
+	// Classes pushed or called at least half the time
+	pub fn push_range(&self) -> Range {
+		return range_of(&self.push);
+	}
+
+	pub fn call_range(&self) -> Range {
+		return range_of(&self.call);
+	}
+
+	pub fn push_share(&self) -> f64 {
+		return combo_share(&self.push);
+	}
+
+	pub fn call_share(&self) -> f64 {
+		return combo_share(&self.call);
+	}
+
+	pub fn push_grid(&self) -> String {
+		return grid(&self.push);
+	}
+
+	pub fn call_grid(&self) -> String {
+		return grid(&self.call);
+	}
+}
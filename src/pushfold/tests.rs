@@ -0,0 +1,43 @@
+use super::*;
+
+fn class(s: &str) -> usize {
+	return HandClass::parse(s).unwrap().0 as usize;
+}
+
+#[test]
+fn check_ten_big_blinds() {
+	let chart = solve(10.0, 0.0, 1000).unwrap();
+	assert_eq!(chart.exploitability < 0.01, true);
+	assert_eq!(chart.push[class("AA")], 1.0);
+	assert_eq!(chart.push[class("K2s")] > 0.99, true);
+	assert_eq!(chart.push[class("72o")] < 0.01, true);
+	assert_eq!(chart.call[class("AA")], 1.0);
+	assert_eq!(chart.call[class("A2o")] > 0.99, true);
+	assert_eq!(chart.call[class("J7o")] < 0.01, true);
+
+	// the usual chart shoves a little over half of all hands and calls
+	// with about a third
+	assert_eq!(chart.push_share() > 0.5 && chart.push_share() < 0.65, true);
+	assert_eq!(chart.call_share() > 0.3 && chart.call_share() < 0.45, true);
+	assert_eq!(chart.push_range().contains([Card::new_from_string("As".to_string()), Card::new_from_string("Ad".to_string())]), true);
+	assert_eq!(chart.push_range().len() as f64 / 1326.0 > 0.5, true);
+
+	let grid = chart.push_grid();
+	assert_eq!(grid.lines().count(), 13);
+	assert_eq!(grid.starts_with("AA  AKs AQs"), true);
+}
+
+#[test]
+fn check_stacks_and_antes() {
+	let short = solve(3.0, 0.0, 500).unwrap();
+	let deep = solve(20.0, 0.0, 500).unwrap();
+	let antes = solve(20.0, 0.125, 500).unwrap();
+	assert_eq!(short.push_share() > 0.7, true);
+	assert_eq!(deep.push_share() < short.push_share(), true);
+	assert_eq!(antes.push_share() > deep.push_share(), true);
+	assert_eq!(antes.call_share() > deep.call_share(), true);
+	assert_eq!(deep.ev > -0.5 && deep.ev < 1.0, true);
+
+	assert_eq!(solve(1.0, 0.0, 10).err(), Some(PushFoldError::INVALID_STACK));
+	assert_eq!(solve(10.0, -1.0, 10).err(), Some(PushFoldError::INVALID_ANTE));
+}
@@ -0,0 +1,189 @@
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::hand::Rank;
+use crate::preflop::HandClass;
+
+#[cfg(test)]
+mod tests;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RangeError {
+	// index of the comma separated token that could not be read
+	INVALID_TOKEN(usize),
+	EMPTY
+}
+
+// Two card starting hands, as written in the usual notation: "TT+", "A2s+",
+// "KQo", "AK", "22-55", "A5s-A2s" and single combos such as "AhKh".
+#[derive(Clone, PartialEq, Default)]
+pub struct Range {
+	combos: Vec<[Card; 2]>
+}
+
+// Every class from the first to the second one, which have to share the high
+// rank, or both be pairs
+fn class_span(from: HandClass, to: HandClass) -> Option<Vec<HandClass>> {
+	let (low, high) = if from.low_rank() <= to.low_rank() { (from, to) } else { (to, from) };
+	if low.is_pair() != high.is_pair() || low.is_suited() != high.is_suited() {
+		return None;
+	}
+
+	let mut classes = Vec::new();
+	if low.is_pair() {
+		for rank in Rank::iterator().filter(|r| **r >= low.low_rank() && **r <= high.low_rank()) {
+			classes.push(HandClass::new(*rank, *rank, false));
+		}
+	} else {
+		if low.high_rank() != high.high_rank() {
+			return None;
+		}
+
+		for rank in Rank::iterator().filter(|r| **r >= low.low_rank() && **r <= high.low_rank()) {
+			classes.push(HandClass::new(low.high_rank(), *rank, low.is_suited()));
+		}
+	}
+
+	return Some(classes);
+}
+
+// "AK" stands for both the suited and the offsuit class
+fn parse_classes(s: &str) -> Option<Vec<HandClass>> {
+	if let Some(class) = HandClass::parse(s) {
+		return Some(vec![class]);
+	}
+
+	let suited = HandClass::parse(&format!("{}s", s))?;
+	let offsuit = HandClass::parse(&format!("{}o", s))?;
+
+	return Some(vec![suited, offsuit]);
+}
+
+fn parse_token(token: &str) -> Option<Vec<[Card; 2]>> {
+	if token.len() == 4 {
+		// the slices are None when a multi-byte character straddles them
+		let card = |range| token.get(range).and_then(Card::parse);
+		if let (Some(first), Some(second)) = (card(0..2), card(2..4)) {
+			if first == second {
+				return None;
+			}
+
+			return Some(vec![[first, second]]);
+		}
+	}
+
+	let mut classes = Vec::new();
+	if let Some(base) = token.strip_suffix('+') {
+		for class in parse_classes(base)? {
+			let top = if class.is_pair() {
+				HandClass::new(Rank::ACE, Rank::ACE, false)
+			} else {
+				let below = Rank::iterator().rev().find(|r| **r < class.high_rank()).copied()?;
+				HandClass::new(class.high_rank(), below, class.is_suited())
+			};
+
+			classes.extend(class_span(class, top)?);
+		}
+	} else if let Some((from, to)) = token.split_once('-') {
+		let from = parse_classes(from)?;
+		let to = parse_classes(to)?;
+		if from.len() != to.len() {
+			return None;
+		}
+
+		for (from, to) in from.iter().zip(to.iter()) {
+			classes.extend(class_span(*from, *to)?);
+		}
+	} else {
+		classes = parse_classes(token)?;
+	}
+
+	return Some(classes.iter().flat_map(|c| c.combos()).collect());
+}
+
+impl Range {
+	pub fn parse(s: &str) -> Result<Range, RangeError> {
+		let mut range = Range::default();
+		for (i, token) in s.split(',').map(|t| t.trim()).enumerate() {
+			if token.is_empty() {
+				continue;
+			}
+
+			for combo in parse_token(token).ok_or(RangeError::INVALID_TOKEN(i))? {
+				range.insert(combo);
+			}
+		}
+
+		if range.is_empty() {
+			return Err(RangeError::EMPTY);
+		}
+
+		return Ok(range);
+	}
+
+	pub fn from_combos(combos: &[[Card; 2]]) -> Range {
+		let mut range = Range::default();
+		for combo in combos {
+			range.insert(*combo);
+		}
+
+		return range;
+	}
+
+	pub fn insert(&mut self, combo: [Card; 2]) -> bool {
+		if self.contains(combo) {
+			return false;
+		}
+
+		self.combos.push(combo);
+		return true;
+	}
+
+	pub fn contains(&self, combo: [Card; 2]) -> bool {
+		let set = CardSet::from_cards(&combo);
+		return self.combos.iter().any(|c| CardSet::from_cards(c) == set);
+	}
+
+	pub fn combos(&self) -> &[[Card; 2]] {
+		return &self.combos;
+	}
+
+	pub fn len(&self) -> usize {
+		return self.combos.len();
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.combos.is_empty();
+	}
+
+	// The combos that do not use any of the given cards
+	pub fn without(&self, dead: CardSet) -> Range {
+		return Range {combos: self.combos.iter().filter(|c| !dead.intersects(CardSet::from_cards(*c))).copied().collect()};
+	}
+
+	// Classes with all of their combos in the range, then the single combos
+	// left over, strongest class first
+	pub fn expand(&self) -> (Vec<HandClass>, Vec<[Card; 2]>) {
+		let mut classes = Vec::new();
+		let mut partial = Vec::new();
+		for class in HandClass::iterator() {
+			let combos: Vec<[Card; 2]> = class.combos().into_iter().filter(|c| self.contains(*c)).collect();
+			if combos.len() == class.combo_count() {
+				classes.push(class);
+			} else {
+				partial.extend(combos);
+			}
+		}
+
+		classes.sort_by_key(|c| (!c.is_pair(), 14 - c.high_rank().to_int(), 14 - c.low_rank().to_int(), !c.is_suited()));
+		return (classes, partial);
+	}
+
+	pub fn to_string(&self) -> String {
+		let (classes, combos) = self.expand();
+		let mut tokens: Vec<String> = classes.iter().map(|c| c.to_string()).collect();
+		tokens.extend(combos.iter().map(|c| format!("{}{}", c[0], c[1])));
+
+		return tokens.join(",");
+	}
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+fn card(s: &str) -> Card {
+	return Card::new_from_string(s.to_string());
+}
+
+#[test]
+fn check_parse() {
+	let range = Range::parse("TT+,AKs").unwrap();
+	assert_eq!(range.len(), 34);
+	assert_eq!(range.to_string(), "AA,KK,QQ,JJ,TT,AKs");
+
+	assert_eq!(Range::parse("A2s+").unwrap().len(), 48);
+	assert_eq!(Range::parse("KQo+").unwrap().to_string(), "KQo");
+	assert_eq!(Range::parse("AK").unwrap().to_string(), "AKs,AKo");
+	assert_eq!(Range::parse("22-44").unwrap().to_string(), "44,33,22");
+	assert_eq!(Range::parse("A5s-A2s").unwrap().to_string(), "A5s,A4s,A3s,A2s");
+	assert_eq!(Range::parse(" AhKh , AK ").unwrap().len(), 16);
+	assert_eq!(Range::parse("AhKh,QQ").unwrap().to_string(), "QQ,AhKh");
+}
+
+#[test]
+fn check_parse_errors() {
+	assert_eq!(Range::parse("TT+,XYZ").err(), Some(RangeError::INVALID_TOKEN(1)));
+	assert_eq!(Range::parse("KK-AKs").err(), Some(RangeError::INVALID_TOKEN(0)));
+	assert_eq!(Range::parse("AKs-QJs").err(), Some(RangeError::INVALID_TOKEN(0)));
+	assert_eq!(Range::parse("AhAh").err(), Some(RangeError::INVALID_TOKEN(0)));
+	assert_eq!(Range::parse(" , ").err(), Some(RangeError::EMPTY));
+
+	// four bytes, but not four characters
+	assert_eq!(Range::parse("AK,A♠").err(), Some(RangeError::INVALID_TOKEN(1)));
+	assert_eq!(Range::parse("A♠K♠").err(), Some(RangeError::INVALID_TOKEN(0)));
+}
+
+#[test]
+fn check_combos() {
+	let mut range = Range::parse("AA").unwrap();
+	assert_eq!(range.contains([card("Ad"), card("As")]), true);
+	assert_eq!(range.insert([card("As"), card("Ad")]), false);
+	assert_eq!(range.insert([card("Ks"), card("Kd")]), true);
+	assert_eq!(range.len(), 7);
+
+	let live = range.without(CardSet::from_cards(&[card("As")]));
+	assert_eq!(live.len(), 4);
+	assert_eq!(live.to_string(), "AcAd,AcAh,AdAh,KdKs");
+}
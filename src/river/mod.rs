@@ -0,0 +1,483 @@
+use crate::eval;
+use crate::eval::CardSet;
+use crate::eval::HandValue;
+use crate::hand::Card;
+use crate::range::Range;
+
+#[cfg(test)]
+mod tests;
+
+// Heads-up river spots solved with CFR+ over both ranges at once. Player 0 is
+// out of position and acts first. Payoffs are chips won on the river: the pot
+// and what the opponent put in for the winner, what was put in lost for the
+// other, half the pot each on a tie.
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum RiverError {
+	// the board needs five cards
+	INVALID_BOARD,
+	REPEATING_CARDS,
+	// no combo with a positive weight is left next to the board, by player
+	EMPTY_RANGE(usize),
+	// at least one size, each a positive share of the pot
+	INVALID_BET_SIZES,
+	INVALID_POT
+}
+
+// A combo with its weight in the range, from 0 to 1
+pub type WeightedCombo = ([Card; 2], f64);
+
+pub struct RiverSpot {
+	pub board: Vec<Card>,
+	pub ranges: [Vec<WeightedCombo>; 2],
+	pub pot: u64,
+	// effective stack behind at the start of the river
+	pub stack: u64,
+	// bets and raises as shares of the pot, e.g. 0.5 for half pot
+	pub bet_sizes: Vec<f64>,
+	// raises allowed after the first bet
+	pub max_raises: usize
+}
+
+// Every combo of the range with the same weight
+pub fn uniform(range: &Range) -> Vec<WeightedCombo> {
+	return range.combos().iter().map(|c| (*c, 1.0)).collect();
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Debug)]
+enum TreeNode {
+	// the actions as "x" check, "f" fold, "c" call, "b<chips>" bet and
+	// "r<chips>" raise, counting all the chips the player put in on the river
+	ACTION {player: usize, line: String, actions: Vec<String>, children: Vec<usize>},
+	FOLD {folder: usize, committed: [u64; 2]},
+	SHOWDOWN {committed: u64}
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Mode {
+	// current strategies, updating the regrets of the traverser
+	TRAIN,
+	AVERAGE,
+	// the traverser picks the best action of every hand
+	BEST_RESPONSE
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ComboResult {
+	pub combo: [Card; 2],
+	pub weight: f64,
+	// expected winnings with this combo when both play the average strategy
+	pub ev: f64
+}
+
+pub struct RiverSolver {
+	pub spot: RiverSpot,
+	pub iterations: u64,
+	tree: Vec<TreeNode>,
+	hands: [Vec<[Card; 2]>; 2],
+	weights: [Vec<f64>; 2],
+	// hand indexes from the weakest to the strongest, with their values
+	order: [Vec<usize>; 2],
+	values: [Vec<HandValue>; 2],
+	// the opponent hand with the same cards, if any
+	same: [Vec<Option<usize>>; 2],
+	// per action node, hand by hand with the actions of each in a row
+	regrets: Vec<Vec<f64>>,
+	strategy_sums: Vec<Vec<f64>>
+}
+
+fn card_indexes(combo: &[Card; 2]) -> [usize; 2] {
+	return [combo[0].to_index() as usize, combo[1].to_index() as usize];
+}
+
+fn regret_matching(regrets: &[f64]) -> Vec<f64> {
+	let positive: f64 = regrets.iter().map(|r| r.max(0.0)).sum();
+	if positive <= 0.0 {
+		return vec![1.0 / regrets.len() as f64; regrets.len()];
+	}
+
+	return regrets.iter().map(|r| r.max(0.0) / positive).collect();
+}
+
+fn normalized(sums: &[f64]) -> Vec<f64> {
+	let total: f64 = sums.iter().sum();
+	if total <= 0.0 {
+		return vec![1.0 / sums.len() as f64; sums.len()];
+	}
+
+	return sums.iter().map(|s| s / total).collect();
+}
+
+impl RiverSolver {
+	pub fn new(spot: RiverSpot) -> Result<RiverSolver, RiverError> {
+		if spot.board.len() != 5 {
+			return Err(RiverError::INVALID_BOARD);
+		}
+
+		let board = CardSet::from_cards(&spot.board);
+		if board.len() != 5 {
+			return Err(RiverError::REPEATING_CARDS);
+		}
+
+		if spot.bet_sizes.is_empty() || spot.bet_sizes.iter().any(|s| *s <= 0.0) {
+			return Err(RiverError::INVALID_BET_SIZES);
+		}
+
+		if spot.pot == 0 {
+			return Err(RiverError::INVALID_POT);
+		}
+
+		let mut hands: [Vec<[Card; 2]>; 2] = [Vec::new(), Vec::new()];
+		let mut weights: [Vec<f64>; 2] = [Vec::new(), Vec::new()];
+		let mut values: [Vec<HandValue>; 2] = [Vec::new(), Vec::new()];
+		for player in 0..2 {
+			for (combo, weight) in &spot.ranges[player] {
+				let cards = CardSet::from_cards(combo);
+				if *weight <= 0.0 || cards.len() != 2 || cards.intersects(board) || hands[player].contains(combo) {
+					continue;
+				}
+
+				hands[player].push(*combo);
+				weights[player].push(weight.min(1.0));
+				values[player].push(eval::evaluate(cards.union(board)));
+			}
+
+			if hands[player].is_empty() {
+				return Err(RiverError::EMPTY_RANGE(player));
+			}
+		}
+
+		let order = [0, 1].map(|p| {
+			let mut order: Vec<usize> = (0..hands[p].len()).collect();
+			order.sort_by_key(|i| values[p][*i]);
+			order
+		});
+
+		let same = [0, 1].map(|p| hands[p].iter().map(|h| hands[1 - p].iter().position(|o| CardSet::from_cards(o) == CardSet::from_cards(h))).collect());
+
+		let mut solver = RiverSolver {
+			spot,
+			iterations: 0,
+			tree: Vec::new(),
+			hands,
+			weights,
+			order,
+			values,
+			same,
+			regrets: Vec::new(),
+			strategy_sums: Vec::new()
+		};
+
+		solver.build([0, 0], 0, 0, String::new());
+		return Ok(solver);
+	}
+
+	// Chip totals a player can put in: each size of the pot after calling, up
+	// to the stack, without repeats
+	fn sizes(&self, committed: [u64; 2], player: usize) -> Vec<u64> {
+		let facing = committed[1 - player];
+		let pot = self.spot.pot + 2 * facing;
+
+		let mut totals: Vec<u64> = Vec::new();
+		for size in &self.spot.bet_sizes {
+			let total = (facing + (size * pot as f64).round() as u64).min(self.spot.stack);
+			if total > facing && !totals.contains(&total) {
+				totals.push(total);
+			}
+		}
+
+		totals.sort();
+		return totals;
+	}
+
+	fn build(&mut self, committed: [u64; 2], player: usize, raises: usize, line: String) -> usize {
+		let index = self.tree.len();
+		self.tree.push(TreeNode::SHOWDOWN {committed: committed[0]});
+		let other = 1 - player;
+		let next_line = |action: &str| if line.is_empty() { action.to_string() } else { format!("{} {}", line, action) };
+
+		let mut actions: Vec<String> = Vec::new();
+		let mut children: Vec<usize> = Vec::new();
+		if committed[0] == committed[1] {
+			actions.push("x".to_string());
+			if player == 0 {
+				children.push(self.build(committed, other, raises, next_line("x")));
+			} else {
+				children.push(self.tree.len());
+				self.tree.push(TreeNode::SHOWDOWN {committed: committed[0]});
+			}
+
+			for total in self.sizes(committed, player) {
+				let label = format!("b{}", total);
+				let mut next = committed;
+				next[player] = total;
+				children.push(self.build(next, other, 0, next_line(&label)));
+				actions.push(label);
+			}
+		} else {
+			actions.push("f".to_string());
+			children.push(self.tree.len());
+			self.tree.push(TreeNode::FOLD {folder: player, committed});
+
+			actions.push("c".to_string());
+			children.push(self.tree.len());
+			self.tree.push(TreeNode::SHOWDOWN {committed: committed[other]});
+
+			if raises < self.spot.max_raises && committed[other] < self.spot.stack {
+				for total in self.sizes(committed, player) {
+					let label = format!("r{}", total);
+					let mut next = committed;
+					next[player] = total;
+					children.push(self.build(next, other, raises + 1, next_line(&label)));
+					actions.push(label);
+				}
+			}
+		}
+
+		let cells = self.hands[player].len() * actions.len();
+		self.tree[index] = TreeNode::ACTION {player, line, actions, children};
+		while self.regrets.len() < self.tree.len() {
+			self.regrets.push(Vec::new());
+			self.strategy_sums.push(Vec::new());
+		}
+
+		self.regrets[index] = vec![0.0; cells];
+		self.strategy_sums[index] = vec![0.0; cells];
+		return index;
+	}
+
+	// Opponent weight that each of the player's hands beats, ties and loses to,
+	// found with one sweep up and one down the strength order
+	fn showdown_weights(&self, player: usize, reach: &[f64]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+		let other = 1 - player;
+		let (hands, opponents) = (&self.hands[player], &self.hands[other]);
+		let (values, opponent_values) = (&self.values[player], &self.values[other]);
+
+		let mut win = vec![0.0; hands.len()];
+		let mut lose = vec![0.0; hands.len()];
+		for up in [true, false] {
+			let (mut total, mut by_card) = (0.0, [0.0f64; 52]);
+			let mut hand_order = self.order[player].clone();
+			let mut opponent_order = self.order[other].clone();
+			if !up {
+				hand_order.reverse();
+				opponent_order.reverse();
+			}
+
+			let mut next = 0;
+			for i in hand_order {
+				while next < opponent_order.len() {
+					let j = opponent_order[next];
+					let passed = if up { opponent_values[j] < values[i] } else { opponent_values[j] > values[i] };
+					if !passed {
+						break;
+					}
+
+					total += reach[j];
+					for card in card_indexes(&opponents[j]) {
+						by_card[card] += reach[j];
+					}
+
+					next += 1;
+				}
+
+				let [a, b] = card_indexes(&hands[i]);
+				let weight = total - by_card[a] - by_card[b];
+				if up { win[i] = weight; } else { lose[i] = weight; }
+			}
+		}
+
+		let compatible = self.compatible_weights(player, reach);
+		let tie = (0..hands.len()).map(|i| compatible[i] - win[i] - lose[i]).collect();
+		return (win, tie, lose);
+	}
+
+	// Opponent weight that does not share a card with each of the player's hands
+	fn compatible_weights(&self, player: usize, reach: &[f64]) -> Vec<f64> {
+		let other = 1 - player;
+		let mut by_card = [0.0f64; 52];
+		let mut total = 0.0;
+		for (j, combo) in self.hands[other].iter().enumerate() {
+			total += reach[j];
+			for card in card_indexes(combo) {
+				by_card[card] += reach[j];
+			}
+		}
+
+		return self.hands[player].iter().enumerate().map(|(i, combo)| {
+			let [a, b] = card_indexes(combo);
+			total - by_card[a] - by_card[b] + self.same[player][i].map_or(0.0, |j| reach[j])
+		}).collect();
+	}
+
+	// Counterfactual value of every hand of the player
+	fn evaluate(&mut self, node: usize, player: usize, reach: &[Vec<f64>; 2], mode: Mode, weight: f64) -> Vec<f64> {
+		let other = 1 - player;
+		let (acting, actions, children) = match &self.tree[node] {
+			TreeNode::SHOWDOWN {committed} => {
+				let committed = *committed as f64;
+				let pot = self.spot.pot as f64;
+				let (win, tie, lose) = self.showdown_weights(player, &reach[other]);
+				return (0..win.len()).map(|i| win[i] * (pot + committed) + tie[i] * pot / 2.0 - lose[i] * committed).collect();
+			},
+			TreeNode::FOLD {folder, committed} => {
+				let payoff = if *folder == player { -(committed[player] as f64) } else { self.spot.pot as f64 + committed[*folder] as f64 };
+				return self.compatible_weights(player, &reach[other]).iter().map(|w| w * payoff).collect();
+			},
+			TreeNode::ACTION {player: acting, actions, children, ..} => (*acting, actions.len(), children.clone())
+		};
+
+		let hands = self.hands[acting].len();
+		let strategy: Vec<f64> = match mode {
+			Mode::TRAIN => (0..hands).flat_map(|h| regret_matching(&self.regrets[node][h * actions..(h + 1) * actions])).collect(),
+			_ => (0..hands).flat_map(|h| normalized(&self.strategy_sums[node][h * actions..(h + 1) * actions])).collect()
+		};
+
+		if acting != player {
+			let mut values = vec![0.0; self.hands[player].len()];
+			for (action, child) in children.iter().enumerate() {
+				let mut next = reach.clone();
+				for (h, r) in next[acting].iter_mut().enumerate() {
+					*r *= strategy[h * actions + action];
+				}
+
+				for (value, child_value) in values.iter_mut().zip(self.evaluate(*child, player, &next, mode, weight)) {
+					*value += child_value;
+				}
+			}
+
+			return values;
+		}
+
+		let mut action_values = Vec::with_capacity(actions);
+		for (action, child) in children.iter().enumerate() {
+			let mut next = reach.clone();
+			if mode == Mode::TRAIN {
+				for (h, r) in next[player].iter_mut().enumerate() {
+					*r *= strategy[h * actions + action];
+				}
+			}
+
+			action_values.push(self.evaluate(*child, player, &next, mode, weight));
+		}
+
+		let mut values = vec![0.0; hands];
+		for h in 0..hands {
+			values[h] = match mode {
+				Mode::BEST_RESPONSE => action_values.iter().map(|v| v[h]).fold(f64::NEG_INFINITY, f64::max),
+				_ => (0..actions).map(|a| strategy[h * actions + a] * action_values[a][h]).sum()
+			};
+		}
+
+		if mode == Mode::TRAIN {
+			for h in 0..hands {
+				for (a, action) in action_values.iter().enumerate() {
+					let cell = h * actions + a;
+					self.regrets[node][cell] = (self.regrets[node][cell] + action[h] - values[h]).max(0.0);
+					self.strategy_sums[node][cell] += weight * reach[player][h] * strategy[cell];
+				}
+			}
+		}
+
+		return values;
+	}
+
+	// One CFR+ iteration, updating each player in turn with linear averaging
+	pub fn iterate(&mut self) {
+		self.iterations += 1;
+		let weight = self.iterations as f64;
+		for player in 0..2 {
+			let reach = self.weights.clone();
+			self.evaluate(0, player, &reach, Mode::TRAIN, weight);
+		}
+	}
+
+	// Runs the iterations, giving the exploitability after every given number
+	// of them and after the last one
+	pub fn solve(&mut self, iterations: u64, report_every: u64) -> Vec<(u64, f64)> {
+		let mut report = Vec::new();
+		for i in 1..=iterations {
+			self.iterate();
+			if i == iterations || (report_every > 0 && i % report_every == 0) {
+				report.push((self.iterations, self.exploitability()));
+			}
+		}
+
+		return report;
+	}
+
+	// Weight of all the pairs of hands the two players can hold together
+	fn total_weight(&self) -> f64 {
+		let compatible = self.compatible_weights(0, &self.weights[1]);
+		return compatible.iter().zip(self.weights[0].iter()).map(|(c, w)| c * w).sum();
+	}
+
+	fn expected(&mut self, player: usize, mode: Mode) -> f64 {
+		let reach = self.weights.clone();
+		let values = self.evaluate(0, player, &reach, mode, 0.0);
+		let total: f64 = values.iter().zip(self.weights[player].iter()).map(|(v, w)| v * w).sum();
+		return total / self.total_weight();
+	}
+
+	// Expected winnings of the player when both play the average strategy
+	pub fn ev(&mut self, player: usize) -> f64 {
+		return self.expected(player, Mode::AVERAGE);
+	}
+
+	// Expected winnings of a best response to the other's average strategy
+	pub fn best_response(&mut self, player: usize) -> f64 {
+		return self.expected(player, Mode::BEST_RESPONSE);
+	}
+
+	// Chips a best response gains on average over the equilibrium; the two
+	// players share the pot, so zero when both strategies are optimal
+	pub fn exploitability(&mut self) -> f64 {
+		return (self.best_response(0) + self.best_response(1) - self.spot.pot as f64) / 2.0;
+	}
+
+	pub fn combos(&self, player: usize) -> &[[Card; 2]] {
+		return &self.hands[player];
+	}
+
+	// The betting lines where a player acts, with the player, in tree order
+	pub fn lines(&self) -> Vec<(String, usize)> {
+		return self.tree.iter().filter_map(|n| match n {
+			TreeNode::ACTION {player, line, ..} => Some((line.clone(), *player)),
+			_ => None
+		}).collect();
+	}
+
+	// Average strategy of a combo after a betting line such as "x b100"
+	pub fn strategy(&self, line: &str, combo: [Card; 2]) -> Option<Vec<(String, f64)>> {
+		for (node, tree_node) in self.tree.iter().enumerate() {
+			if let TreeNode::ACTION {player, line: node_line, actions, ..} = tree_node {
+				if node_line != line {
+					continue;
+				}
+
+				let cards = CardSet::from_cards(&combo);
+				let hand = self.hands[*player].iter().position(|h| CardSet::from_cards(h) == cards)?;
+				let shares = normalized(&self.strategy_sums[node][hand * actions.len()..(hand + 1) * actions.len()]);
+				return Some(actions.iter().cloned().zip(shares).collect());
+			}
+		}
+
+		return None;
+	}
+
+	// The EV of each of the player's combos, given that they hold it
+	pub fn combo_results(&mut self, player: usize) -> Vec<ComboResult> {
+		let reach = self.weights.clone();
+		let values = self.evaluate(0, player, &reach, Mode::AVERAGE, 0.0);
+		let compatible = self.compatible_weights(player, &self.weights[1 - player]);
+
+		return (0..self.hands[player].len()).map(|i| ComboResult {
+			combo: self.hands[player][i],
+			weight: self.weights[player][i],
+			ev: if compatible[i] > 0.0 { values[i] / compatible[i] } else { 0.0 }
+		}).collect();
+	}
+}
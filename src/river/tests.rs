@@ -0,0 +1,97 @@
+use super::*;
+
+use std::convert::TryInto;
+
+// Queens against a set of kings or air: the kings bet, the air bluffs half the
+// time with a pot sized bet and the queens call half the time
+fn toy_spot(stack: u64, bet_sizes: Vec<f64>, max_raises: usize) -> RiverSpot {
+	return RiverSpot {
+		board: Card::parse_list("2c 5d 9h Js Kh").unwrap(),
+		ranges: [vec![(Card::parse_list("Qc Qd").unwrap()[..].try_into().unwrap(), 1.0)], vec![(Card::parse_list("Ks Kd").unwrap()[..].try_into().unwrap(), 1.0), (Card::parse_list("4s 3s").unwrap()[..].try_into().unwrap(), 1.0)]],
+		pot: 100,
+		stack,
+		bet_sizes,
+		max_raises
+	};
+}
+
+#[test]
+fn check_tree() {
+	let solver = RiverSolver::new(toy_spot(400, vec![0.5, 1.0], 1)).unwrap();
+	let lines = solver.lines();
+	assert_eq!(lines[0], ("".to_string(), 0));
+	assert_eq!(lines.contains(&("x".to_string(), 1)), true);
+	assert_eq!(lines.contains(&("b100".to_string(), 1)), true);
+	assert_eq!(lines.contains(&("x b50 r250".to_string(), 1)), true);
+	assert_eq!(lines.contains(&("b100 r400".to_string(), 0)), true);
+	// one raise only
+	assert_eq!(lines.iter().any(|l| l.0.matches('r').count() > 1), false);
+
+	let actions: Vec<String> = solver.strategy("b100", Card::parse_list("Ks Kd").unwrap()[..].try_into().unwrap()).unwrap().into_iter().map(|a| a.0).collect();
+	assert_eq!(actions, vec!["f", "c", "r250", "r400"]);
+	assert_eq!(solver.strategy("b100", Card::parse_list("Qc Qd").unwrap()[..].try_into().unwrap()), None);
+	assert_eq!(solver.strategy("b75", Card::parse_list("Ks Kd").unwrap()[..].try_into().unwrap()), None);
+
+	// bets are capped at the stack
+	let solver = RiverSolver::new(toy_spot(60, vec![0.5, 1.0], 2)).unwrap();
+	let actions: Vec<String> = solver.strategy("", Card::parse_list("Qc Qd").unwrap()[..].try_into().unwrap()).unwrap().into_iter().map(|a| a.0).collect();
+	assert_eq!(actions, vec!["x", "b50", "b60"]);
+}
+
+#[test]
+fn check_polarized_spot() {
+	let mut solver = RiverSolver::new(toy_spot(100, vec![1.0], 0)).unwrap();
+	let report = solver.solve(2000, 1000);
+	assert_eq!(report.len(), 2);
+	assert_eq!(report[1].1 < 0.5, true);
+	assert_eq!((solver.ev(0) - 25.0).abs() < 1.0, true);
+	assert_eq!((solver.ev(1) - 75.0).abs() < 1.0, true);
+
+	let bluff = solver.strategy("x", Card::parse_list("4s 3s").unwrap()[..].try_into().unwrap()).unwrap();
+	assert_eq!((bluff[1].1 - 0.5).abs() < 0.05, true);
+	let value = solver.strategy("x", Card::parse_list("Ks Kd").unwrap()[..].try_into().unwrap()).unwrap();
+	assert_eq!(value[1].1 > 0.95, true);
+	let call = solver.strategy("x b100", Card::parse_list("Qc Qd").unwrap()[..].try_into().unwrap()).unwrap();
+	assert_eq!((call[1].1 - 0.5).abs() < 0.05, true);
+	let lead = solver.strategy("", Card::parse_list("Qc Qd").unwrap()[..].try_into().unwrap()).unwrap();
+	assert_eq!(lead[0].1 > 0.95, true);
+
+	let results = solver.combo_results(1);
+	assert_eq!(results.len(), 2);
+	assert_eq!((results[0].ev - 150.0).abs() < 2.0, true);
+	assert_eq!(results[1].ev.abs() < 2.0, true);
+}
+
+#[test]
+fn check_ranges() {
+	let board = Card::parse_list("Ah 8d 5c 2s Ks").unwrap();
+	let spot = RiverSpot {
+		board: board.clone(),
+		ranges: [uniform(&Range::parse("AK,KQ,88,55,QJs").unwrap()), uniform(&Range::parse("AA,AQ,KJ,T9s").unwrap())],
+		pot: 60,
+		stack: 200,
+		bet_sizes: vec![0.5, 1.0],
+		max_raises: 1
+	};
+
+	let mut solver = RiverSolver::new(spot).unwrap();
+	let start = solver.exploitability();
+	let report = solver.solve(150, 50);
+	assert_eq!(report[2].1 < report[0].1, true);
+	assert_eq!(report[2].1 < start, true);
+	assert_eq!(report[2].1 < 0.01 * 60.0, true);
+	assert_eq!((solver.ev(0) + solver.ev(1) - 60.0).abs() < 1e-6, true);
+
+	// combos blocked by the board are left out
+	assert_eq!(solver.combos(1).len(), 3 + 12 + 12 + 4);
+	assert_eq!(solver.combos(0).iter().all(|c| !CardSet::from_cards(&board).intersects(CardSet::from_cards(c))), true);
+
+	let mut bad = toy_spot(100, vec![1.0], 0);
+	bad.board.pop();
+	assert_eq!(RiverSolver::new(bad).err(), Some(RiverError::INVALID_BOARD));
+	assert_eq!(RiverSolver::new(toy_spot(100, vec![], 0)).err(), Some(RiverError::INVALID_BET_SIZES));
+
+	let mut bad = toy_spot(100, vec![1.0], 0);
+	bad.ranges[1] = vec![(Card::parse_list("Kh Kd").unwrap()[..].try_into().unwrap(), 1.0)];
+	assert_eq!(RiverSolver::new(bad).err(), Some(RiverError::EMPTY_RANGE(1)));
+}
@@ -0,0 +1,350 @@
+use rand::Rng;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::equity;
+use crate::equity::EquityError;
+use crate::equity::EquityResult;
+use crate::eval;
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::history;
+use crate::range::Range;
+use crate::range::RangeError;
+use crate::table::Street;
+
+#[cfg(test)]
+mod tests;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SessionError {
+	UNKNOWN_COMMAND,
+	MISSING_ARGUMENT,
+	INVALID_CARDS,
+	INVALID_RANGE(RangeError),
+	REPEATING_CARDS,
+	// the board takes 0, 3, 4 or 5 cards
+	INVALID_BOARD,
+	// the street cannot be dealt on the current board
+	WRONG_STREET,
+	NO_HERO,
+	NO_VILLAINS,
+	NOTHING_TO_UNDO,
+	INVALID_SESSION,
+	EQUITY(EquityError)
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone)]
+pub enum Command {
+	HERO(Vec<Card>),
+	VILLAIN(Range),
+	CLEAR_VILLAINS,
+	BOARD(Vec<Card>),
+	// deals the street, at random when no cards are given
+	DEAL(Street, Vec<Card>),
+	UNDO,
+	RESET,
+	SHOW,
+	EQUITY,
+	OUTS,
+	SAVE(String),
+	LOAD(String),
+	HELP,
+	QUIT
+}
+
+pub const HELP: &str = "\
+hero <cards>            set the hero's hole cards, e.g. hero AhKh
+villain <range>         add an opponent with a hand or a range, e.g. villain QQ+,AKs
+clear                   remove all opponents
+board <cards>           set the whole board, 0, 3, 4 or 5 cards
+flop|turn|river [cards] deal the next street, at random without cards
+undo                    take back the last change
+reset                   start over
+show                    the hand, the board and the opponents
+equity                  equity of every player
+outs                    cards that put the hero ahead
+save <file>             save the session
+load <file>             load a saved session
+help                    this message
+quit                    leave";
+
+fn parse_cards(s: &str) -> Result<Vec<Card>, SessionError> {
+	let cards = Card::parse_list(s).ok_or(SessionError::INVALID_CARDS)?;
+	if CardSet::from_cards(&cards).len() != cards.len() {
+		return Err(SessionError::REPEATING_CARDS);
+	}
+
+	return Ok(cards);
+}
+
+impl Command {
+	pub fn parse(line: &str) -> Result<Command, SessionError> {
+		let line = line.trim();
+		let (name, argument) = match line.split_once(char::is_whitespace) {
+			Some((name, argument)) => (name, argument.trim()),
+			None => (line, "")
+		};
+
+		let required = || if argument.is_empty() { Err(SessionError::MISSING_ARGUMENT) } else { Ok(argument) };
+		let command = match name.to_lowercase().as_str() {
+			"hero" => Command::HERO(parse_cards(required()?)?),
+			"villain" => Command::VILLAIN(Range::parse(required()?).map_err(SessionError::INVALID_RANGE)?),
+			"clear" => Command::CLEAR_VILLAINS,
+			"board" => Command::BOARD(parse_cards(argument)?),
+			"flop" => Command::DEAL(Street::FLOP, parse_cards(argument)?),
+			"turn" => Command::DEAL(Street::TURN, parse_cards(argument)?),
+			"river" => Command::DEAL(Street::RIVER, parse_cards(argument)?),
+			"undo" => Command::UNDO,
+			"reset" => Command::RESET,
+			"show" => Command::SHOW,
+			"equity" => Command::EQUITY,
+			"outs" => Command::OUTS,
+			"save" => Command::SAVE(required()?.to_string()),
+			"load" => Command::LOAD(required()?.to_string()),
+			"help" => Command::HELP,
+			"quit" | "exit" => Command::QUIT,
+			_ => return Err(SessionError::UNKNOWN_COMMAND)
+		};
+
+		return Ok(command);
+	}
+}
+
+// What gets written by save, with cards and ranges in their usual notation
+#[derive(Serialize, Deserialize)]
+struct SavedSession {
+	hero: String,
+	board: String,
+	villains: Vec<String>
+}
+
+#[derive(Clone, PartialEq, Default)]
+struct State {
+	hero: Vec<Card>,
+	board: Vec<Card>,
+	villains: Vec<Range>
+}
+
+// A spot being studied: the hero's cards, the board and the opponents' ranges,
+// with every change kept so that it can be undone.
+#[derive(Clone, Default)]
+pub struct Session {
+	state: State,
+	history: Vec<State>
+}
+
+impl Session {
+	pub fn new() -> Session {
+		return Session::default();
+	}
+
+	pub fn hero(&self) -> &[Card] {
+		return &self.state.hero;
+	}
+
+	pub fn board(&self) -> &[Card] {
+		return &self.state.board;
+	}
+
+	pub fn villains(&self) -> &[Range] {
+		return &self.state.villains;
+	}
+
+	fn known_cards(&self) -> CardSet {
+		return CardSet::from_cards(&self.state.hero).union(CardSet::from_cards(&self.state.board));
+	}
+
+	fn deal(&self, street: Street, cards: &[Card], rng: &mut impl Rng) -> Result<Vec<Card>, SessionError> {
+		let (before, count) = match street {
+			Street::FLOP => (0, 3),
+			Street::TURN => (3, 1),
+			Street::RIVER => (4, 1),
+			_ => return Err(SessionError::WRONG_STREET)
+		};
+
+		if self.state.board.len() != before {
+			return Err(SessionError::WRONG_STREET);
+		}
+
+		if cards.is_empty() {
+			let mut deck: Vec<Card> = CardSet::FULL_DECK.difference(self.known_cards()).iter().collect();
+			return Ok((0..count).map(|_| deck.swap_remove(rng.gen_range(0, deck.len()))).collect());
+		}
+
+		if cards.len() != count {
+			return Err(SessionError::INVALID_CARDS);
+		}
+
+		if self.known_cards().intersects(CardSet::from_cards(cards)) {
+			return Err(SessionError::REPEATING_CARDS);
+		}
+
+		return Ok(cards.to_vec());
+	}
+
+	// Changes the spot; the commands that only look at it are left to the caller
+	pub fn apply(&mut self, command: &Command, rng: &mut impl Rng) -> Result<(), SessionError> {
+		let mut state = self.state.clone();
+		match command {
+			Command::HERO(cards) => {
+				if cards.len() != 2 {
+					return Err(SessionError::INVALID_CARDS);
+				}
+
+				if CardSet::from_cards(&state.board).intersects(CardSet::from_cards(cards)) {
+					return Err(SessionError::REPEATING_CARDS);
+				}
+
+				state.hero = cards.clone();
+			},
+			Command::VILLAIN(range) => state.villains.push(range.clone()),
+			Command::CLEAR_VILLAINS => state.villains.clear(),
+			Command::BOARD(cards) => {
+				if cards.len() == 1 || cards.len() == 2 || cards.len() > 5 {
+					return Err(SessionError::INVALID_BOARD);
+				}
+
+				if CardSet::from_cards(&state.hero).intersects(CardSet::from_cards(cards)) {
+					return Err(SessionError::REPEATING_CARDS);
+				}
+
+				state.board = cards.clone();
+			},
+			Command::DEAL(street, cards) => {
+				let dealt = self.deal(*street, cards, rng)?;
+				state.board.extend(dealt);
+			},
+			Command::UNDO => {
+				self.state = self.history.pop().ok_or(SessionError::NOTHING_TO_UNDO)?;
+				return Ok(());
+			},
+			Command::RESET => state = State::default(),
+			_ => return Ok(())
+		}
+
+		self.history.push(std::mem::replace(&mut self.state, state));
+		return Ok(());
+	}
+
+	pub fn describe(&self) -> Option<String> {
+		if self.state.hero.is_empty() || self.state.board.len() < 3 {
+			return None;
+		}
+
+		let cards: Vec<Card> = self.state.hero.iter().chain(self.state.board.iter()).copied().collect();
+		return Some(history::describe(eval::evaluate_cards(&cards)));
+	}
+
+	// Hero first, then the villains in the order they were added
+	pub fn equity(&self, rng: &mut impl Rng) -> Result<EquityResult, SessionError> {
+		if self.state.hero.is_empty() {
+			return Err(SessionError::NO_HERO);
+		}
+
+		if self.state.villains.is_empty() {
+			return Err(SessionError::NO_VILLAINS);
+		}
+
+		let mut ranges = vec![Range::from_combos(&[[self.state.hero[0], self.state.hero[1]]])];
+		ranges.extend(self.state.villains.iter().cloned());
+
+		return equity::calculate(&ranges, &self.state.board, &[], rng).map_err(SessionError::EQUITY);
+	}
+
+	// The hero is ahead when their made hand beats more than half of every
+	// villain's possible holdings, ties counting half.
+	fn is_ahead(&self, board: &[Card]) -> bool {
+		let known = CardSet::from_cards(&self.state.hero).union(CardSet::from_cards(board));
+		let hero = eval::evaluate(known);
+
+		return self.state.villains.iter().all(|range| {
+			let live = range.without(known);
+			let score = live.combos().iter().map(|c| {
+				let villain = eval::evaluate(CardSet::from_cards(c).union(CardSet::from_cards(board)));
+				if hero > villain { 2 } else if hero == villain { 1 } else { 0 }
+			}).sum::<usize>();
+
+			score > live.len()
+		});
+	}
+
+	// Cards that put the hero ahead on the next street while they are behind
+	// now; empty when the hero is already ahead.
+	pub fn outs(&self) -> Result<Vec<Card>, SessionError> {
+		if self.state.hero.is_empty() {
+			return Err(SessionError::NO_HERO);
+		}
+
+		if self.state.villains.is_empty() {
+			return Err(SessionError::NO_VILLAINS);
+		}
+
+		if self.state.board.len() < 3 || self.state.board.len() > 4 {
+			return Err(SessionError::WRONG_STREET);
+		}
+
+		if self.is_ahead(&self.state.board) {
+			return Ok(Vec::new());
+		}
+
+		let mut board = self.state.board.clone();
+		let mut outs = Vec::new();
+		for card in CardSet::FULL_DECK.difference(self.known_cards()).iter() {
+			board.push(card);
+			if self.is_ahead(&board) {
+				outs.push(card);
+			}
+			board.pop();
+		}
+
+		return Ok(outs);
+	}
+
+	pub fn to_string(&self) -> String {
+		let cards = |cards: &[Card]| cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ");
+		let mut lines = vec![
+			format!("Hero:  {}", if self.state.hero.is_empty() { "-".to_string() } else { cards(&self.state.hero) }),
+			format!("Board: {}", if self.state.board.is_empty() { "-".to_string() } else { cards(&self.state.board) })
+		];
+
+		for (i, range) in self.state.villains.iter().enumerate() {
+			lines.push(format!("Villain {}: {} ({} combos)", i + 1, range.to_string(), range.len()));
+		}
+
+		if let Some(description) = self.describe() {
+			lines.push(format!("Hand:  {}", description));
+		}
+
+		return lines.join("\n");
+	}
+
+	pub fn to_json(&self) -> String {
+		let saved = SavedSession {
+			hero: self.state.hero.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" "),
+			board: self.state.board.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" "),
+			villains: self.state.villains.iter().map(|r| r.to_string()).collect()
+		};
+
+		return serde_json::to_string_pretty(&saved).unwrap();
+	}
+
+	// Replaces the spot with a saved one, which can be undone like any other change
+	pub fn load_json(&mut self, json: &str) -> Result<(), SessionError> {
+		let saved: SavedSession = serde_json::from_str(json).map_err(|_| SessionError::INVALID_SESSION)?;
+		let hero = Card::parse_list(&saved.hero).ok_or(SessionError::INVALID_SESSION)?;
+		let board = Card::parse_list(&saved.board).ok_or(SessionError::INVALID_SESSION)?;
+		let villains = saved.villains.iter().map(|v| Range::parse(v)).collect::<Result<Vec<Range>, RangeError>>().map_err(|_| SessionError::INVALID_SESSION)?;
+
+		let known = CardSet::from_cards(&hero).union(CardSet::from_cards(&board));
+		if (!hero.is_empty() && hero.len() != 2) || board.len() > 5 || known.len() != hero.len() + board.len() {
+			return Err(SessionError::INVALID_SESSION);
+		}
+
+		let state = State {hero, board, villains};
+		self.history.push(std::mem::replace(&mut self.state, state));
+		return Ok(());
+	}
+}
@@ -0,0 +1,105 @@
+use super::*;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn cards(s: &str) -> Vec<Card> {
+	return Card::parse_list(s).unwrap();
+}
+
+fn run(session: &mut Session, lines: &[&str]) -> Result<(), SessionError> {
+	let mut rng = StdRng::seed_from_u64(39);
+	for line in lines {
+		session.apply(&Command::parse(line)?, &mut rng)?;
+	}
+
+	return Ok(());
+}
+
+#[test]
+fn check_commands() {
+	let mut session = Session::new();
+	run(&mut session, &["hero AhKh", "villain QQ", "board 2c 7d 9h", "turn Js"]).unwrap();
+	assert_eq!(session.hero() == cards("Ah Kh").as_slice(), true);
+	assert_eq!(session.board() == cards("2c 7d 9h Js").as_slice(), true);
+	assert_eq!(session.villains().len(), 1);
+	assert_eq!(session.describe(), Some("high card Ace".to_string()));
+	assert_eq!(session.to_string(), "Hero:  Ah Kh\nBoard: 2c 7d 9h Js\nVillain 1: QQ (6 combos)\nHand:  high card Ace");
+
+	// a random river avoids the known cards
+	run(&mut session, &["river"]).unwrap();
+	assert_eq!(session.board().len(), 5);
+	assert_eq!(CardSet::from_cards(session.board()).union(CardSet::from_cards(session.hero())).len(), 7);
+
+	assert_eq!(run(&mut session, &["river 3d"]).err(), Some(SessionError::WRONG_STREET));
+	assert_eq!(run(&mut session, &["board Ah"]).err(), Some(SessionError::INVALID_BOARD));
+	assert_eq!(run(&mut session, &["board As Kd 2d 2c Ah"]).err(), Some(SessionError::REPEATING_CARDS));
+	assert_eq!(run(&mut session, &["hero Ah"]).err(), Some(SessionError::INVALID_CARDS));
+	assert_eq!(run(&mut session, &["villain XX"]).err(), Some(SessionError::INVALID_RANGE(RangeError::INVALID_TOKEN(0))));
+	assert_eq!(run(&mut session, &["villain"]).err(), Some(SessionError::MISSING_ARGUMENT));
+	assert_eq!(run(&mut session, &["fold"]).err(), Some(SessionError::UNKNOWN_COMMAND));
+}
+
+#[test]
+fn check_undo() {
+	let mut session = Session::new();
+	run(&mut session, &["hero AhKh", "villain QQ", "flop 2c 7d 9h", "clear"]).unwrap();
+	assert_eq!(session.villains().len(), 0);
+
+	run(&mut session, &["undo"]).unwrap();
+	assert_eq!(session.villains().len(), 1);
+	run(&mut session, &["undo", "undo"]).unwrap();
+	assert_eq!(session.board().len(), 0);
+	assert_eq!(session.villains().len(), 0);
+
+	run(&mut session, &["undo"]).unwrap();
+	assert_eq!(session.hero().len(), 0);
+	assert_eq!(run(&mut session, &["undo"]).err(), Some(SessionError::NOTHING_TO_UNDO));
+
+	// looking does not count as a change
+	run(&mut session, &["hero AhKh", "show", "equity", "reset"]).unwrap();
+	run(&mut session, &["undo"]).unwrap();
+	assert_eq!(session.hero().len(), 2);
+}
+
+#[test]
+fn check_equity_and_outs() {
+	let mut rng = StdRng::seed_from_u64(39);
+	let mut session = Session::new();
+	assert_eq!(session.equity(&mut rng).err(), Some(SessionError::NO_HERO));
+
+	run(&mut session, &["hero QhJh", "board As Ks 2d 3c"]).unwrap();
+	assert_eq!(session.outs().err(), Some(SessionError::NO_VILLAINS));
+
+	run(&mut session, &["villain AhAd"]).unwrap();
+	let result = session.equity(&mut rng).unwrap();
+	assert_eq!(result.exact, true);
+	assert_eq!(result.players[0].equity, 4.0 / 44.0);
+
+	let outs: Vec<String> = session.outs().unwrap().iter().map(|c| c.to_string()).collect();
+	assert_eq!(outs, vec!["Tc", "Td", "Th", "Ts"]);
+
+	run(&mut session, &["undo", "villain 22"]).unwrap();
+	assert_eq!(session.outs().unwrap().is_empty(), false);
+	run(&mut session, &["river 2s"]).unwrap();
+	assert_eq!(session.outs().err(), Some(SessionError::WRONG_STREET));
+}
+
+#[test]
+fn check_save_and_load() {
+	let mut session = Session::new();
+	run(&mut session, &["hero AhKh", "villain TT+,AKs", "villain 22", "flop 2c 7d 9h"]).unwrap();
+	let json = session.to_json();
+
+	let mut loaded = Session::new();
+	run(&mut loaded, &["hero 5c5d"]).unwrap();
+	loaded.load_json(&json).unwrap();
+	assert_eq!(loaded.to_json(), json);
+	assert_eq!(loaded.villains()[0].to_string(), "AA,KK,QQ,JJ,TT,AKs");
+
+	run(&mut loaded, &["undo"]).unwrap();
+	assert_eq!(loaded.hero() == cards("5c 5d").as_slice(), true);
+
+	assert_eq!(loaded.load_json("{").err(), Some(SessionError::INVALID_SESSION));
+	assert_eq!(loaded.load_json(&json.replace("Ah Kh", "Ah Ah")).err(), Some(SessionError::INVALID_SESSION));
+}
@@ -0,0 +1,176 @@
+use crate::eval;
+use crate::eval::CardSet;
+use crate::eval::HandValue;
+use crate::hand::Card;
+use crate::hand::Hand;
+
+#[cfg(test)]
+mod tests;
+
+pub const MAX_PLAYERS: usize = 10;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Variant {
+	HOLDEM,
+	OMAHA
+}
+
+impl Variant {
+	pub fn hole_cards(&self) -> usize {
+		match self {
+			Variant::HOLDEM => 2,
+			Variant::OMAHA => 4
+		}
+	}
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ShowdownError {
+	NO_PLAYERS,
+	TOO_MANY_PLAYERS,
+	DUPLICATE_SEAT,
+	INVALID_HOLE_CARDS,
+	INVALID_BOARD,
+	REPEATING_CARDS
+}
+
+#[derive(Clone)]
+pub struct Showdown {
+	pub variant: Variant,
+	pub board: Vec<Card>,
+	pub button: usize,
+	// (seat, hole cards) of every player still in the hand
+	pub players: Vec<(usize, Vec<Card>)>
+}
+
+#[derive(Copy, Clone)]
+pub struct PlayerResult {
+	pub seat: usize,
+	pub value: HandValue,
+	pub best_hand: Hand,
+	pub tier: usize,
+	pub amount: u64
+}
+
+#[derive(Clone)]
+pub struct ShowdownResult {
+	// strongest tier first, seats within a tier in order from the button
+	pub tiers: Vec<Vec<usize>>,
+	pub players: Vec<PlayerResult>
+}
+
+impl ShowdownResult {
+	pub fn winners(&self) -> Vec<&PlayerResult> {
+		return self.players.iter().filter(|p| p.tier == 0).collect();
+	}
+
+	pub fn player(&self, seat: usize) -> Option<&PlayerResult> {
+		return self.players.iter().find(|p| p.seat == seat);
+	}
+}
+
+// Seats clockwise from the button: the first seat after the button comes
+// first and the button itself comes last.
+pub fn seat_order(seat: usize, button: usize) -> (bool, usize) {
+	return (seat <= button, seat);
+}
+
+// Splits an amount evenly between the seats, the odd chips going one each
+// to the winners closest to the left of the button.
+pub fn split_pot(amount: u64, seats: &[usize], button: usize) -> Vec<(usize, u64)> {
+	let mut ordered = seats.to_vec();
+	ordered.sort_by_key(|s| seat_order(*s, button));
+
+	if ordered.is_empty() {
+		return Vec::new();
+	}
+
+	let share = amount / ordered.len() as u64;
+	let odd_chips = (amount % ordered.len() as u64) as usize;
+
+	return ordered.iter().enumerate()
+		.map(|(i, s)| (*s, share + if i < odd_chips { 1 } else { 0 }))
+		.collect();
+}
+
+impl Showdown {
+	pub fn evaluate(&self, hole: &[Card]) -> (HandValue, Hand) {
+		let hand = match self.variant {
+			Variant::HOLDEM => {
+				let mut cards = hole.to_vec();
+				cards.extend_from_slice(&self.board);
+				eval::best_hand(&cards)
+			},
+			Variant::OMAHA => eval::best_omaha_hand(hole, &self.board)
+		};
+
+		return (eval::evaluate_hand(&hand), hand);
+	}
+
+	pub fn validate(&self) -> Result<(), ShowdownError> {
+		if self.players.is_empty() {
+			return Err(ShowdownError::NO_PLAYERS);
+		}
+
+		if self.players.len() > MAX_PLAYERS {
+			return Err(ShowdownError::TOO_MANY_PLAYERS);
+		}
+
+		if self.board.len() != 5 {
+			return Err(ShowdownError::INVALID_BOARD);
+		}
+
+		let mut seen = CardSet::from_cards(&self.board);
+		if seen.len() != self.board.len() {
+			return Err(ShowdownError::REPEATING_CARDS);
+		}
+
+		for (i, (seat, hole)) in self.players.iter().enumerate() {
+			if self.players[..i].iter().any(|p| p.0 == *seat) {
+				return Err(ShowdownError::DUPLICATE_SEAT);
+			}
+
+			if hole.len() != self.variant.hole_cards() {
+				return Err(ShowdownError::INVALID_HOLE_CARDS);
+			}
+
+			for card in hole {
+				if !seen.insert(*card) {
+					return Err(ShowdownError::REPEATING_CARDS);
+				}
+			}
+		}
+
+		return Ok(());
+	}
+
+	pub fn resolve(&self, pot: u64) -> Result<ShowdownResult, ShowdownError> {
+		self.validate()?;
+
+		let mut players: Vec<PlayerResult> = self.players.iter().map(|(seat, hole)| {
+			let (value, best_hand) = self.evaluate(hole);
+			PlayerResult {seat: *seat, value, best_hand, tier: 0, amount: 0}
+		}).collect();
+
+		players.sort_by(|a, b| b.value.cmp(&a.value).then(seat_order(a.seat, self.button).cmp(&seat_order(b.seat, self.button))));
+
+		let mut tiers: Vec<Vec<usize>> = Vec::new();
+		for i in 0..players.len() {
+			if i > 0 && players[i].value == players[i - 1].value {
+				tiers.last_mut().unwrap().push(players[i].seat);
+			} else {
+				tiers.push(vec![players[i].seat]);
+			}
+
+			players[i].tier = tiers.len() - 1;
+		}
+
+		for (seat, amount) in split_pot(pot, &tiers[0], self.button) {
+			players.iter_mut().find(|p| p.seat == seat).unwrap().amount = amount;
+		}
+
+		return Ok(ShowdownResult {tiers, players});
+	}
+}
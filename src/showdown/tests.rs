@@ -0,0 +1,89 @@
+use super::*;
+
+use crate::hand::cards;
+
+#[test]
+fn check_single_winner() {
+	let showdown = Showdown {
+		variant: Variant::HOLDEM,
+		board: cards("Ah Kd 7c 7s 2h"),
+		button: 0,
+		players: vec![(1, cards("As Qc")), (3, cards("7d 3c")), (5, cards("Kh Ks"))]
+	};
+
+	let result = showdown.resolve(300).unwrap();
+	assert_eq!(result.tiers, vec![vec![5], vec![3], vec![1]]);
+	assert_eq!(result.winners().len(), 1);
+	assert_eq!(result.player(5).unwrap().amount, 300);
+	assert_eq!(result.player(3).unwrap().amount, 0);
+	assert_eq!(eval::evaluate_hand(&result.player(5).unwrap().best_hand), eval::evaluate_cards(&cards("Kh Ks Kd 7c 7s")));
+}
+
+#[test]
+fn check_split_with_odd_chips() {
+	let showdown = Showdown {
+		variant: Variant::HOLDEM,
+		board: cards("Ah Kd Qc Js Th"),
+		button: 4,
+		players: vec![(1, cards("2c 3c")), (3, cards("4d 5d")), (6, cards("6s 8s")), (8, cards("Ac Kc"))]
+	};
+
+	let result = showdown.resolve(101).unwrap();
+	assert_eq!(result.tiers.len(), 1);
+	assert_eq!(result.tiers[0], vec![6, 8, 1, 3]);
+	assert_eq!(result.player(6).unwrap().amount, 26);
+	assert_eq!(result.player(8).unwrap().amount, 25);
+	assert_eq!(result.player(1).unwrap().amount, 25);
+	assert_eq!(result.player(3).unwrap().amount, 25);
+}
+
+#[test]
+fn check_split_pot() {
+	assert_eq!(split_pot(10, &[2, 7, 5], 5), vec![(7, 4), (2, 3), (5, 3)]);
+	assert_eq!(split_pot(11, &[2, 7, 5], 5), vec![(7, 4), (2, 4), (5, 3)]);
+	assert_eq!(split_pot(9, &[2], 5), vec![(2, 9)]);
+	assert_eq!(split_pot(9, &[], 5), vec![]);
+}
+
+#[test]
+fn check_omaha() {
+	let showdown = Showdown {
+		variant: Variant::OMAHA,
+		board: cards("Ah Kh Qh 2c 3d"),
+		button: 0,
+		players: vec![(1, cards("Jh 4c 5c 6c")), (2, cards("Jh Th 9s 9d"))]
+	};
+	assert_eq!(showdown.resolve(10).err(), Some(ShowdownError::REPEATING_CARDS));
+
+	let showdown = Showdown {
+		variant: Variant::OMAHA,
+		board: cards("Ah Kh Qh 2c 3d"),
+		button: 0,
+		players: vec![(1, cards("Jh 4c 5c 6c")), (2, cards("Js Ts 9s 9d"))]
+	};
+
+	let result = showdown.resolve(10).unwrap();
+	assert_eq!(result.tiers, vec![vec![2], vec![1]]);
+	assert_eq!(result.player(2).unwrap().amount, 10);
+}
+
+#[test]
+fn check_invalid_showdowns() {
+	let mut showdown = Showdown {
+		variant: Variant::HOLDEM,
+		board: cards("Ah Kd Qc Js"),
+		button: 0,
+		players: vec![(1, cards("2c 3c"))]
+	};
+	assert_eq!(showdown.resolve(10).err(), Some(ShowdownError::INVALID_BOARD));
+
+	showdown.board = cards("Ah Kd Qc Js Th");
+	showdown.players.push((1, cards("4c 5c")));
+	assert_eq!(showdown.resolve(10).err(), Some(ShowdownError::DUPLICATE_SEAT));
+
+	showdown.players = vec![(1, cards("2c 3c 4c"))];
+	assert_eq!(showdown.resolve(10).err(), Some(ShowdownError::INVALID_HOLE_CARDS));
+
+	showdown.players = (0..11).map(|s| (s, cards("2c 3c"))).collect();
+	assert_eq!(showdown.resolve(10).err(), Some(ShowdownError::TOO_MANY_PLAYERS));
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use crate::history::ActionKind;
+use crate::history::HandRecord;
+use crate::table::Street;
+
+#[cfg(test)]
+mod tests;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Position {
+	SMALL_BLIND,
+	BIG_BLIND,
+	EARLY,
+	MIDDLE,
+	CUTOFF,
+	BUTTON
+}
+
+impl Position {
+	pub fn to_int(&self) -> usize {
+		match self {
+			Position::SMALL_BLIND => 	0,
+			Position::BIG_BLIND => 		1,
+			Position::EARLY => 			2,
+			Position::MIDDLE => 		3,
+			Position::CUTOFF => 		4,
+			Position::BUTTON => 		5
+		}
+	}
+}
+
+// Raw counts, so that hands can be added one at a time and the percentages
+// worked out whenever they are needed.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Counters {
+	pub hands: u32,
+	pub vpip: u32,
+	pub pfr: u32,
+	pub three_bet_chances: u32,
+	pub three_bets: u32,
+	pub fold_to_three_bet_chances: u32,
+	pub folds_to_three_bet: u32,
+	pub cbet_chances: u32,
+	pub cbets: u32,
+	// postflop bets and raises
+	pub aggressive_actions: u32,
+	// postflop calls
+	pub calls: u32,
+	pub saw_flop: u32,
+	pub showdowns: u32,
+	pub showdowns_won: u32,
+	// net result in big blinds
+	pub won_bb: f64
+}
+
+fn ratio(count: u32, chances: u32) -> Option<f64> {
+	if chances == 0 {
+		return None;
+	}
+
+	return Some(count as f64 / chances as f64);
+}
+
+impl Counters {
+	pub fn add(&mut self, other: &Counters) {
+		self.hands += other.hands;
+		self.vpip += other.vpip;
+		self.pfr += other.pfr;
+		self.three_bet_chances += other.three_bet_chances;
+		self.three_bets += other.three_bets;
+		self.fold_to_three_bet_chances += other.fold_to_three_bet_chances;
+		self.folds_to_three_bet += other.folds_to_three_bet;
+		self.cbet_chances += other.cbet_chances;
+		self.cbets += other.cbets;
+		self.aggressive_actions += other.aggressive_actions;
+		self.calls += other.calls;
+		self.saw_flop += other.saw_flop;
+		self.showdowns += other.showdowns;
+		self.showdowns_won += other.showdowns_won;
+		self.won_bb += other.won_bb;
+	}
+
+	pub fn vpip(&self) -> Option<f64> {
+		return ratio(self.vpip, self.hands);
+	}
+
+	pub fn pfr(&self) -> Option<f64> {
+		return ratio(self.pfr, self.hands);
+	}
+
+	pub fn three_bet(&self) -> Option<f64> {
+		return ratio(self.three_bets, self.three_bet_chances);
+	}
+
+	pub fn fold_to_three_bet(&self) -> Option<f64> {
+		return ratio(self.folds_to_three_bet, self.fold_to_three_bet_chances);
+	}
+
+	pub fn cbet(&self) -> Option<f64> {
+		return ratio(self.cbets, self.cbet_chances);
+	}
+
+	pub fn aggression_factor(&self) -> Option<f64> {
+		return ratio(self.aggressive_actions, self.calls);
+	}
+
+	pub fn wtsd(&self) -> Option<f64> {
+		return ratio(self.showdowns, self.saw_flop);
+	}
+
+	pub fn wsd(&self) -> Option<f64> {
+		return ratio(self.showdowns_won, self.showdowns);
+	}
+
+	pub fn bb_per_100(&self) -> Option<f64> {
+		if self.hands == 0 {
+			return None;
+		}
+
+		return Some(self.won_bb / self.hands as f64 * 100.0);
+	}
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PlayerStats {
+	pub total: Counters,
+	pub positions: [Counters; 6]
+}
+
+impl PlayerStats {
+	pub fn position(&self, position: Position) -> &Counters {
+		return &self.positions[position.to_int()];
+	}
+}
+
+// Players dealt in, clockwise from the small blind. Heads-up the button posts
+// the small blind and is counted as the button.
+pub fn positions(record: &HandRecord) -> Vec<(String, Position)> {
+	let mut seats: Vec<(usize, &str)> = record.seats.iter().filter(|s| !s.sitting_out).map(|s| (s.seat, s.name.as_str())).collect();
+	seats.sort_by_key(|s| (s.0 <= record.button, s.0));
+
+	if seats.len() == 2 {
+		return vec![(seats[1].1.to_string(), Position::BUTTON), (seats[0].1.to_string(), Position::BIG_BLIND)];
+	}
+
+	let others = seats.len().saturating_sub(4);
+	let early = others.div_ceil(2);
+	return seats.iter().enumerate().map(|(i, (_, name))| {
+		let position = match i {
+			0 => Position::SMALL_BLIND,
+			1 => Position::BIG_BLIND,
+			_ if i == seats.len() - 1 => Position::BUTTON,
+			_ if i == seats.len() - 2 => Position::CUTOFF,
+			_ if i - 2 < early => Position::EARLY,
+			_ => Position::MIDDLE
+		};
+
+		(name.to_string(), position)
+	}).collect();
+}
+
+fn is_aggressive(kind: &ActionKind) -> bool {
+	return matches!(kind, ActionKind::BET(_) | ActionKind::RAISE(_, _));
+}
+
+// What a single hand adds to every player's counters
+pub fn hand_counters(record: &HandRecord) -> Vec<(String, Position, Counters)> {
+	let mut result: Vec<(String, Position, Counters)> = positions(record).into_iter().map(|(name, position)| (name, position, Counters {hands: 1, ..Counters::default()})).collect();
+	let names: Vec<String> = result.iter().map(|r| r.0.clone()).collect();
+	let index = |name: &str| names.iter().position(|n| n == name);
+
+	// preflop: raises faced by each player the first time they act in a spot
+	let mut raises = 0;
+	let mut first_raiser: Option<String> = None;
+	let mut last_aggressor: Option<String> = None;
+	let mut three_bet_seen = vec![false; result.len()];
+	let mut fold_to_three_bet_seen = vec![false; result.len()];
+
+	for action in record.actions.iter().filter(|a| a.street == Street::PREFLOP) {
+		let i = match index(&action.player) {
+			Some(i) => i,
+			None => continue
+		};
+
+		if !matches!(action.kind, ActionKind::FOLD | ActionKind::CHECK | ActionKind::CALL(_) | ActionKind::BET(_) | ActionKind::RAISE(_, _)) {
+			continue;
+		}
+
+		let counters = &mut result[i].2;
+		if raises == 1 && !three_bet_seen[i] && first_raiser.as_deref() != Some(action.player.as_str()) {
+			three_bet_seen[i] = true;
+			counters.three_bet_chances += 1;
+			if is_aggressive(&action.kind) {
+				counters.three_bets += 1;
+			}
+		}
+
+		if raises >= 2 && !fold_to_three_bet_seen[i] && first_raiser.as_deref() == Some(action.player.as_str()) {
+			fold_to_three_bet_seen[i] = true;
+			counters.fold_to_three_bet_chances += 1;
+			if action.kind == ActionKind::FOLD {
+				counters.folds_to_three_bet += 1;
+			}
+		}
+
+		if matches!(action.kind, ActionKind::CALL(_) | ActionKind::BET(_) | ActionKind::RAISE(_, _)) {
+			counters.vpip = 1;
+		}
+
+		if is_aggressive(&action.kind) {
+			counters.pfr = 1;
+			raises += 1;
+			if first_raiser.is_none() {
+				first_raiser = Some(action.player.clone());
+			}
+			last_aggressor = Some(action.player.clone());
+		}
+	}
+
+	// c-bet: the preflop aggressor is first to bet on the flop
+	if let Some(aggressor) = last_aggressor {
+		let mut flop = record.actions.iter().filter(|a| a.street == Street::FLOP);
+		if let Some(action) = flop.find(|a| a.player == aggressor || is_aggressive(&a.kind)) {
+			if action.player == aggressor {
+				let counters = &mut result[index(&aggressor).unwrap()].2;
+				counters.cbet_chances += 1;
+				if is_aggressive(&action.kind) {
+					counters.cbets += 1;
+				}
+			}
+		}
+	}
+
+	let live: Vec<bool> = result.iter().map(|r| record.folded(&r.0).is_none()).collect();
+	let showdown = live.iter().filter(|l| **l).count() > 1;
+	let contributions = record.contributions();
+	let big_blind = record.big_blind().max(1) as f64;
+
+	for (i, (name, _, counters)) in result.iter_mut().enumerate() {
+		for action in record.player_actions(name).filter(|a| a.street >= Street::FLOP && a.street <= Street::RIVER) {
+			match action.kind {
+				ActionKind::BET(_) | ActionKind::RAISE(_, _) => counters.aggressive_actions += 1,
+				ActionKind::CALL(_) => counters.calls += 1,
+				_ => ()
+			}
+		}
+
+		if record.board.len() >= 3 && record.folded(name) != Some(Street::PREFLOP) {
+			counters.saw_flop = 1;
+			if showdown && live[i] {
+				counters.showdowns = 1;
+				if record.collected(name) > 0 {
+					counters.showdowns_won = 1;
+				}
+			}
+		}
+
+		let seat = record.seat(name).unwrap().seat;
+		let put_in = contributions.iter().find(|c| c.seat == seat).map_or(0, |c| c.amount);
+		let returned = record.player_actions(name).map(|a| match a.kind {
+			ActionKind::UNCALLED(amount) => amount,
+			_ => 0
+		}).sum::<u64>();
+
+		counters.won_bb = ((record.collected(name) + returned) as f64 - put_in as f64) / big_blind;
+	}
+
+	return result;
+}
+
+#[derive(Clone, Default)]
+pub struct StatsEngine {
+	pub hands: u32,
+	players: HashMap<String, PlayerStats>
+}
+
+impl StatsEngine {
+	pub fn new() -> StatsEngine {
+		return StatsEngine::default();
+	}
+
+	pub fn add_hand(&mut self, record: &HandRecord) {
+		self.hands += 1;
+		for (name, position, counters) in hand_counters(record) {
+			let stats = self.players.entry(name).or_default();
+			stats.total.add(&counters);
+			stats.positions[position.to_int()].add(&counters);
+		}
+	}
+
+	pub fn add_hands(&mut self, records: &[HandRecord]) {
+		for record in records {
+			self.add_hand(record);
+		}
+	}
+
+	pub fn player(&self, name: &str) -> Option<&PlayerStats> {
+		return self.players.get(name);
+	}
+
+	// Sorted by name
+	pub fn players(&self) -> Vec<(&String, &PlayerStats)> {
+		let mut players: Vec<(&String, &PlayerStats)> = self.players.iter().collect();
+		players.sort_by_key(|p| p.0);
+
+		return players;
+	}
+}
@@ -0,0 +1,168 @@
+use super::*;
+
+use crate::history::parse_hand;
+
+const THREE_BET_HAND: &str = "\
+PokerStars Hand #240000000001:  Hold'em No Limit ($0.50/$1 USD) - 2021/07/01 20:00:00 ET
+Table 'Delta' 6-max Seat #6 is the button
+Seat 1: P1 ($100 in chips)
+Seat 2: P2 ($100 in chips)
+Seat 3: P3 ($100 in chips)
+Seat 4: P4 ($100 in chips)
+Seat 5: P5 ($100 in chips)
+Seat 6: P6 ($100 in chips)
+P1: posts small blind $0.50
+P2: posts big blind $1
+*** HOLE CARDS ***
+P3: raises $2 to $3
+P4: calls $3
+P5: raises $6 to $9
+P6: folds
+P1: folds
+P2: folds
+P3: calls $6
+P4: folds
+*** FLOP *** [2c 7d 9h]
+P3: checks
+P5: bets $10
+P3: calls $10
+*** TURN *** [2c 7d 9h] [Js]
+P3: checks
+P5: checks
+*** RIVER *** [2c 7d 9h Js] [3d]
+P3: bets $20
+P5: calls $20
+*** SHOW DOWN ***
+P3: shows [Ah Ad] (a pair of Aces)
+P5: mucks hand
+P3 collected $79.50 from pot
+*** SUMMARY ***
+Total pot $82.50 | Rake $3
+Board [2c 7d 9h Js 3d]
+";
+
+const STEAL_HAND: &str = "\
+PokerStars Hand #240000000002:  Hold'em No Limit ($0.50/$1 USD) - 2021/07/01 20:01:00 ET
+Table 'Delta' 6-max Seat #5 is the button
+Seat 2: P2 ($100 in chips)
+Seat 5: P5 ($100 in chips)
+Seat 6: P6 ($100 in chips)
+P6: posts small blind $0.50
+P2: posts big blind $1
+*** HOLE CARDS ***
+P5: raises $2 to $3
+P6: folds
+P2: folds
+Uncalled bet ($2) returned to P5
+P5 collected $2.50 from pot
+*** SUMMARY ***
+Total pot $2.50 | Rake $0
+";
+
+fn counters(record: &HandRecord, name: &str) -> (Position, Counters) {
+	let (_, position, counters) = hand_counters(record).into_iter().find(|c| c.0 == name).unwrap();
+	return (position, counters);
+}
+
+#[test]
+fn check_positions() {
+	let record = parse_hand(THREE_BET_HAND).unwrap();
+	assert_eq!(positions(&record), vec![
+		("P1".to_string(), Position::SMALL_BLIND),
+		("P2".to_string(), Position::BIG_BLIND),
+		("P3".to_string(), Position::EARLY),
+		("P4".to_string(), Position::MIDDLE),
+		("P5".to_string(), Position::CUTOFF),
+		("P6".to_string(), Position::BUTTON)
+	]);
+
+	let record = parse_hand(STEAL_HAND).unwrap();
+	assert_eq!(positions(&record), vec![
+		("P6".to_string(), Position::SMALL_BLIND),
+		("P2".to_string(), Position::BIG_BLIND),
+		("P5".to_string(), Position::BUTTON)
+	]);
+
+	let text = STEAL_HAND.replace("Seat 6: P6 ($100 in chips)\n", "").replace("P6: posts small blind $0.50\n", "").replace("P6: folds\n", "");
+	let text = text.replace("P2: posts big blind $1", "P5: posts small blind $0.50\nP2: posts big blind $1");
+	let record = parse_hand(&text).unwrap();
+	assert_eq!(positions(&record), vec![("P5".to_string(), Position::BUTTON), ("P2".to_string(), Position::BIG_BLIND)]);
+}
+
+#[test]
+fn check_hand_counters() {
+	let record = parse_hand(THREE_BET_HAND).unwrap();
+
+	let (position, p3) = counters(&record, "P3");
+	assert_eq!(position, Position::EARLY);
+	assert_eq!(p3, Counters {
+		hands: 1, vpip: 1, pfr: 1,
+		fold_to_three_bet_chances: 1,
+		calls: 1, aggressive_actions: 1,
+		saw_flop: 1, showdowns: 1, showdowns_won: 1,
+		won_bb: 40.5,
+		..Counters::default()
+	});
+
+	let (_, p4) = counters(&record, "P4");
+	assert_eq!(p4, Counters {hands: 1, vpip: 1, three_bet_chances: 1, won_bb: -3.0, ..Counters::default()});
+
+	let (_, p5) = counters(&record, "P5");
+	assert_eq!(p5, Counters {
+		hands: 1, vpip: 1, pfr: 1,
+		three_bet_chances: 1, three_bets: 1,
+		cbet_chances: 1, cbets: 1,
+		calls: 1, aggressive_actions: 1,
+		saw_flop: 1, showdowns: 1,
+		won_bb: -39.0,
+		..Counters::default()
+	});
+
+	// facing a 3-bet is not a 3-bet opportunity
+	let (_, p6) = counters(&record, "P6");
+	assert_eq!(p6, Counters {hands: 1, ..Counters::default()});
+	let (_, p1) = counters(&record, "P1");
+	assert_eq!(p1, Counters {hands: 1, won_bb: -0.5, ..Counters::default()});
+
+	// a bet in front of the preflop raiser takes away the c-bet
+	let text = THREE_BET_HAND.replace("P3: checks\nP5: bets $10\nP3: calls $10", "P3: bets $10\nP5: calls $10");
+	let (_, p5) = counters(&parse_hand(&text).unwrap(), "P5");
+	assert_eq!(p5.cbet_chances, 0);
+	assert_eq!(p5.calls, 2);
+}
+
+#[test]
+fn check_engine() {
+	let first = parse_hand(THREE_BET_HAND).unwrap();
+	let second = parse_hand(STEAL_HAND).unwrap();
+
+	let mut engine = StatsEngine::new();
+	engine.add_hand(&first);
+	assert_eq!(engine.player("P5").unwrap().total.vpip(), Some(1.0));
+	engine.add_hand(&second);
+	assert_eq!(engine.hands, 2);
+
+	let mut batch = StatsEngine::new();
+	batch.add_hands(&[first, second]);
+	assert_eq!(engine.players() == batch.players(), true);
+
+	let p5 = engine.player("P5").unwrap();
+	assert_eq!(p5.total.hands, 2);
+	assert_eq!(p5.total.pfr(), Some(1.0));
+	assert_eq!(p5.total.three_bet(), Some(1.0));
+	assert_eq!(p5.total.cbet(), Some(1.0));
+	assert_eq!(p5.total.aggression_factor(), Some(1.0));
+	assert_eq!(p5.total.wtsd(), Some(1.0));
+	assert_eq!(p5.total.wsd(), Some(0.0));
+	assert_eq!(p5.total.bb_per_100(), Some((-39.0 + 1.5) / 2.0 * 100.0));
+	assert_eq!(p5.position(Position::CUTOFF).hands, 1);
+	assert_eq!(p5.position(Position::BUTTON).pfr, 1);
+	assert_eq!(p5.position(Position::EARLY).hands, 0);
+
+	let p2 = engine.player("P2").unwrap();
+	assert_eq!(p2.total.vpip(), Some(0.0));
+	assert_eq!(p2.total.three_bet(), Some(0.0));
+	assert_eq!(p2.total.fold_to_three_bet(), None);
+	assert_eq!(p2.total.wtsd(), None);
+	assert_eq!(engine.player("P7").is_none(), true);
+}
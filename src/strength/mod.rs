@@ -0,0 +1,212 @@
+use std::cmp::Ordering;
+
+use crate::eval;
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::range::Range;
+
+#[cfg(test)]
+mod tests;
+
+// Billings' hand strength and potential. The opponent holds any two unseen
+// cards, or a combo of the given range; dead cards are dealt to no one.
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum StrengthError {
+	INVALID_HOLE_CARDS,
+	// 3 to 5 board cards
+	INVALID_BOARD,
+	REPEATING_CARDS,
+	// the range has no combo left next to the known cards
+	EMPTY_RANGE,
+	// the lookahead goes past the river
+	INVALID_LOOKAHEAD
+}
+
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct Potential {
+	// chance of getting ahead when behind or tied now
+	pub positive: f64,
+	// chance of falling behind when ahead or tied now
+	pub negative: f64
+}
+
+const AHEAD: usize = 0;
+const TIED: usize = 1;
+const BEHIND: usize = 2;
+
+fn check_cards(hole: &[Card], board: &[Card], dead: &[Card]) -> Result<CardSet, StrengthError> {
+	if hole.len() != 2 {
+		return Err(StrengthError::INVALID_HOLE_CARDS);
+	}
+
+	if board.len() < 3 || board.len() > 5 {
+		return Err(StrengthError::INVALID_BOARD);
+	}
+
+	let known = CardSet::from_cards(hole).union(CardSet::from_cards(board)).union(CardSet::from_cards(dead));
+	if known.len() != hole.len() + board.len() + dead.len() {
+		return Err(StrengthError::REPEATING_CARDS);
+	}
+
+	return Ok(known);
+}
+
+fn opponent_holdings(known: CardSet, range: Option<&Range>) -> Result<Vec<CardSet>, StrengthError> {
+	let holdings: Vec<CardSet> = match range {
+		Some(range) => range.without(known).combos().iter().map(|c| CardSet::from_cards(c)).collect(),
+		None => {
+			let deck: Vec<Card> = CardSet::FULL_DECK.difference(known).iter().collect();
+			let mut holdings = Vec::with_capacity(deck.len() * (deck.len() - 1) / 2);
+			for a in 0..deck.len() {
+				for b in a+1..deck.len() {
+					holdings.push(CardSet::from_cards(&[deck[a], deck[b]]));
+				}
+			}
+
+			holdings
+		}
+	};
+
+	if holdings.is_empty() {
+		return Err(StrengthError::EMPTY_RANGE);
+	}
+
+	return Ok(holdings);
+}
+
+fn compare(hero: CardSet, opponent: CardSet, board: CardSet) -> usize {
+	match eval::evaluate(hero.union(board)).cmp(&eval::evaluate(opponent.union(board))) {
+		Ordering::Greater => return AHEAD,
+		Ordering::Equal => return TIED,
+		Ordering::Less => return BEHIND
+	}
+}
+
+// Every set of the given number of cards out of the deck
+pub(crate) fn runouts(deck: &[Card], count: usize) -> Vec<CardSet> {
+	if count == 0 {
+		return vec![CardSet::EMPTY];
+	}
+
+	let mut result = Vec::new();
+	for i in 0..deck.len() {
+		for rest in runouts(&deck[i + 1..], count - 1) {
+			let mut runout = rest;
+			runout.insert(deck[i]);
+			result.push(runout);
+		}
+	}
+
+	return result;
+}
+
+fn strength_against(hero: CardSet, board: CardSet, holdings: &[CardSet]) -> Option<f64> {
+	let value = eval::evaluate(hero.union(board));
+	let (mut score, mut total) = (0.0, 0.0);
+	for holding in holdings.iter().filter(|h| !h.intersects(hero.union(board))) {
+		score += match value.cmp(&eval::evaluate(holding.union(board))) {
+			Ordering::Greater => 1.0,
+			Ordering::Equal => 0.5,
+			Ordering::Less => 0.0
+		};
+		total += 1.0;
+	}
+
+	if total == 0.0 {
+		return None;
+	}
+
+	return Some(score / total);
+}
+
+// Hand strength against every holding of the unseen cards, for cards already checked
+pub(crate) fn strength_against_random(hero: CardSet, board: CardSet) -> f64 {
+	let holdings = opponent_holdings(hero.union(board), None).unwrap();
+	return strength_against(hero, board, &holdings).unwrap();
+}
+
+// Share of the opponent holdings the hand beats right now, ties counting half
+pub fn hand_strength(hole: &[Card], board: &[Card], dead: &[Card], range: Option<&Range>) -> Result<f64, StrengthError> {
+	let known = check_cards(hole, board, dead)?;
+	let holdings = opponent_holdings(known, range)?;
+
+	return Ok(strength_against(CardSet::from_cards(hole), CardSet::from_cards(board), &holdings).unwrap());
+}
+
+// Against several opponents, taken as independent
+pub fn multiway_strength(strength: f64, opponents: u32) -> f64 {
+	return strength.powi(opponents as i32);
+}
+
+// Positive and negative potential over the next one or two cards
+pub fn potential(hole: &[Card], board: &[Card], dead: &[Card], range: Option<&Range>, lookahead: usize) -> Result<Potential, StrengthError> {
+	let known = check_cards(hole, board, dead)?;
+	if lookahead == 0 || board.len() + lookahead > 5 {
+		return Err(StrengthError::INVALID_LOOKAHEAD);
+	}
+
+	let hero = CardSet::from_cards(hole);
+	let board_set = CardSet::from_cards(board);
+	let mut transitions = [[0.0f64; 3]; 3];
+	let mut totals = [0.0f64; 3];
+
+	for holding in opponent_holdings(known, range)? {
+		let now = compare(hero, holding, board_set);
+		let deck: Vec<Card> = CardSet::FULL_DECK.difference(known.union(holding)).iter().collect();
+		for runout in runouts(&deck, lookahead) {
+			let later = compare(hero, holding, board_set.union(runout));
+			transitions[now][later] += 1.0;
+			totals[now] += 1.0;
+		}
+	}
+
+	let ratio = |a: f64, b: f64| if b == 0.0 { 0.0 } else { a / b };
+	let positive = ratio(
+		transitions[BEHIND][AHEAD] + transitions[BEHIND][TIED] / 2.0 + transitions[TIED][AHEAD] / 2.0,
+		totals[BEHIND] + totals[TIED] / 2.0
+	);
+	let negative = ratio(
+		transitions[AHEAD][BEHIND] + transitions[TIED][BEHIND] / 2.0 + transitions[AHEAD][TIED] / 2.0,
+		totals[AHEAD] + totals[TIED] / 2.0
+	);
+
+	return Ok(Potential {positive, negative});
+}
+
+// HS * (1 - NPot) + (1 - HS) * PPot, with the potential over the next card
+// and the strength raised to the number of opponents. On the river it is the
+// plain hand strength.
+pub fn effective_strength(hole: &[Card], board: &[Card], dead: &[Card], range: Option<&Range>, opponents: u32) -> Result<f64, StrengthError> {
+	let strength = multiway_strength(hand_strength(hole, board, dead, range)?, opponents);
+	if board.len() == 5 {
+		return Ok(strength);
+	}
+
+	let potential = potential(hole, board, dead, range, 1)?;
+	return Ok(strength * (1.0 - potential.negative) + (1.0 - strength) * potential.positive);
+}
+
+// Mean of the squared river hand strength over every runout of the board
+pub fn effective_strength_squared(hole: &[Card], board: &[Card], dead: &[Card], range: Option<&Range>) -> Result<f64, StrengthError> {
+	let known = check_cards(hole, board, dead)?;
+	let holdings = opponent_holdings(known, range)?;
+	let hero = CardSet::from_cards(hole);
+	let board_set = CardSet::from_cards(board);
+
+	let deck: Vec<Card> = CardSet::FULL_DECK.difference(known).iter().collect();
+	let (mut sum, mut count) = (0.0, 0.0);
+	for runout in runouts(&deck, 5 - board.len()) {
+		if let Some(strength) = strength_against(hero, board_set.union(runout), &holdings) {
+			sum += strength * strength;
+			count += 1.0;
+		}
+	}
+
+	if count == 0.0 {
+		return Err(StrengthError::EMPTY_RANGE);
+	}
+
+	return Ok(sum / count);
+}
@@ -0,0 +1,68 @@
+use super::*;
+
+fn range(s: &str) -> Range {
+	return Range::parse(s).unwrap();
+}
+
+#[test]
+fn check_hand_strength() {
+	let board = Card::parse_list("Ah Kh Qh 7c 2d").unwrap();
+	assert_eq!(hand_strength(&Card::parse_list("Ac Ad").unwrap(), &board, &[], None).unwrap(), 930.0 / 990.0);
+	assert_eq!(hand_strength(&Card::parse_list("Ac Ad").unwrap(), &board, &[], Some(&range("JTs"))).unwrap(), 0.0);
+	assert_eq!(hand_strength(&Card::parse_list("Ac Ad").unwrap(), &board, &[], Some(&range("KK"))).unwrap(), 1.0);
+	assert_eq!(hand_strength(&Card::parse_list("Jc Tc").unwrap(), &board, &[], Some(&range("JTs"))).unwrap(), 1.0 / 3.0);
+
+	// with the ten of diamonds dead only the royal flush and one tie are left
+	assert_eq!(hand_strength(&Card::parse_list("Jc Tc").unwrap(), &board, &Card::parse_list("Td").unwrap(), Some(&range("JTs"))).unwrap(), 0.25);
+	assert_eq!(multiway_strength(0.5, 3), 0.125);
+}
+
+#[test]
+fn check_potential() {
+	let hole = Card::parse_list("8h 9h").unwrap();
+	let board = Card::parse_list("6h 7c Kh 2d").unwrap();
+	let kings = range("KcKd");
+
+	// seven flushes that do not pair the board and six straights
+	let turn = potential(&hole, &board, &[], Some(&kings), 1).unwrap();
+	assert_eq!(turn.positive, 13.0 / 44.0);
+	assert_eq!(turn.negative, 0.0);
+	assert_eq!(effective_strength(&hole, &board, &[], Some(&kings), 1).unwrap(), 13.0 / 44.0);
+	assert_eq!(effective_strength_squared(&hole, &board, &[], Some(&kings)).unwrap(), 13.0 / 44.0);
+
+	// the nuts on the river can only lose ground to nothing
+	let river = Card::parse_list("6h 7c Kh 2d Th").unwrap();
+	assert_eq!(potential(&hole, &river, &[], None, 1).err(), Some(StrengthError::INVALID_LOOKAHEAD));
+	let strength = hand_strength(&hole, &river, &[], None).unwrap();
+	assert_eq!(effective_strength(&hole, &river, &[], None, 2).unwrap(), strength * strength);
+	assert_eq!(effective_strength_squared(&hole, &river, &[], None).unwrap(), strength * strength);
+
+	let flop = Card::parse_list("6h 7c Kh").unwrap();
+	let one = potential(&hole, &flop, &[], Some(&kings), 1).unwrap();
+	let two = potential(&hole, &flop, &[], Some(&kings), 2).unwrap();
+	assert_eq!(two.positive > one.positive, true);
+}
+
+#[test]
+fn check_random_opponent() {
+	let hole = Card::parse_list("Ac Kc").unwrap();
+	let board = Card::parse_list("Qc 7c 2d 9s").unwrap();
+	let strength = hand_strength(&hole, &board, &[], None).unwrap();
+	let next = potential(&hole, &board, &[], None, 1).unwrap();
+	let ehs = effective_strength(&hole, &board, &[], None, 1).unwrap();
+
+	assert_eq!(next.positive > 0.3 && next.positive < 0.5, true);
+	assert_eq!(next.negative < 0.2, true);
+	assert_eq!(ehs, strength * (1.0 - next.negative) + (1.0 - strength) * next.positive);
+	assert_eq!(ehs > strength, true);
+}
+
+#[test]
+fn check_errors() {
+	let board = Card::parse_list("Ah Kh Qh").unwrap();
+	assert_eq!(hand_strength(&Card::parse_list("Ac").unwrap(), &board, &[], None).err(), Some(StrengthError::INVALID_HOLE_CARDS));
+	assert_eq!(hand_strength(&Card::parse_list("Ac Ad").unwrap(), &Card::parse_list("Ah Kh").unwrap(), &[], None).err(), Some(StrengthError::INVALID_BOARD));
+	assert_eq!(hand_strength(&Card::parse_list("Ac Ad").unwrap(), &board, &Card::parse_list("Ac").unwrap(), None).err(), Some(StrengthError::REPEATING_CARDS));
+	assert_eq!(hand_strength(&Card::parse_list("Ac Ad").unwrap(), &board, &[], Some(&range("AA"))).err(), Some(StrengthError::EMPTY_RANGE));
+	assert_eq!(potential(&Card::parse_list("Ac Ad").unwrap(), &board, &[], None, 3).err(), Some(StrengthError::INVALID_LOOKAHEAD));
+}
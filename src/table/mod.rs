@@ -0,0 +1,505 @@
+use crate::eval::CardSet;
+use crate::hand::Card;
+use crate::pot;
+use crate::pot::Contribution;
+use crate::showdown::seat_order;
+use crate::showdown::Showdown;
+use crate::showdown::Variant;
+
+#[cfg(test)]
+mod tests;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BettingStructure {
+	NO_LIMIT,
+	POT_LIMIT,
+	FIXED_LIMIT
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Ord, PartialOrd)]
+pub enum Street {
+	PREFLOP,
+	FLOP,
+	TURN,
+	RIVER,
+	SHOWDOWN
+}
+
+// Bet and raise amounts are totals for the street ("raise to").
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Action {
+	FOLD,
+	CHECK,
+	CALL,
+	BET(u64),
+	RAISE(u64)
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Post {
+	ANTE,
+	SMALL_BLIND,
+	BIG_BLIND,
+	STRADDLE
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Eq, PartialEq)]
+pub enum Event {
+	POST(usize, Post, u64),
+	DEAL_HOLE(usize, Vec<Card>),
+	ACTION(usize, Action),
+	DEAL_BOARD(Street, Vec<Card>),
+	AWARD(usize, u64)
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TableError {
+	NOT_ENOUGH_PLAYERS,
+	DUPLICATE_SEAT,
+	NOT_ENOUGH_CARDS,
+	REPEATING_CARDS,
+	HAND_OVER,
+	NOT_YOUR_TURN,
+	ILLEGAL_ACTION,
+	INVALID_AMOUNT
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct TableConfig {
+	pub variant: Variant,
+	pub structure: BettingStructure,
+	pub small_blind: u64,
+	pub big_blind: u64,
+	pub ante: u64,
+	pub straddle: Option<u64>
+}
+
+#[derive(Clone)]
+pub struct PlayerState {
+	pub seat: usize,
+	pub stack: u64,
+	pub hole: Vec<Card>,
+	pub street_committed: u64,
+	pub total_committed: u64,
+	pub folded: bool,
+	needs_action: bool,
+	raise_allowed: bool
+}
+
+impl PlayerState {
+	pub fn is_all_in(&self) -> bool {
+		return self.stack == 0 && !self.folded;
+	}
+
+	fn can_act(&self) -> bool {
+		return !self.folded && self.stack > 0;
+	}
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct LegalActions {
+	pub fold: bool,
+	pub check: bool,
+	// chips added by calling
+	pub call: Option<u64>,
+	// lowest and highest "to" amounts
+	pub bet: Option<(u64, u64)>,
+	pub raise: Option<(u64, u64)>
+}
+
+#[derive(Clone)]
+pub struct Table {
+	pub config: TableConfig,
+	pub button: usize,
+	pub players: Vec<PlayerState>,
+	pub board: Vec<Card>,
+	pub street: Street,
+	pub log: Vec<Event>,
+	deck: Vec<Card>,
+	next_card: usize,
+	to_act: Option<usize>,
+	current_bet: u64,
+	last_raise: u64,
+	bets: u32
+}
+
+const FIXED_LIMIT_CAP: u32 = 4;
+
+impl Table {
+	// Players are given as (seat, stack); the deck is dealt from the front,
+	// so a fixed deck order makes the whole hand deterministic.
+	pub fn new(config: TableConfig, seats: &[(usize, u64)], button: usize, deck: Vec<Card>) -> Result<Table, TableError> {
+		if seats.len() < 2 {
+			return Err(TableError::NOT_ENOUGH_PLAYERS);
+		}
+
+		for (i, seat) in seats.iter().enumerate() {
+			if seats[..i].iter().any(|s| s.0 == seat.0) {
+				return Err(TableError::DUPLICATE_SEAT);
+			}
+		}
+
+		if deck.len() < seats.len() * config.variant.hole_cards() + 8 {
+			return Err(TableError::NOT_ENOUGH_CARDS);
+		}
+
+		if CardSet::from_cards(&deck).len() != deck.len() {
+			return Err(TableError::REPEATING_CARDS);
+		}
+
+		let mut ordered = seats.to_vec();
+		ordered.sort_by_key(|s| seat_order(s.0, button));
+
+		let mut table = Table {
+			config,
+			button,
+			players: ordered.iter().map(|(seat, stack)| PlayerState {
+				seat: *seat,
+				stack: *stack,
+				hole: Vec::new(),
+				street_committed: 0,
+				total_committed: 0,
+				folded: false,
+				needs_action: true,
+				raise_allowed: true
+			}).collect(),
+			board: Vec::new(),
+			street: Street::PREFLOP,
+			log: Vec::new(),
+			deck,
+			next_card: 0,
+			to_act: None,
+			current_bet: 0,
+			last_raise: config.big_blind,
+			bets: 1
+		};
+
+		let last_blind = table.post_blinds();
+		table.deal_hole_cards();
+
+		table.to_act = table.next_to_act_from((last_blind + 1) % table.players.len());
+		table.advance_if_complete();
+
+		return Ok(table);
+	}
+
+	pub fn replay(config: TableConfig, seats: &[(usize, u64)], button: usize, deck: Vec<Card>, actions: &[(usize, Action)]) -> Result<Table, TableError> {
+		let mut table = Table::new(config, seats, button, deck)?;
+		for (seat, action) in actions {
+			table.apply(*seat, *action)?;
+		}
+
+		return Ok(table);
+	}
+
+	pub fn actions(&self) -> Vec<(usize, Action)> {
+		return self.log.iter().filter_map(|e| match e {
+			Event::ACTION(seat, action) => Some((*seat, *action)),
+			_ => None
+		}).collect();
+	}
+
+	pub fn to_act(&self) -> Option<usize> {
+		return self.to_act.map(|i| self.players[i].seat);
+	}
+
+	pub fn is_over(&self) -> bool {
+		return self.street == Street::SHOWDOWN;
+	}
+
+	pub fn pot(&self) -> u64 {
+		return self.players.iter().map(|p| p.total_committed).sum();
+	}
+
+	pub fn player(&self, seat: usize) -> Option<&PlayerState> {
+		return self.players.iter().find(|p| p.seat == seat);
+	}
+
+	fn commit(&mut self, index: usize, amount: u64) -> u64 {
+		let player = &mut self.players[index];
+		let amount = amount.min(player.stack);
+		player.stack -= amount;
+		player.street_committed += amount;
+		player.total_committed += amount;
+
+		return amount;
+	}
+
+	// Returns the index of the last player to post a blind or straddle.
+	fn post_blinds(&mut self) -> usize {
+		if self.config.ante > 0 {
+			for i in 0..self.players.len() {
+				let amount = self.commit(i, self.config.ante);
+				self.players[i].street_committed = 0;
+				self.log.push(Event::POST(self.players[i].seat, Post::ANTE, amount));
+			}
+		}
+
+		let heads_up = self.players.len() == 2;
+		let (small, big) = if heads_up { (1, 0) } else { (0, 1) };
+
+		let mut blinds = vec![(small, Post::SMALL_BLIND, self.config.small_blind), (big, Post::BIG_BLIND, self.config.big_blind)];
+		if let Some(straddle) = self.config.straddle {
+			if !heads_up {
+				blinds.push((2, Post::STRADDLE, straddle));
+				self.last_raise = self.last_raise.max(straddle);
+				self.bets += 1;
+			}
+		}
+
+		let last = blinds.last().unwrap().0;
+		for (index, post, blind) in blinds {
+			let amount = self.commit(index, blind);
+			self.current_bet = self.current_bet.max(self.players[index].street_committed);
+			self.log.push(Event::POST(self.players[index].seat, post, amount));
+		}
+
+		return last;
+	}
+
+	fn draw(&mut self, count: usize) -> Vec<Card> {
+		let cards = self.deck[self.next_card..self.next_card + count].to_vec();
+		self.next_card += count;
+
+		return cards;
+	}
+
+	fn deal_hole_cards(&mut self) {
+		let count = self.config.variant.hole_cards();
+		for _ in 0..count {
+			for i in 0..self.players.len() {
+				let card = self.draw(1)[0];
+				self.players[i].hole.push(card);
+			}
+		}
+
+		for i in 0..self.players.len() {
+			self.log.push(Event::DEAL_HOLE(self.players[i].seat, self.players[i].hole.clone()));
+		}
+	}
+
+	fn next_to_act_from(&self, start: usize) -> Option<usize> {
+		for k in 0..self.players.len() {
+			let i = (start + k) % self.players.len();
+			if self.players[i].can_act() && self.players[i].needs_action {
+				return Some(i);
+			}
+		}
+
+		return None;
+	}
+
+	fn bet_size(&self) -> u64 {
+		if self.config.structure == BettingStructure::FIXED_LIMIT && self.street >= Street::TURN {
+			return self.config.big_blind * 2;
+		}
+
+		return self.config.big_blind;
+	}
+
+	pub fn legal_actions(&self) -> LegalActions {
+		let index = match self.to_act {
+			Some(index) => index,
+			None => return LegalActions::default()
+		};
+
+		let player = &self.players[index];
+		let to_call = self.current_bet - player.street_committed;
+		let all_in_to = player.street_committed + player.stack;
+
+		let mut legal = LegalActions {
+			fold: to_call > 0,
+			check: to_call == 0,
+			call: if to_call > 0 { Some(to_call.min(player.stack)) } else { None },
+			bet: None,
+			raise: None
+		};
+
+		let opponents_can_act = self.players.iter().enumerate().any(|(i, p)| i != index && p.can_act());
+		let capped = self.config.structure == BettingStructure::FIXED_LIMIT && self.bets >= FIXED_LIMIT_CAP;
+		if !player.raise_allowed || !opponents_can_act || capped || all_in_to <= self.current_bet {
+			return legal;
+		}
+
+		let min_to = if self.current_bet == 0 { self.bet_size() } else { self.current_bet + self.last_raise.max(self.bet_size()) };
+		let max_to = match self.config.structure {
+			BettingStructure::NO_LIMIT => all_in_to,
+			BettingStructure::POT_LIMIT => self.current_bet + self.pot() + to_call,
+			BettingStructure::FIXED_LIMIT => if self.current_bet == 0 { self.bet_size() } else { self.current_bet + self.bet_size() }
+		};
+		let range = (min_to.min(all_in_to), max_to.min(all_in_to));
+
+		if self.current_bet == 0 {
+			legal.bet = Some(range);
+		} else {
+			legal.raise = Some(range);
+		}
+
+		return legal;
+	}
+
+	pub fn apply(&mut self, seat: usize, action: Action) -> Result<(), TableError> {
+		let index = match self.to_act {
+			Some(index) => index,
+			None => return Err(TableError::HAND_OVER)
+		};
+
+		if self.players[index].seat != seat {
+			return Err(TableError::NOT_YOUR_TURN);
+		}
+
+		let legal = self.legal_actions();
+		match action {
+			Action::FOLD => {
+				if !legal.fold {
+					return Err(TableError::ILLEGAL_ACTION);
+				}
+
+				self.players[index].folded = true;
+			},
+			Action::CHECK => {
+				if !legal.check {
+					return Err(TableError::ILLEGAL_ACTION);
+				}
+			},
+			Action::CALL => {
+				match legal.call {
+					Some(amount) => { self.commit(index, amount); },
+					None => return Err(TableError::ILLEGAL_ACTION)
+				}
+			},
+			Action::BET(to) | Action::RAISE(to) => {
+				let range = match action {
+					Action::BET(_) => legal.bet,
+					_ => legal.raise
+				};
+
+				let (min_to, max_to) = match range {
+					Some(range) => range,
+					None => return Err(TableError::ILLEGAL_ACTION)
+				};
+
+				if to < min_to || to > max_to {
+					return Err(TableError::INVALID_AMOUNT);
+				}
+
+				let added = to - self.players[index].street_committed;
+				self.commit(index, added);
+
+				let raise_size = to - self.current_bet;
+				let full_raise = raise_size >= self.last_raise.max(self.bet_size()) || self.current_bet == 0;
+				if full_raise {
+					self.last_raise = raise_size;
+				}
+
+				for (i, player) in self.players.iter_mut().enumerate() {
+					if i != index && player.can_act() {
+						player.needs_action = true;
+						if full_raise {
+							player.raise_allowed = true;
+						}
+					}
+				}
+
+				self.current_bet = to;
+				self.bets += 1;
+			}
+		}
+
+		self.players[index].needs_action = false;
+		self.players[index].raise_allowed = false;
+		self.log.push(Event::ACTION(seat, action));
+
+		self.to_act = self.next_to_act_from(index + 1);
+		self.advance_if_complete();
+
+		return Ok(());
+	}
+
+	fn betting_complete(&self) -> bool {
+		return self.players.iter().all(|p| !p.can_act() || (!p.needs_action && p.street_committed == self.current_bet));
+	}
+
+	fn advance_if_complete(&mut self) {
+		let remaining = self.players.iter().filter(|p| !p.folded).count();
+		if remaining == 1 {
+			self.finish();
+			return;
+		}
+
+		if !self.betting_complete() {
+			return;
+		}
+
+		// with at most one player left to act the board is simply run out
+		let can_act = self.players.iter().filter(|p| p.can_act()).count();
+		if self.street == Street::RIVER || can_act <= 1 {
+			self.finish();
+			return;
+		}
+
+		self.next_street();
+		self.to_act = self.next_to_act_from(0);
+	}
+
+	fn next_street(&mut self) {
+		self.street = match self.street {
+			Street::PREFLOP => Street::FLOP,
+			Street::FLOP => Street::TURN,
+			_ => Street::RIVER
+		};
+
+		for player in self.players.iter_mut() {
+			player.street_committed = 0;
+			player.needs_action = true;
+			player.raise_allowed = true;
+		}
+
+		self.current_bet = 0;
+		self.last_raise = self.bet_size();
+		self.bets = 0;
+
+		self.draw(1);
+		let count = if self.street == Street::FLOP { 3 } else { 1 };
+		let cards = self.draw(count);
+		self.board.extend_from_slice(&cards);
+		self.log.push(Event::DEAL_BOARD(self.street, cards));
+	}
+
+	fn finish(&mut self) {
+		self.to_act = None;
+
+		let contributions: Vec<Contribution> = self.players.iter()
+			.map(|p| Contribution {seat: p.seat, amount: p.total_committed, folded: p.folded})
+			.collect();
+
+		let contested = self.players.iter().filter(|p| !p.folded).count() > 1;
+		while contested && self.board.len() < 5 {
+			self.next_street();
+		}
+
+		let showdown = Showdown {
+			variant: self.config.variant,
+			board: self.board.clone(),
+			button: self.button,
+			players: self.players.iter().filter(|p| !p.folded).map(|p| (p.seat, p.hole.clone())).collect()
+		};
+
+		let resolution = pot::settle(&contributions, &showdown).expect("Pots could not be settled!");
+		for i in 0..self.players.len() {
+			let total = resolution.total_for(self.players[i].seat);
+			if total > 0 {
+				self.players[i].stack += total;
+				self.log.push(Event::AWARD(self.players[i].seat, total));
+			}
+		}
+
+		self.street = Street::SHOWDOWN;
+	}
+}
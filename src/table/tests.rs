@@ -0,0 +1,203 @@
+use super::*;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+fn shuffled_deck(seed: u64) -> Vec<Card> {
+	let mut deck: Vec<Card> = CardSet::FULL_DECK.iter().collect();
+	deck.shuffle(&mut StdRng::seed_from_u64(seed));
+
+	return deck;
+}
+
+fn config(structure: BettingStructure) -> TableConfig {
+	return TableConfig {
+		variant: Variant::HOLDEM,
+		structure,
+		small_blind: 1,
+		big_blind: 2,
+		ante: 0,
+		straddle: None
+	};
+}
+
+fn total_chips(table: &Table) -> u64 {
+	return table.players.iter().map(|p| p.stack).sum::<u64>() + if table.is_over() { 0 } else { table.pot() };
+}
+
+#[test]
+fn check_heads_up_no_limit() {
+	let mut table = Table::new(config(BettingStructure::NO_LIMIT), &[(0, 100), (1, 100)], 0, shuffled_deck(1)).unwrap();
+
+	assert_eq!(table.to_act(), Some(0));
+	let legal = table.legal_actions();
+	assert_eq!(legal.fold, true);
+	assert_eq!(legal.call, Some(1));
+	assert_eq!(legal.raise, Some((4, 100)));
+
+	assert_eq!(table.apply(1, Action::CALL), Err(TableError::NOT_YOUR_TURN));
+	assert_eq!(table.apply(0, Action::RAISE(3)), Err(TableError::INVALID_AMOUNT));
+	table.apply(0, Action::RAISE(6)).unwrap();
+	table.apply(1, Action::CALL).unwrap();
+
+	assert_eq!(table.street, Street::FLOP);
+	assert_eq!(table.board.len(), 3);
+	assert_eq!(table.to_act(), Some(1));
+	assert_eq!(table.legal_actions().bet, Some((2, 94)));
+
+	table.apply(1, Action::CHECK).unwrap();
+	table.apply(0, Action::BET(10)).unwrap();
+	table.apply(1, Action::FOLD).unwrap();
+
+	assert_eq!(table.is_over(), true);
+	assert_eq!(table.player(0).unwrap().stack, 106);
+	assert_eq!(table.player(1).unwrap().stack, 94);
+	assert_eq!(table.apply(1, Action::CHECK), Err(TableError::HAND_OVER));
+}
+
+#[test]
+fn check_pot_limit() {
+	let mut table = Table::new(config(BettingStructure::POT_LIMIT), &[(0, 200), (1, 200), (2, 200)], 0, shuffled_deck(2)).unwrap();
+
+	assert_eq!(table.to_act(), Some(0));
+	assert_eq!(table.legal_actions().raise, Some((4, 7)));
+	table.apply(0, Action::RAISE(7)).unwrap();
+
+	assert_eq!(table.legal_actions().raise, Some((12, 23)));
+	table.apply(1, Action::CALL).unwrap();
+	table.apply(2, Action::CALL).unwrap();
+
+	assert_eq!(table.pot(), 21);
+	assert_eq!(table.to_act(), Some(1));
+	assert_eq!(table.legal_actions().bet, Some((2, 21)));
+}
+
+#[test]
+fn check_fixed_limit_cap() {
+	let mut table = Table::new(config(BettingStructure::FIXED_LIMIT), &[(0, 200), (1, 200), (2, 200)], 0, shuffled_deck(3)).unwrap();
+
+	assert_eq!(table.legal_actions().raise, Some((4, 4)));
+	table.apply(0, Action::RAISE(4)).unwrap();
+	table.apply(1, Action::RAISE(6)).unwrap();
+	table.apply(2, Action::RAISE(8)).unwrap();
+	assert_eq!(table.legal_actions().raise, None);
+	table.apply(0, Action::CALL).unwrap();
+	table.apply(1, Action::CALL).unwrap();
+
+	assert_eq!(table.street, Street::FLOP);
+	assert_eq!(table.legal_actions().bet, Some((2, 2)));
+	table.apply(1, Action::CHECK).unwrap();
+	table.apply(2, Action::CHECK).unwrap();
+	table.apply(0, Action::CHECK).unwrap();
+
+	assert_eq!(table.street, Street::TURN);
+	assert_eq!(table.legal_actions().bet, Some((4, 4)));
+}
+
+#[test]
+fn check_incomplete_raise_does_not_reopen() {
+	let mut table = Table::new(config(BettingStructure::NO_LIMIT), &[(0, 100), (1, 100), (2, 15)], 0, shuffled_deck(4)).unwrap();
+
+	table.apply(0, Action::CALL).unwrap();
+	table.apply(1, Action::CALL).unwrap();
+	table.apply(2, Action::CHECK).unwrap();
+
+	table.apply(1, Action::BET(10)).unwrap();
+	assert_eq!(table.legal_actions().raise, Some((13, 13)));
+	table.apply(2, Action::RAISE(13)).unwrap();
+	table.apply(0, Action::CALL).unwrap();
+
+	let legal = table.legal_actions();
+	assert_eq!(table.to_act(), Some(1));
+	assert_eq!(legal.call, Some(3));
+	assert_eq!(legal.raise, None);
+	table.apply(1, Action::CALL).unwrap();
+	assert_eq!(table.street, Street::TURN);
+}
+
+#[test]
+fn check_antes_and_straddle() {
+	let mut config = config(BettingStructure::NO_LIMIT);
+	config.ante = 1;
+	config.straddle = Some(4);
+	let mut table = Table::new(config, &[(1, 100), (3, 100), (5, 100), (7, 100)], 7, shuffled_deck(5)).unwrap();
+
+	assert_eq!(table.pot(), 4 + 1 + 2 + 4);
+	assert_eq!(table.to_act(), Some(7));
+	assert_eq!(table.legal_actions().raise, Some((8, 99)));
+	table.apply(7, Action::CALL).unwrap();
+	table.apply(1, Action::CALL).unwrap();
+	table.apply(3, Action::CALL).unwrap();
+
+	assert_eq!(table.to_act(), Some(5));
+	assert_eq!(table.legal_actions().check, true);
+	table.apply(5, Action::CHECK).unwrap();
+	assert_eq!(table.street, Street::FLOP);
+	assert_eq!(table.pot(), 20);
+}
+
+#[test]
+fn check_all_in_runs_out_board() {
+	let mut table = Table::new(config(BettingStructure::NO_LIMIT), &[(0, 100), (1, 50), (2, 30)], 0, shuffled_deck(6)).unwrap();
+
+	table.apply(0, Action::RAISE(100)).unwrap();
+	table.apply(1, Action::CALL).unwrap();
+	table.apply(2, Action::CALL).unwrap();
+
+	assert_eq!(table.is_over(), true);
+	assert_eq!(table.board.len(), 5);
+	assert_eq!(total_chips(&table), 180);
+	assert_eq!(table.log.iter().any(|e| matches!(e, Event::AWARD(_, _))), true);
+}
+
+#[test]
+fn check_omaha_deals_four_cards() {
+	let mut config = config(BettingStructure::POT_LIMIT);
+	config.variant = Variant::OMAHA;
+	let table = Table::new(config, &[(0, 100), (1, 100), (2, 100)], 2, shuffled_deck(7)).unwrap();
+
+	for player in &table.players {
+		assert_eq!(player.hole.len(), 4);
+	}
+}
+
+#[test]
+fn check_replay_is_deterministic() {
+	let seats = [(0, 100), (2, 80), (4, 120), (6, 60)];
+	let mut table = Table::new(config(BettingStructure::NO_LIMIT), &seats, 0, shuffled_deck(8)).unwrap();
+	let mut rng = StdRng::seed_from_u64(8);
+
+	while !table.is_over() {
+		let legal = table.legal_actions();
+		let seat = table.to_act().unwrap();
+		let mut options = Vec::new();
+		if legal.check { options.push(Action::CHECK); }
+		if legal.call.is_some() { options.push(Action::CALL); }
+		if let Some((min, _)) = legal.bet { options.push(Action::BET(min)); }
+		if let Some((_, max)) = legal.raise { options.push(Action::RAISE(max)); }
+		if legal.fold && options.len() < 3 { options.push(Action::FOLD); }
+
+		table.apply(seat, *options.choose(&mut rng).unwrap()).unwrap();
+	}
+
+	assert_eq!(total_chips(&table), 360);
+
+	let replayed = Table::replay(config(BettingStructure::NO_LIMIT), &seats, 0, shuffled_deck(8), &table.actions()).unwrap();
+	assert_eq!(replayed.log == table.log, true);
+	for (a, b) in replayed.players.iter().zip(table.players.iter()) {
+		assert_eq!(a.stack, b.stack);
+	}
+}
+
+#[test]
+fn check_invalid_tables() {
+	let deck = shuffled_deck(9);
+	assert_eq!(Table::new(config(BettingStructure::NO_LIMIT), &[(0, 100)], 0, deck.clone()).err(), Some(TableError::NOT_ENOUGH_PLAYERS));
+	assert_eq!(Table::new(config(BettingStructure::NO_LIMIT), &[(0, 100), (0, 100)], 0, deck.clone()).err(), Some(TableError::DUPLICATE_SEAT));
+	assert_eq!(Table::new(config(BettingStructure::NO_LIMIT), &[(0, 100), (1, 100)], 0, deck[..5].to_vec()).err(), Some(TableError::NOT_ENOUGH_CARDS));
+
+	let mut repeated = deck.clone();
+	repeated[1] = repeated[0];
+	assert_eq!(Table::new(config(BettingStructure::NO_LIMIT), &[(0, 100), (1, 100)], 0, repeated).err(), Some(TableError::REPEATING_CARDS));
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+fn args(s: &str) -> Vec<String> {
+	return s.split_whitespace().map(|a| a.to_string()).collect();
+}
+
+fn exit_code(s: &str) -> i32 {
+	return run(&args(s)).err().map_or(0, |e| e.exit_code());
+}
+
+fn output(s: &str) -> Value {
+	return run(&args(s)).ok().unwrap().json;
+}
+
+#[test]
+fn check_usage_errors() {
+	assert_eq!(exit_code(""), 2);
+	assert_eq!(exit_code("deal"), 2);
+	assert_eq!(exit_code("eval"), 2);
+	assert_eq!(exit_code("equity AA"), 2);
+	assert_eq!(exit_code("equity AA KK --runs 3"), 2);
+	assert_eq!(exit_code("range AA"), 2);
+	assert_eq!(exit_code("table build"), 2);
+	assert_eq!(exit_code("cfr"), 2);
+	assert_eq!(exit_code("river QcQd KsKd --pot 100 --stack 100"), 2);
+	assert_eq!(exit_code("pushfold --ante 0.1"), 2);
+	assert_eq!(exit_code("icm --stacks 100,200"), 2);
+	assert_eq!(exit_code("ev AhAd KK --pot 100"), 2);
+	assert_eq!(exit_code("bench --hands"), 2);
+	assert_eq!(exit_code("repl now"), 2);
+	assert_eq!(exit_code("help"), 0);
+}
+
+#[test]
+fn check_invalid_input() {
+	assert_eq!(exit_code("eval AhKhQhJh1h"), 1);
+	assert_eq!(exit_code("eval AhKhQhJhAh"), 1);
+	assert_eq!(exit_code("eval AhKhQh"), 1);
+	assert_eq!(exit_code("equity AA K♠K♦"), 1);
+	assert_eq!(exit_code("equity AA KK --trials 0"), 1);
+	assert_eq!(exit_code("range expand AK,A♠"), 1);
+	assert_eq!(exit_code("cfr chess"), 1);
+	assert_eq!(exit_code("cfr kuhn --variant dcfr"), 1);
+	assert_eq!(exit_code("river QcQd KsKd --board 2c5d9hJs1h --pot 100 --stack 100"), 1);
+	assert_eq!(exit_code("river QcQd KsKd --board 2c5d9hJsKh --pot 100 --stack 100 --sizes 0,1"), 1);
+	assert_eq!(exit_code("pushfold --stack ten"), 1);
+	assert_eq!(exit_code("pushfold --stack 1"), 1);
+	assert_eq!(exit_code("icm --stacks 100,x --payouts 50,30"), 1);
+	assert_eq!(exit_code("ev AhAdKc KK --pot 100 --call 50"), 1);
+}
+
+#[test]
+fn check_io_errors() {
+	// the path is checked before the table is generated
+	assert_eq!(exit_code("table build --out /nonexistent/dir/table.txt"), 3);
+}
+
+#[test]
+fn check_json() {
+	let eval = output("eval Ah Kh Qh Jh Th 2c");
+	assert_eq!(eval["category"], "straight flush");
+	assert_eq!(eval["strength"], 1);
+	assert_eq!(eval["best_hand"], json!(["Ah", "Kh", "Qh", "Jh", "Th"]));
+
+	let equity = output("equity AhAd KhKd --board 2c3c4c5d");
+	assert_eq!(equity["exact"], true);
+	assert_eq!(equity["deals"], 44);
+	assert_eq!(equity["board"], json!(["2c", "3c", "4c", "5d"]));
+	assert_eq!(equity["players"][1]["hand"], "KhKd");
+	assert_eq!(equity["players"][1]["combos"], 1);
+	assert_eq!(equity["players"][0]["equity"].as_f64().unwrap() > 0.9, true);
+
+	let range = output("range expand TT+,AKs");
+	assert_eq!(range["range"], "AA,KK,QQ,JJ,TT,AKs");
+	assert_eq!(range["combos"], 34);
+	assert_eq!(range["classes"][5]["class"], "AKs");
+	assert_eq!(range["classes"][5]["combos"].as_array().unwrap().len(), 4);
+
+	let cfr = output("cfr kuhn --iterations 200 --every 100");
+	assert_eq!(cfr["game"], "kuhn");
+	assert_eq!(cfr["variant"], "cfr+");
+	assert_eq!(cfr["exploitability"][1]["iteration"], 200);
+	assert_eq!((cfr["game_value"].as_f64().unwrap() + 1.0 / 18.0).abs() < 0.01, true);
+	assert_eq!(cfr["path"], Value::Null);
+
+	let river = output("river QcQd KsKd,4s3s --board 2c5d9hJsKh --pot 100 --stack 100 --sizes 1 --iterations 100");
+	assert_eq!(river["iterations"], 100);
+	assert_eq!(river["players"][0]["line"], "");
+	assert_eq!(river["players"][0]["combos"].as_array().unwrap().len(), 1);
+	assert_eq!(river["players"][1]["combos"].as_array().unwrap().len(), 2);
+	assert_eq!((river["ev"][0].as_f64().unwrap() + river["ev"][1].as_f64().unwrap() - 100.0).abs() < 1e-6, true);
+
+	let pushfold = output("pushfold --stack 10 --iterations 200");
+	assert_eq!(pushfold["stack"], 10.0);
+	assert_eq!(pushfold["ante"], 0.0);
+	assert_eq!(pushfold["push"].as_str().unwrap().starts_with("AA,"), true);
+	assert_eq!(pushfold["push_share"].as_f64().unwrap() > pushfold["call_share"].as_f64().unwrap(), true);
+
+	let icm = output("icm --stacks 5000,3000,2000 --payouts 50,30,20");
+	assert_eq!(icm["exact"], true);
+	assert_eq!(icm["stacks"], json!([5000.0, 3000.0, 2000.0]));
+	let equities: Vec<f64> = icm["equities"].as_array().unwrap().iter().map(|e| e.as_f64().unwrap()).collect();
+	assert_eq!((equities.iter().sum::<f64>() - 100.0).abs() < 1e-9, true);
+	assert_eq!(equities[0] > equities[1] && equities[1] > equities[2], true);
+
+	let ev = output("ev AhAd KhKd,QhQd --pot 100 --call 50 --board 2c7d9s");
+	assert_eq!(ev["board"], json!(["2c", "7d", "9s"]));
+	assert_eq!(ev["pot"], 100.0);
+	assert_eq!(ev["to_call"], 50.0);
+	assert_eq!(ev["should_call"], true);
+	assert_eq!(ev["combos"].as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,223 @@
+use crate::eval;
+use crate::eval::CardSet;
+use crate::eval::HandValue;
+use crate::hand::Card;
+use crate::hand::HandRank;
+use crate::hand::Rank;
+use crate::hand::Suit;
+
+#[cfg(test)]
+mod tests;
+
+// Boards where at least this share of the next cards changes the category of
+// the nuts count as dynamic
+const DYNAMIC_SHARE: f64 = 0.25;
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum TextureError {
+	// a texture needs 3 to 5 board cards
+	INVALID_BOARD,
+	REPEATING_CARDS
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum SuitPattern {
+	RAINBOW,
+	TWO_TONE,
+	MONOTONE
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Pairing {
+	UNPAIRED,
+	PAIRED,
+	TWO_PAIR,
+	TRIPS,
+	FULL_HOUSE,
+	QUADS
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Height {
+	ACE_HIGH,
+	// king to ten high
+	BROADWAY,
+	// nine to seven high
+	MIDDLE,
+	// six high and lower
+	LOW
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Connectedness {
+	// no two hole card ranks make a straight
+	DISCONNECTED,
+	SEMI_CONNECTED,
+	CONNECTED
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Texture {
+	pub suits: SuitPattern,
+	pub pairing: Pairing,
+	pub high_rank: Rank,
+	pub height: Height,
+	pub connectedness: Connectedness,
+	// distinct pairs of hole card ranks that make a straight
+	pub straight_combos: usize,
+	pub flush_possible: bool,
+	pub straight_possible: bool,
+	// high card of the best straight two hole cards can make
+	pub nut_straight: Option<Rank>,
+	pub nuts: HandValue,
+	// categories some two hole cards make on this board, best first
+	pub possible: Vec<HandRank>,
+	// share of the next cards that change the category of the nuts
+	pub nut_change: f64,
+	pub dynamic: bool
+}
+
+fn rank_at(index: u8) -> Rank {
+	return Rank::iterator().as_slice()[index as usize];
+}
+
+// Best value two unseen cards make with the board, with every category seen
+fn holdings(board: CardSet) -> (HandValue, Vec<HandRank>) {
+	let deck: Vec<Card> = CardSet::FULL_DECK.difference(board).iter().collect();
+	let mut nuts = HandValue(0);
+	let mut categories: Vec<HandRank> = Vec::new();
+
+	for a in 0..deck.len() {
+		for b in a+1..deck.len() {
+			let mut cards = board;
+			cards.insert(deck[a]);
+			cards.insert(deck[b]);
+
+			let value = eval::evaluate(cards);
+			nuts = nuts.max(value);
+			if !categories.contains(&value.category()) {
+				categories.push(value.category());
+			}
+		}
+	}
+
+	categories.sort();
+	return (nuts, categories);
+}
+
+pub fn analyze(board: &[Card]) -> Result<Texture, TextureError> {
+	if board.len() < 3 || board.len() > 5 {
+		return Err(TextureError::INVALID_BOARD);
+	}
+
+	let board_set = CardSet::from_cards(board);
+	if board_set.len() != board.len() {
+		return Err(TextureError::REPEATING_CARDS);
+	}
+
+	let most_suited = Suit::iterator().map(|s| board.iter().filter(|c| c.suit == *s).count()).max().unwrap();
+	let suits = if most_suited == board.len() {
+		SuitPattern::MONOTONE
+	} else if most_suited == 1 {
+		SuitPattern::RAINBOW
+	} else {
+		SuitPattern::TWO_TONE
+	};
+
+	let mut counts: Vec<usize> = Rank::iterator().map(|r| board.iter().filter(|c| c.rank == *r).count()).filter(|n| *n > 1).collect();
+	counts.sort();
+	let pairing = match counts.as_slice() {
+		[] => Pairing::UNPAIRED,
+		[2] => Pairing::PAIRED,
+		[2, 2] => Pairing::TWO_PAIR,
+		[3] => Pairing::TRIPS,
+		[4] => Pairing::QUADS,
+		_ => Pairing::FULL_HOUSE
+	};
+
+	let high_rank = board.iter().map(|c| c.rank).max().unwrap();
+	let height = match high_rank {
+		Rank::ACE => Height::ACE_HIGH,
+		Rank::KING | Rank::QUEEN | Rank::JACK | Rank::TEN => Height::BROADWAY,
+		Rank::NINE | Rank::EIGHT | Rank::SEVEN => Height::MIDDLE,
+		_ => Height::LOW
+	};
+
+	let mask = board_set.rank_mask();
+	let mut straight_combos = 0;
+	let mut nut_straight = None;
+	for a in 0..13 {
+		for b in a..13 {
+			if let Some(high) = eval::straight_high(mask | (1u16 << a) | (1u16 << b)) {
+				// a pair only counts once it adds a rank the board lacks
+				if a != b {
+					straight_combos += 1;
+				}
+				nut_straight = nut_straight.max(Some(high));
+			}
+		}
+	}
+
+	let connectedness = match straight_combos {
+		0 => Connectedness::DISCONNECTED,
+		1..=2 => Connectedness::SEMI_CONNECTED,
+		_ => Connectedness::CONNECTED
+	};
+
+	let (nuts, possible) = holdings(board_set);
+	let mut nut_change = 0.0;
+	if board.len() < 5 {
+		let unseen: Vec<Card> = CardSet::FULL_DECK.difference(board_set).iter().collect();
+		let changed = unseen.iter().filter(|c| {
+			let mut next = board_set;
+			next.insert(**c);
+			holdings(next).0.category() != nuts.category()
+		}).count();
+
+		nut_change = changed as f64 / unseen.len() as f64;
+	}
+
+	return Ok(Texture {
+		suits,
+		pairing,
+		high_rank,
+		height,
+		connectedness,
+		straight_combos,
+		flush_possible: most_suited >= 3,
+		straight_possible: nut_straight.is_some(),
+		nut_straight: nut_straight.map(rank_at),
+		nuts,
+		possible,
+		nut_change,
+		dynamic: nut_change >= DYNAMIC_SHARE
+	});
+}
+
+impl Texture {
+	// Short notes such as "flush possible" or "nut straight is 9-high"
+	pub fn describe(&self) -> Vec<String> {
+		let mut notes = vec![
+			format!("{:?}", self.suits).replace('_', "-").to_lowercase(),
+			format!("{:?}", self.pairing).replace('_', " ").to_lowercase(),
+			format!("{}-high", self.high_rank.to_string()),
+			format!("{:?}", self.connectedness).replace('_', "-").to_lowercase()
+		];
+
+		if self.flush_possible {
+			notes.push("flush possible".to_string());
+		}
+
+		if let Some(rank) = self.nut_straight {
+			notes.push(format!("nut straight is {}-high", rank.to_string()));
+		}
+
+		notes.push(if self.dynamic { "dynamic".to_string() } else { "static".to_string() });
+		return notes;
+	}
+}
@@ -0,0 +1,65 @@
+use super::*;
+
+#[test]
+fn check_wet_board() {
+	let texture = analyze(&Card::parse_list("Jh Th 9c").unwrap()).unwrap();
+	assert_eq!(texture.suits, SuitPattern::TWO_TONE);
+	assert_eq!(texture.pairing, Pairing::UNPAIRED);
+	assert_eq!(texture.high_rank, Rank::JACK);
+	assert_eq!(texture.height, Height::BROADWAY);
+	assert_eq!(texture.connectedness, Connectedness::CONNECTED);
+	assert_eq!(texture.straight_combos, 3);
+	assert_eq!(texture.flush_possible, false);
+	assert_eq!(texture.nut_straight, Some(Rank::KING));
+	assert_eq!(texture.nuts.category(), HandRank::STRAIGHT);
+	assert_eq!(texture.possible, vec![HandRank::STRAIGHT, HandRank::THREE_OF_A_KIND, HandRank::TWO_PAIRS, HandRank::PAIR, HandRank::HIGH_CARD]);
+	assert_eq!(texture.dynamic, true);
+	assert_eq!(texture.describe(), vec!["two-tone", "unpaired", "J-high", "connected", "nut straight is K-high", "dynamic"]);
+}
+
+#[test]
+fn check_dry_board() {
+	let texture = analyze(&Card::parse_list("Kd 7s 2c").unwrap()).unwrap();
+	assert_eq!(texture.suits, SuitPattern::RAINBOW);
+	assert_eq!(texture.connectedness, Connectedness::DISCONNECTED);
+	assert_eq!(texture.straight_possible, false);
+	assert_eq!(texture.nuts.category(), HandRank::THREE_OF_A_KIND);
+	assert_eq!(texture.nut_change, 9.0 / 49.0);
+	assert_eq!(texture.dynamic, false);
+	assert_eq!(texture.describe(), vec!["rainbow", "unpaired", "K-high", "disconnected", "static"]);
+
+	let texture = analyze(&Card::parse_list("Ah 7h 2h").unwrap()).unwrap();
+	assert_eq!(texture.suits, SuitPattern::MONOTONE);
+	assert_eq!(texture.height, Height::ACE_HIGH);
+	assert_eq!(texture.flush_possible, true);
+	assert_eq!(texture.nuts.category(), HandRank::FLUSH);
+	assert_eq!(texture.possible.contains(&HandRank::STRAIGHT), false);
+}
+
+#[test]
+fn check_turn_and_river() {
+	let texture = analyze(&Card::parse_list("7c 8c 9c Tc").unwrap()).unwrap();
+	assert_eq!(texture.suits, SuitPattern::MONOTONE);
+	assert_eq!(texture.height, Height::BROADWAY);
+	assert_eq!(texture.nut_straight, Some(Rank::QUEEN));
+	assert_eq!(texture.nuts.category(), HandRank::STRAIGHT_FLUSH);
+
+	let texture = analyze(&Card::parse_list("9s 9d 4c 4h 2s").unwrap()).unwrap();
+	assert_eq!(texture.pairing, Pairing::TWO_PAIR);
+	assert_eq!(texture.height, Height::MIDDLE);
+	assert_eq!(texture.nut_change, 0.0);
+	assert_eq!(texture.dynamic, false);
+	assert_eq!(texture.nuts.category(), HandRank::FOUR_OF_A_KIND);
+
+	assert_eq!(analyze(&Card::parse_list("Qs Qd Qh").unwrap()).unwrap().pairing, Pairing::TRIPS);
+	assert_eq!(analyze(&Card::parse_list("Qs Qd Qh 5c").unwrap()).unwrap().height, Height::BROADWAY);
+	assert_eq!(analyze(&Card::parse_list("Qs Qd Qh 5c 5d").unwrap()).unwrap().pairing, Pairing::FULL_HOUSE);
+	assert_eq!(analyze(&Card::parse_list("6s 5d 3h").unwrap()).unwrap().height, Height::LOW);
+}
+
+#[test]
+fn check_errors() {
+	assert_eq!(analyze(&Card::parse_list("Qs Qd").unwrap()).err(), Some(TextureError::INVALID_BOARD));
+	assert_eq!(analyze(&Card::parse_list("Qs Qd 2c 3c 4c 5c").unwrap()).err(), Some(TextureError::INVALID_BOARD));
+	assert_eq!(analyze(&Card::parse_list("Qs Qs 2c").unwrap()).err(), Some(TextureError::REPEATING_CARDS));
+}